@@ -0,0 +1,353 @@
+/* envflag-derive/src/lib.rs */
+
+//! Derive and schema macros for the [`envflag`](https://docs.rs/envflag)
+//! crate.
+//!
+//! Use through `envflag` with the `derive` feature enabled rather than
+//! depending on this crate directly.
+
+mod schema;
+mod test;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+	Data, DeriveInput, Field, Fields, GenericArgument, LitStr, PathArguments, Type, parse_macro_input,
+};
+
+/// Derives `envflag::EnvConfig`, loading each field from the store.
+///
+/// Every field maps to the upper-cased field name (`database_url` →
+/// `DATABASE_URL`). Field shapes:
+///
+/// - `T` — required; a missing variable is an error.
+/// - `Option<T>` — `None` when the variable is missing.
+/// - `Vec<T>` — a comma-separated value, or `NAME_0`, `NAME_1`, … when the
+///   variable itself is not set.
+///
+/// Field attributes:
+///
+/// - `#[env(name = "KEY")]` — use a different variable name.
+/// - `#[env(default = "value")]` — parsed when the variable is missing.
+/// - `#[env(prefix = "DB_")]` — load a nested `EnvConfig` struct from the
+///   variables under `DB_`.
+/// - `#[env(nested)]` — load a nested `EnvConfig` struct without an extra
+///   prefix.
+///
+/// The derive also generates `schema()`, describing every variable with its
+/// type, default, requiredness, and the field's doc comment.
+#[proc_macro_derive(EnvConfig, attributes(env))]
+pub fn derive_env_config(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	expand(&input)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
+
+/// Builds an `envflag::schema::Schema` from a schema file, checked at
+/// compile time.
+///
+/// The path is relative to the crate's `Cargo.toml`. Malformed TOML,
+/// duplicate keys, unknown fields, and defaults that do not parse as their
+/// primitive type are compile errors, as are type and validator names that
+/// do not exist.
+///
+/// ```rust,ignore
+/// let schema = envflag::static_schema!("schema.toml");
+/// ```
+#[proc_macro]
+pub fn static_schema(input: TokenStream) -> TokenStream {
+	let path = parse_macro_input!(input as LitStr);
+	schema::expand(&path)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
+
+/// Runs a test with its own configuration instead of the global one.
+///
+/// `vars(NAME = "value", ...)` lists the variables the test sees; every
+/// global query in the body (`envflag::get`, `envflag::key`, …) reads an
+/// isolated store holding exactly those, through `envflag::with_store`.
+/// Tests using it run in parallel without calling `init()`.
+///
+/// ```rust,ignore
+/// #[envflag::test(vars(PORT = "8080", DEBUG = "true"))]
+/// fn reads_port() {
+///     assert_eq!(envflag::get("PORT", 0u16), 8080);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn test(attr: TokenStream, item: TokenStream) -> TokenStream {
+	let mut args = test::Args::default();
+	let parser = syn::meta::parser(|meta| args.parse(&meta));
+	parse_macro_input!(attr with parser);
+	let item = parse_macro_input!(item as syn::ItemFn);
+	test::expand(&args, item)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
+
+/// Options collected from a field's `#[env(...)]` attributes.
+#[derive(Default)]
+struct FieldAttrs {
+	name: Option<LitStr>,
+	default: Option<LitStr>,
+	prefix: Option<LitStr>,
+	nested: bool,
+}
+
+impl FieldAttrs {
+	/// Returns the variable name for the field.
+	fn key(&self, field: &Field) -> String {
+		self.name.as_ref().map_or_else(
+			|| {
+				let ident = field.ident.as_ref().expect("named field").to_string();
+				ident.trim_start_matches("r#").to_uppercase()
+			},
+			LitStr::value,
+		)
+	}
+
+	/// Returns the extra prefix if the field is a nested struct.
+	fn nested_prefix(&self, field: &Field) -> syn::Result<Option<String>> {
+		if !self.nested && self.prefix.is_none() {
+			return Ok(None);
+		}
+		if self.default.is_some() || self.name.is_some() {
+			return Err(syn::Error::new_spanned(
+				field,
+				"nested fields do not accept `name` or `default`",
+			));
+		}
+		Ok(Some(
+			self.prefix.as_ref().map_or_else(String::new, LitStr::value),
+		))
+	}
+
+	fn parse(field: &Field) -> syn::Result<Self> {
+		let mut attrs = Self::default();
+		for attr in field.attrs.iter().filter(|a| a.path().is_ident("env")) {
+			attr.parse_nested_meta(|meta| {
+				if meta.path.is_ident("name") {
+					attrs.name = Some(meta.value()?.parse()?);
+				} else if meta.path.is_ident("default") {
+					attrs.default = Some(meta.value()?.parse()?);
+				} else if meta.path.is_ident("prefix") {
+					attrs.prefix = Some(meta.value()?.parse()?);
+				} else if meta.path.is_ident("nested") {
+					attrs.nested = true;
+				} else {
+					return Err(meta.error("unknown envflag attribute"));
+				}
+				Ok(())
+			})?;
+		}
+		Ok(attrs)
+	}
+}
+
+/// The container shape of a field type.
+enum Shape<'a> {
+	Plain,
+	Option(&'a Type),
+	Vec(&'a Type),
+}
+
+impl<'a> Shape<'a> {
+	fn of(ty: &'a Type) -> Self {
+		let Type::Path(path) = ty else {
+			return Self::Plain;
+		};
+		let Some(segment) = path.path.segments.last() else {
+			return Self::Plain;
+		};
+		let PathArguments::AngleBracketed(args) = &segment.arguments else {
+			return Self::Plain;
+		};
+		let Some(GenericArgument::Type(inner)) = args.args.first() else {
+			return Self::Plain;
+		};
+		if segment.ident == "Option" {
+			Self::Option(inner)
+		} else if segment.ident == "Vec" {
+			Self::Vec(inner)
+		} else {
+			Self::Plain
+		}
+	}
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+	let Data::Struct(data) = &input.data else {
+		return Err(syn::Error::new_spanned(
+			input,
+			"EnvConfig can only be derived for structs",
+		));
+	};
+	let Fields::Named(fields) = &data.fields else {
+		return Err(syn::Error::new_spanned(
+			input,
+			"EnvConfig requires a struct with named fields",
+		));
+	};
+
+	let inits = fields
+		.named
+		.iter()
+		.map(field_init)
+		.collect::<syn::Result<Vec<_>>>()?;
+	let specs = fields
+		.named
+		.iter()
+		.map(field_spec)
+		.collect::<syn::Result<Vec<_>>>()?;
+
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	Ok(quote! {
+		impl #impl_generics ::envflag::EnvConfig for #ident #ty_generics #where_clause {
+			fn from_store_prefixed(
+				store: &::envflag::store::EnvStore,
+				prefix: &str,
+			) -> ::std::result::Result<Self, ::envflag::EnvflagError> {
+				::std::result::Result::Ok(Self { #(#inits)* })
+			}
+
+			fn schema() -> ::envflag::schema::Schema {
+				let schema = ::envflag::schema::Schema::new();
+				#(let schema = #specs;)*
+				schema
+			}
+		}
+
+		impl #impl_generics #ident #ty_generics #where_clause {
+			/// Loads this configuration from the global store.
+			///
+			/// # Errors
+			///
+			/// Returns the first error encountered while loading a field.
+			pub fn from_env() -> ::std::result::Result<Self, ::envflag::EnvflagError> {
+				<Self as ::envflag::EnvConfig>::from_env()
+			}
+
+			/// Loads this configuration from the given store.
+			///
+			/// # Errors
+			///
+			/// Returns the first error encountered while loading a field.
+			pub fn from_store(
+				store: &::envflag::store::EnvStore,
+			) -> ::std::result::Result<Self, ::envflag::EnvflagError> {
+				<Self as ::envflag::EnvConfig>::from_store(store)
+			}
+
+			/// Returns the schema describing every variable this configuration
+			/// reads.
+			#[must_use]
+			pub fn schema() -> ::envflag::schema::Schema {
+				<Self as ::envflag::EnvConfig>::schema()
+			}
+		}
+	})
+}
+
+/// Generates the `field: expr,` initializer for one field.
+fn field_init(field: &Field) -> syn::Result<TokenStream2> {
+	let attrs = FieldAttrs::parse(field)?;
+	let ident = field.ident.as_ref().expect("named field");
+	let ty = &field.ty;
+	let key = attrs.key(field);
+
+	if let Some(nested_prefix) = attrs.nested_prefix(field)? {
+		return Ok(quote! {
+			#ident: <#ty as ::envflag::EnvConfig>::from_store_prefixed(
+				store,
+				&::std::format!("{}{}", prefix, #nested_prefix),
+			)?,
+		});
+	}
+
+	let name = quote! { &::std::format!("{}{}", prefix, #key) };
+	let expr = match (Shape::of(ty), &attrs.default) {
+		(Shape::Plain, Some(default)) => {
+			quote! { ::envflag::__private::field_or::<#ty>(store, #name, #default)? }
+		}
+		(Shape::Plain, None) => quote! { ::envflag::__private::field::<#ty>(store, #name)? },
+		(Shape::Option(inner), None) => {
+			quote! { ::envflag::__private::optional_field::<#inner>(store, #name)? }
+		}
+		(Shape::Vec(inner), None) => {
+			quote! { ::envflag::__private::list_field::<#inner>(store, #name)? }
+		}
+		(Shape::Option(_) | Shape::Vec(_), Some(_)) => {
+			return Err(syn::Error::new_spanned(
+				field,
+				"`default` is not supported on `Option` or `Vec` fields",
+			));
+		}
+	};
+
+	Ok(quote! { #ident: #expr, })
+}
+
+/// Generates the expression that adds one field to `schema`.
+fn field_spec(field: &Field) -> syn::Result<TokenStream2> {
+	let attrs = FieldAttrs::parse(field)?;
+	let ty = &field.ty;
+
+	if let Some(nested_prefix) = attrs.nested_prefix(field)? {
+		return Ok(quote! {
+			schema.nest(#nested_prefix, <#ty as ::envflag::EnvConfig>::schema())
+		});
+	}
+
+	let key = attrs.key(field);
+	let type_name = match Shape::of(ty) {
+		Shape::Option(inner) => type_name(inner),
+		Shape::Plain | Shape::Vec(_) => type_name(ty),
+	};
+	let mut spec = quote! { ::envflag::schema::KeySpec::new(#key).type_name(#type_name) };
+	if let Some(default) = &attrs.default {
+		spec = quote! { #spec.default(#default) };
+	} else if matches!(Shape::of(ty), Shape::Plain) {
+		spec = quote! { #spec.required() };
+	}
+
+	if let Some(description) = doc_comment(field) {
+		spec = quote! { #spec.description(#description) };
+	}
+	Ok(quote! { schema.key(#spec) })
+}
+
+/// Renders a type as compact source text, e.g. `Vec<String>`.
+fn type_name(ty: &Type) -> String {
+	quote!(#ty).to_string().replace(' ', "")
+}
+
+/// Joins a field's `///` doc comment lines into one description.
+fn doc_comment(field: &Field) -> Option<String> {
+	let lines: Vec<String> = field
+		.attrs
+		.iter()
+		.filter(|a| a.path().is_ident("doc"))
+		.filter_map(|a| match &a.meta {
+			syn::Meta::NameValue(nv) => match &nv.value {
+				syn::Expr::Lit(syn::ExprLit {
+					lit: syn::Lit::Str(s),
+					..
+				}) => Some(s.value().trim().to_owned()),
+				_ => None,
+			},
+			_ => None,
+		})
+		.filter(|line| !line.is_empty())
+		.collect();
+
+	if lines.is_empty() {
+		None
+	} else {
+		Some(lines.join(" "))
+	}
+}
@@ -0,0 +1,235 @@
+/* envflag-derive/src/schema.rs */
+
+//! `static_schema!`: a schema file checked at compile time.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{LitStr, Type};
+
+/// Fields accepted in a `[[key]]` table.
+const FIELDS: &[&str] = &[
+	"name",
+	"type",
+	"default",
+	"description",
+	"required",
+	"validate",
+];
+
+/// One `[[key]]` table, checked.
+struct Key {
+	name: String,
+	ty: Option<String>,
+	default: Option<String>,
+	description: Option<String>,
+	required: bool,
+	validate: Vec<String>,
+}
+
+pub(crate) fn expand(path: &LitStr) -> syn::Result<TokenStream2> {
+	let err = |message: String| syn::Error::new(path.span(), message);
+
+	let dir = std::env::var_os("CARGO_MANIFEST_DIR")
+		.ok_or_else(|| err("CARGO_MANIFEST_DIR is not set".to_owned()))?;
+	let full: PathBuf = PathBuf::from(dir).join(path.value());
+	let text = std::fs::read_to_string(&full)
+		.map_err(|e| err(format!("cannot read {}: {e}", full.display())))?;
+	let keys = parse(&text).map_err(|m| err(format!("{}: {m}", path.value())))?;
+
+	let full = full.to_string_lossy().into_owned();
+	let mut checks = Vec::new();
+	let mut specs = Vec::new();
+	for key in &keys {
+		let name = &key.name;
+		let mut spec = quote! { ::envflag::schema::KeySpec::new(#name) };
+		if let Some(ty) = &key.ty {
+			// Let the compiler reject unknown types, pointing at the macro.
+			let parsed: Type =
+				syn::parse_str(ty).map_err(|_| err(format!("key '{name}': '{ty}' is not a Rust type")))?;
+			checks
+				.push(quote! { let _: ::core::option::Option<#parsed> = ::core::option::Option::None; });
+			spec = quote! { #spec.type_name(#ty) };
+		}
+		if let Some(default) = &key.default {
+			spec = quote! { #spec.default(#default) };
+		}
+		if key.required {
+			spec = quote! { #spec.required() };
+		}
+		if let Some(description) = &key.description {
+			spec = quote! { #spec.description(#description) };
+		}
+		for v in &key.validate {
+			let ident = format_ident!("{}", v, span = path.span());
+			checks.push(quote! { let _: fn(&str) -> bool = ::envflag::validators::#ident; });
+			spec = quote! { #spec.validator(#v) };
+		}
+		specs.push(spec);
+	}
+
+	Ok(quote! {{
+		// Rebuild when the schema file changes.
+		const _: &[u8] = ::core::include_bytes!(#full);
+		#[allow(dead_code)]
+		fn __envflag_static_schema_check() {
+			#(#checks)*
+		}
+		::envflag::schema::Schema::new() #(.key(#specs))*
+	}})
+}
+
+/// Parses and checks a schema document.
+fn parse(text: &str) -> Result<Vec<Key>, String> {
+	let doc: toml::Table = text
+		.parse()
+		.map_err(|e: toml::de::Error| e.message().to_owned())?;
+	let mut keys = Vec::new();
+	let mut seen = HashSet::new();
+	for (field, value) in &doc {
+		if field != "key" {
+			return Err(format!("unknown top-level field '{field}'"));
+		}
+		let tables = value
+			.as_array()
+			.ok_or("`key` must be an array of tables ([[key]])")?;
+		for (i, table) in tables.iter().enumerate() {
+			let table = table
+				.as_table()
+				.ok_or_else(|| format!("key #{} is not a table", i + 1))?;
+			let key = parse_key(table).map_err(|m| format!("key #{}: {m}", i + 1))?;
+			if !seen.insert(key.name.clone()) {
+				return Err(format!("duplicate key '{}'", key.name));
+			}
+			keys.push(key);
+		}
+	}
+	Ok(keys)
+}
+
+fn parse_key(table: &toml::Table) -> Result<Key, String> {
+	if let Some(field) = table.keys().find(|k| !FIELDS.contains(&k.as_str())) {
+		return Err(format!("unknown field '{field}'"));
+	}
+	let string = |field: &str| match table.get(field) {
+		None => Ok(None),
+		Some(toml::Value::String(s)) => Ok(Some(s.clone())),
+		Some(_) => Err(format!("`{field}` must be a string")),
+	};
+
+	let name = string("name")?.ok_or("missing `name`")?;
+	if name.is_empty() {
+		return Err("`name` must not be empty".to_owned());
+	}
+	let required = match table.get("required") {
+		None => false,
+		Some(toml::Value::Boolean(b)) => *b,
+		Some(_) => return Err("`required` must be a boolean".to_owned()),
+	};
+	let validate = match table.get("validate") {
+		None => Vec::new(),
+		Some(toml::Value::Array(list)) => list
+			.iter()
+			.map(|v| {
+				v.as_str()
+					.map(str::to_owned)
+					.ok_or("`validate` entries must be strings")
+			})
+			.collect::<Result<_, _>>()?,
+		Some(_) => return Err("`validate` must be an array".to_owned()),
+	};
+	if let Some(v) = validate
+		.iter()
+		.find(|v| syn::parse_str::<syn::Ident>(v).is_err())
+	{
+		return Err(format!("'{v}' is not a validator name"));
+	}
+
+	let key = Key {
+		ty: string("type")?,
+		default: string("default")?,
+		description: string("description")?,
+		required,
+		validate,
+		name,
+	};
+	if let (Some(ty), Some(default)) = (&key.ty, &key.default)
+		&& !parses_as(ty, default)
+	{
+		return Err(format!(
+			"default '{default}' of '{}' is not a valid {ty}",
+			key.name
+		));
+	}
+	Ok(key)
+}
+
+/// Returns `false` if `value` is known not to parse as the primitive
+/// `type_name`. Mirrors the runtime check in `KeySpec::check`.
+fn parses_as(type_name: &str, value: &str) -> bool {
+	let v = value.trim();
+	match type_name {
+		"bool" => matches!(
+			v.to_lowercase().as_str(),
+			"true" | "1" | "yes" | "false" | "0" | "no"
+		),
+		"char" => v.parse::<char>().is_ok(),
+		"f32" | "f64" => v.parse::<f64>().is_ok(),
+		"u8" => v.parse::<u8>().is_ok(),
+		"u16" => v.parse::<u16>().is_ok(),
+		"u32" => v.parse::<u32>().is_ok(),
+		"u64" => v.parse::<u64>().is_ok(),
+		"u128" => v.parse::<u128>().is_ok(),
+		"usize" => v.parse::<usize>().is_ok(),
+		"i8" => v.parse::<i8>().is_ok(),
+		"i16" => v.parse::<i16>().is_ok(),
+		"i32" => v.parse::<i32>().is_ok(),
+		"i64" => v.parse::<i64>().is_ok(),
+		"i128" => v.parse::<i128>().is_ok(),
+		"isize" => v.parse::<isize>().is_ok(),
+		_ => true,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rejects_mistakes() {
+		for (doc, message) in [
+			(
+				"[[key]]\nname = \"A\"\n[[key]]\nname = \"A\"",
+				"duplicate key 'A'",
+			),
+			(
+				"[[key]]\nname = \"A\"\ntype = \"u8\"\ndefault = \"300\"",
+				"not a valid u8",
+			),
+			(
+				"[[key]]\nname = \"A\"\ncolour = 1",
+				"unknown field 'colour'",
+			),
+			("[[key]]\ntype = \"u8\"", "missing `name`"),
+			(
+				"[[key]]\nname = \"A\"\nvalidate = [\"is port\"]",
+				"not a validator name",
+			),
+		] {
+			let err = parse(doc).err().unwrap();
+			assert!(err.contains(message), "{err}");
+		}
+	}
+
+	#[test]
+	fn accepts_valid_schema() {
+		let keys = parse(
+			"[[key]]\nname = \"PORT\"\ntype = \"u16\"\ndefault = \"8080\"\nvalidate = [\"is_port\"]\n",
+		)
+		.unwrap();
+		assert_eq!(keys.len(), 1);
+		assert_eq!(keys[0].validate, ["is_port"]);
+	}
+}
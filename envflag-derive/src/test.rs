@@ -0,0 +1,67 @@
+/* envflag-derive/src/test.rs */
+
+//! `#[envflag::test]`: a test run against its own store.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{Ident, ItemFn, LitStr, ReturnType};
+
+/// Variables collected from `vars(NAME = "value", ...)`, in order.
+#[derive(Default)]
+pub(crate) struct Args {
+	vars: Vec<(Ident, LitStr)>,
+}
+
+impl Args {
+	/// Parses one top-level item of the attribute.
+	pub(crate) fn parse(&mut self, meta: &ParseNestedMeta<'_>) -> syn::Result<()> {
+		if !meta.path.is_ident("vars") {
+			return Err(meta.error("unknown envflag::test argument; expected `vars(...)`"));
+		}
+		meta.parse_nested_meta(|var| {
+			let name = var.path.require_ident()?.clone();
+			if self.vars.iter().any(|(n, _)| *n == name) {
+				return Err(var.error(format!("`{name}` is set twice")));
+			}
+			let value: LitStr = var.value()?.parse()?;
+			self.vars.push((name, value));
+			Ok(())
+		})
+	}
+}
+
+pub(crate) fn expand(args: &Args, item: ItemFn) -> syn::Result<TokenStream2> {
+	if let Some(asyncness) = item.sig.asyncness {
+		return Err(syn::Error::new_spanned(
+			asyncness,
+			"envflag::test does not support async functions",
+		));
+	}
+
+	let ItemFn {
+		attrs,
+		vis,
+		sig,
+		block,
+	} = item;
+	let names = args.vars.iter().map(|(n, _)| n.to_string());
+	let values = args.vars.iter().map(|(_, v)| v);
+	let output = match &sig.output {
+		ReturnType::Default => quote!(),
+		ReturnType::Type(arrow, ty) => quote!(#arrow #ty),
+	};
+
+	Ok(quote! {
+		#[::core::prelude::v1::test]
+		#(#attrs)*
+		#vis #sig {
+			let store = ::envflag::store::EnvStore::from_map(
+				::std::collections::HashMap::from([
+					#((::std::string::String::from(#names), ::std::string::String::from(#values)),)*
+				]),
+			);
+			::envflag::with_store(store, move || #output #block)
+		}
+	})
+}
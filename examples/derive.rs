@@ -0,0 +1,49 @@
+/* examples/derive.rs */
+
+//! Demonstrates loading a whole configuration struct with `#[derive(EnvConfig)]`.
+//!
+//! Run with `cargo run --example derive --features derive`.
+
+use envflag::EnvConfig;
+
+#[derive(EnvConfig)]
+struct DbConfig {
+	/// Database host name.
+	#[env(default = "localhost")]
+	host: String,
+	#[env(default = "5432")]
+	port: u16,
+}
+
+#[derive(EnvConfig)]
+struct AppConfig {
+	/// Port the HTTP server listens on.
+	#[env(name = "APP_PORT", default = "8080")]
+	port: u16,
+	/// Enables verbose logging when set.
+	debug: Option<bool>,
+	/// Comma-separated list of allowed origins.
+	allowed_origins: Vec<String>,
+	#[env(prefix = "DB_")]
+	db: DbConfig,
+}
+
+fn main() {
+	envflag::init().expect("Failed to initialize envflag");
+
+	// Every field is read and validated in one call.
+	match AppConfig::from_env() {
+		Ok(config) => {
+			println!("Listening on port {}", config.port);
+			println!("Debug: {}", config.debug.unwrap_or(false));
+			println!("Allowed origins: {:?}", config.allowed_origins);
+			println!("Database: {}:{}", config.db.host, config.db.port);
+		}
+		Err(e) => eprintln!("Invalid configuration: {e}"),
+	}
+
+	// The same struct describes the variables it expects.
+	for spec in AppConfig::schema().keys() {
+		println!("{} ({})", spec.name, spec.type_name);
+	}
+}
@@ -0,0 +1,405 @@
+/* src/aws.rs */
+
+//! Loading parameters from AWS Systems Manager Parameter Store.
+//!
+//! ```rust,no_run
+//! # fn main() -> Result<(), envflag::EnvflagError> {
+//! envflag::builder()
+//!     .source(Box::new(envflag::aws::SsmParameters::new("/myapp/prod/")))
+//!     .init()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Every parameter under the path is fetched recursively, with
+//! `SecureString` values decrypted. The part of each name after the path
+//! becomes the key: `/myapp/prod/db/password` is loaded as `DB_PASSWORD`.
+//!
+//! Credentials are read from `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`,
+//! and `AWS_SESSION_TOKEN`, and the region from `AWS_REGION` or
+//! `AWS_DEFAULT_REGION`, as set by the AWS CLI, Lambda, and most CI
+//! systems. Instance-profile and shared-config credentials are not
+//! resolved; export them first (e.g. `aws configure export-credentials`).
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Write as _;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::error::EnvflagError;
+use crate::source::Source;
+
+/// The API operation used to list parameters.
+const TARGET: &str = "AmazonSSM.GetParametersByPath";
+
+/// Content type of the SSM JSON protocol.
+const CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+
+/// A [`Source`] that loads every parameter under a path from SSM Parameter
+/// Store.
+#[derive(Debug, Clone)]
+pub struct SsmParameters {
+	path: String,
+	region: Option<String>,
+	endpoint: Option<String>,
+}
+
+impl SsmParameters {
+	/// Loads the parameters under `path`, e.g. `/myapp/prod/`.
+	#[must_use]
+	pub fn new(path: &str) -> Self {
+		let mut path = path.to_owned();
+		if !path.ends_with('/') {
+			path.push('/');
+		}
+		Self {
+			path,
+			region: None,
+			endpoint: None,
+		}
+	}
+
+	/// Sets the region instead of reading `AWS_REGION`.
+	#[must_use]
+	pub fn region(mut self, region: &str) -> Self {
+		self.region = Some(region.to_owned());
+		self
+	}
+
+	/// Sends requests to `url` instead of `https://ssm.{region}.amazonaws.com`,
+	/// e.g. a VPC endpoint or a local emulator.
+	#[must_use]
+	pub fn endpoint(mut self, url: &str) -> Self {
+		self.endpoint = Some(url.trim_end_matches('/').to_owned());
+		self
+	}
+
+	fn error(&self, message: impl Into<String>) -> EnvflagError {
+		EnvflagError::Source {
+			origin: format!("ssm:{}", self.path),
+			message: message.into(),
+		}
+	}
+
+	/// Maps a parameter name under the path to a variable name.
+	fn key_for(&self, name: &str) -> Option<String> {
+		let rest = name.strip_prefix(&self.path)?;
+		Some(
+			rest
+				.chars()
+				.map(|c| match c {
+					'/' | '-' | '.' => '_',
+					c => c.to_ascii_uppercase(),
+				})
+				.collect(),
+		)
+	}
+}
+
+impl Source for SsmParameters {
+	fn load(&self) -> Result<HashMap<String, String>, EnvflagError> {
+		let credentials = Credentials::from_env().map_err(|e| self.error(e))?;
+		let region = self
+			.region
+			.clone()
+			.or_else(|| env::var("AWS_REGION").ok())
+			.or_else(|| env::var("AWS_DEFAULT_REGION").ok())
+			.ok_or_else(|| self.error("no region: set AWS_REGION or SsmParameters::region"))?;
+		let endpoint = self
+			.endpoint
+			.clone()
+			.unwrap_or_else(|| format!("https://ssm.{region}.amazonaws.com"));
+		let host = endpoint
+			.split_once("://")
+			.map_or(endpoint.as_str(), |(_, rest)| rest)
+			.to_owned();
+
+		let agent: ureq::Agent = ureq::Agent::config_builder()
+			.http_status_as_error(false)
+			.build()
+			.into();
+		let mut vars = HashMap::new();
+		let mut next_token: Option<String> = None;
+		loop {
+			let mut body = json!({
+				"Path": self.path,
+				"Recursive": true,
+				"WithDecryption": true,
+			});
+			if let Some(token) = next_token.take() {
+				body["NextToken"] = Value::String(token);
+			}
+			let body = body.to_string();
+
+			let request = SignedRequest::new(&credentials, &region, &host, &body, SystemTime::now());
+			let mut call = agent
+				.post(format!("{endpoint}/"))
+				.header("content-type", CONTENT_TYPE)
+				.header("x-amz-target", TARGET)
+				.header("x-amz-date", &request.date_time)
+				.header("authorization", &request.authorization);
+			if let Some(token) = &credentials.session_token {
+				call = call.header("x-amz-security-token", token);
+			}
+			let mut response = call.send(&body).map_err(|e| self.error(e.to_string()))?;
+			let status = response.status();
+			let text = response
+				.body_mut()
+				.read_to_string()
+				.map_err(|e| self.error(e.to_string()))?;
+			if !status.is_success() {
+				return Err(self.error(format!("{status}: {}", error_message(&text))));
+			}
+
+			let (parameters, token) = parse_page(&text).map_err(|e| self.error(e))?;
+			for (name, value) in parameters {
+				if let Some(key) = self.key_for(&name) {
+					vars.insert(key, value);
+				}
+			}
+			match token {
+				Some(token) => next_token = Some(token),
+				None => return Ok(vars),
+			}
+		}
+	}
+
+	fn origin(&self) -> Option<String> {
+		Some(format!("ssm:{}", self.path))
+	}
+}
+
+/// Static AWS credentials.
+struct Credentials {
+	access_key_id: String,
+	secret_access_key: String,
+	session_token: Option<String>,
+}
+
+impl Credentials {
+	fn from_env() -> Result<Self, String> {
+		let var = |name: &str| env::var(name).map_err(|_| format!("{name} is not set"));
+		Ok(Self {
+			access_key_id: var("AWS_ACCESS_KEY_ID")?,
+			secret_access_key: var("AWS_SECRET_ACCESS_KEY")?,
+			session_token: env::var("AWS_SESSION_TOKEN").ok(),
+		})
+	}
+}
+
+/// The Signature Version 4 headers for one `GetParametersByPath` call.
+struct SignedRequest {
+	date_time: String,
+	authorization: String,
+}
+
+impl SignedRequest {
+	fn new(credentials: &Credentials, region: &str, host: &str, body: &str, now: SystemTime) -> Self {
+		let secs = now.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+		let date_time = amz_date(secs);
+		let date = &date_time[..8];
+
+		let mut headers = vec![
+			("content-type", CONTENT_TYPE),
+			("host", host),
+			("x-amz-date", date_time.as_str()),
+			("x-amz-target", TARGET),
+		];
+		if let Some(token) = &credentials.session_token {
+			headers.push(("x-amz-security-token", token));
+		}
+		headers.sort_unstable();
+		let signed_headers = headers
+			.iter()
+			.map(|(k, _)| *k)
+			.collect::<Vec<_>>()
+			.join(";");
+		let mut canonical = String::from("POST\n/\n\n");
+		for (k, v) in &headers {
+			let _ = writeln!(canonical, "{k}:{}", v.trim());
+		}
+		let _ = write!(
+			canonical,
+			"\n{signed_headers}\n{}",
+			hex(&Sha256::digest(body))
+		);
+
+		let scope = format!("{date}/{region}/ssm/aws4_request");
+		let string_to_sign = format!(
+			"AWS4-HMAC-SHA256\n{date_time}\n{scope}\n{}",
+			hex(&Sha256::digest(&canonical))
+		);
+		let key = signing_key(&credentials.secret_access_key, date, region, "ssm");
+		let signature = hex(&hmac(&key, &string_to_sign));
+		let authorization = format!(
+			"AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+			credentials.access_key_id
+		);
+		Self {
+			date_time,
+			authorization,
+		}
+	}
+}
+
+/// Derives the SigV4 signing key for one day, region, and service.
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+	let key = hmac(format!("AWS4{secret}").as_bytes(), date);
+	let key = hmac(&key, region);
+	let key = hmac(&key, service);
+	hmac(&key, "aws4_request")
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+	let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+	mac.update(data.as_bytes());
+	mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+	bytes.iter().fold(String::new(), |mut s, b| {
+		let _ = write!(s, "{b:02x}");
+		s
+	})
+}
+
+/// Formats a Unix timestamp as `YYYYMMDDTHHMMSSZ`.
+fn amz_date(secs: u64) -> String {
+	let (days, rem) = (secs / 86_400, secs % 86_400);
+	// Civil-from-days, after Howard Hinnant's algorithm.
+	let z = days + 719_468;
+	let era = z / 146_097;
+	let doe = z % 146_097;
+	let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = doy - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = yoe + era * 400 + u64::from(month <= 2);
+	format!(
+		"{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+		rem / 3_600,
+		rem % 3_600 / 60,
+		rem % 60
+	)
+}
+
+/// Extracts the parameters and continuation token from a response body.
+fn parse_page(text: &str) -> Result<(Vec<(String, String)>, Option<String>), String> {
+	let page: Value = serde_json::from_str(text).map_err(|e| format!("invalid response: {e}"))?;
+	let parameters = page["Parameters"]
+		.as_array()
+		.ok_or("invalid response: missing Parameters")?
+		.iter()
+		.filter_map(|p| {
+			Some((
+				p["Name"].as_str()?.to_owned(),
+				p["Value"].as_str()?.to_owned(),
+			))
+		})
+		.collect();
+	let token = page["NextToken"]
+		.as_str()
+		.filter(|t| !t.is_empty())
+		.map(str::to_owned);
+	Ok((parameters, token))
+}
+
+/// Picks the human-readable message out of an error response.
+fn error_message(text: &str) -> String {
+	let Ok(error) = serde_json::from_str::<Value>(text) else {
+		return text.to_owned();
+	};
+	let kind = error["__type"].as_str().unwrap_or("error");
+	let kind = kind.rsplit('#').next().unwrap_or(kind);
+	match error["message"].as_str().or(error["Message"].as_str()) {
+		Some(message) => format!("{kind}: {message}"),
+		None => kind.to_owned(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn signing_key_matches_aws_example() {
+		let key = signing_key(
+			"wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+			"20150830",
+			"us-east-1",
+			"iam",
+		);
+		assert_eq!(
+			hex(&key),
+			"c4afb1cc5771d871763a393e44b703571b55cc28424d1a5e86da6ed3c154a4b9"
+		);
+	}
+
+	#[test]
+	fn signs_requests() {
+		let credentials = Credentials {
+			access_key_id: "AKID".to_owned(),
+			secret_access_key: "secret".to_owned(),
+			session_token: Some("tok".to_owned()),
+		};
+		let request = SignedRequest::new(
+			&credentials,
+			"us-east-1",
+			"ssm.us-east-1.amazonaws.com",
+			r#"{"Path":"/a/"}"#,
+			UNIX_EPOCH + std::time::Duration::from_secs(1_440_938_160),
+		);
+		assert_eq!(request.date_time, "20150830T123600Z");
+		assert_eq!(
+			request.authorization,
+			"AWS4-HMAC-SHA256 Credential=AKID/20150830/us-east-1/ssm/aws4_request, \
+			 SignedHeaders=content-type;host;x-amz-date;x-amz-security-token;x-amz-target, \
+			 Signature=f33d9dab4a89a87ffd669a0b2b9fb017fc9c1e5c0525504e99d65f44ae304e45"
+		);
+	}
+
+	#[test]
+	fn formats_amz_dates() {
+		assert_eq!(amz_date(1_440_938_160), "20150830T123600Z");
+		assert_eq!(amz_date(1_709_251_199), "20240229T235959Z");
+		assert_eq!(amz_date(0), "19700101T000000Z");
+	}
+
+	#[test]
+	fn maps_parameter_names_to_keys() {
+		let source = SsmParameters::new("/myapp/prod");
+		assert_eq!(
+			source.key_for("/myapp/prod/DATABASE_URL").as_deref(),
+			Some("DATABASE_URL")
+		);
+		assert_eq!(
+			source.key_for("/myapp/prod/db/password").as_deref(),
+			Some("DB_PASSWORD")
+		);
+		assert_eq!(
+			source.key_for("/myapp/prod/api-key").as_deref(),
+			Some("API_KEY")
+		);
+		assert_eq!(source.key_for("/other/KEY"), None);
+	}
+
+	#[test]
+	fn parses_pages_and_errors() {
+		let (params, token) = parse_page(
+			r#"{"Parameters":[{"Name":"/a/B","Type":"SecureString","Value":"s"}],"NextToken":"t"}"#,
+		)
+		.unwrap();
+		assert_eq!(params, [("/a/B".to_owned(), "s".to_owned())]);
+		assert_eq!(token.as_deref(), Some("t"));
+		assert!(parse_page("{}").is_err());
+		assert_eq!(
+			error_message(r#"{"__type":"com.amazon.coral#AccessDeniedException","Message":"no"}"#),
+			"AccessDeniedException: no"
+		);
+	}
+}
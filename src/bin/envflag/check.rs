@@ -0,0 +1,60 @@
+/* src/bin/envflag/check.rs */
+
+//! `envflag check`: validates a `.env` file against a schema.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use envflag::Schema;
+
+use crate::Result;
+use crate::env_file;
+
+/// Arguments for `envflag check`.
+#[derive(Debug, clap::Args)]
+pub(crate) struct Args {
+	/// Schema file declaring the expected keys.
+	#[arg(long, default_value = "schema.toml")]
+	schema: PathBuf,
+	/// The .env file to check.
+	#[arg(long, default_value = ".env")]
+	file: PathBuf,
+	/// Check only these keys; may be repeated.
+	#[arg(long = "key", value_name = "KEY")]
+	keys: Vec<String>,
+}
+
+pub(crate) fn run(args: &Args) -> Result<()> {
+	let schema = env_file::read_schema(&args.schema)?;
+	let vars: HashMap<String, String> = dotenvy::from_path_iter(&args.file)
+		.map_err(|e| format!("cannot read {}: {e}", args.file.display()))?
+		.collect::<std::result::Result<_, _>>()?;
+
+	let schema = select(schema, &args.keys)?;
+	let problems = env_file::check(&schema, &vars);
+	if !problems.is_empty() {
+		return Err(
+			format!(
+				"{} does not match the schema:\n  {}",
+				args.file.display(),
+				problems.join("\n  ")
+			)
+			.into(),
+		);
+	}
+	eprintln!("{}: {} keys ok", args.file.display(), schema.keys().len());
+	Ok(())
+}
+
+/// Narrows `schema` to `keys`, or keeps it whole if `keys` is empty.
+fn select(schema: Schema, keys: &[String]) -> Result<Schema> {
+	if keys.is_empty() {
+		return Ok(schema);
+	}
+	keys.iter().try_fold(Schema::new(), |selected, key| {
+		let spec = schema
+			.get(key)
+			.ok_or_else(|| format!("'{key}' is not declared in the schema"))?;
+		Ok(selected.key(spec.clone()))
+	})
+}
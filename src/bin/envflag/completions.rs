@@ -0,0 +1,69 @@
+/* src/bin/envflag/completions.rs */
+
+//! `envflag completions`: prints a shell completion script.
+
+use std::io;
+use std::path::PathBuf;
+
+use clap::builder::PossibleValuesParser;
+use clap_complete::Shell;
+
+use crate::Result;
+use crate::env_file;
+
+/// Arguments for `envflag completions`.
+#[derive(Debug, clap::Args)]
+pub(crate) struct Args {
+	/// Shell to generate the script for.
+	shell: Shell,
+	/// Schema whose key names are offered when completing `--key`.
+	#[arg(long)]
+	schema: Option<PathBuf>,
+}
+
+pub(crate) fn run(args: &Args) -> Result<()> {
+	let keys = match &args.schema {
+		Some(path) => env_file::read_schema(path)?
+			.keys()
+			.iter()
+			.map(|k| k.name.clone())
+			.collect(),
+		None => Vec::new(),
+	};
+	let mut cmd = command_with_keys(keys);
+	clap_complete::generate(args.shell, &mut cmd, "envflag", &mut io::stdout());
+	Ok(())
+}
+
+/// Returns the command definition with `keys` as the values of every
+/// `--key` argument.
+fn command_with_keys(keys: Vec<String>) -> clap::Command {
+	let cmd = crate::command();
+	if keys.is_empty() {
+		return cmd;
+	}
+	cmd.mut_subcommand("check", |sub| {
+		sub.mut_arg("keys", |arg| {
+			arg.value_parser(PossibleValuesParser::new(keys))
+		})
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn script_offers_schema_keys() {
+		let mut cmd = command_with_keys(vec!["DATABASE_URL".to_owned(), "PORT".to_owned()]);
+		let mut script = Vec::new();
+		clap_complete::generate(Shell::Bash, &mut cmd, "envflag", &mut script);
+		let script = String::from_utf8(script).unwrap();
+		assert!(script.contains("DATABASE_URL PORT"));
+	}
+
+	#[test]
+	fn command_definition_is_valid() {
+		crate::command().debug_assert();
+	}
+}
@@ -0,0 +1,51 @@
+/* src/bin/envflag/env_file.rs */
+
+//! Writing and checking `.env` files.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use envflag::Schema;
+
+use crate::Result;
+
+/// Reads and parses a schema file.
+pub(crate) fn read_schema(path: &Path) -> Result<Schema> {
+	let text =
+		fs::read_to_string(path).map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+	Ok(Schema::from_toml(&text)?)
+}
+
+/// Checks `vars` against every key in `schema`, returning one line per
+/// problem with values redacted.
+pub(crate) fn check(schema: &Schema, vars: &HashMap<String, String>) -> Vec<String> {
+	schema.validate(|name| vars.get(name).cloned())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use envflag::KeySpec;
+
+	#[test]
+	fn check_reports_every_problem_redacted() {
+		let schema = Schema::new()
+			.key(KeySpec::new("PORT").type_name("u16"))
+			.key(KeySpec::new("API_TOKEN").validator("is_url"))
+			.key(KeySpec::new("DATABASE_URL").required())
+			.key(KeySpec::new("LOG").required().default("info"));
+		let vars = HashMap::from([
+			("PORT".to_owned(), "http".to_owned()),
+			("API_TOKEN".to_owned(), "hunter2".to_owned()),
+		]);
+		assert_eq!(
+			check(&schema, &vars),
+			[
+				"PORT: 'http' is not a valid u16",
+				"API_TOKEN: '********' does not pass is_url",
+				"DATABASE_URL: required but not set",
+			]
+		);
+	}
+}
@@ -0,0 +1,152 @@
+/* src/bin/envflag/init.rs */
+
+//! `envflag init`: an interactive wizard that writes a `.env` file.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use envflag::schema::quote_value;
+use envflag::{KeySpec, Schema};
+
+use crate::Result;
+use crate::env_file;
+
+/// Arguments for `envflag init`.
+#[derive(Debug, clap::Args)]
+pub(crate) struct Args {
+	/// Schema file declaring the keys to prompt for.
+	#[arg(long, default_value = "schema.toml")]
+	schema: PathBuf,
+	/// Where to write the generated file.
+	#[arg(long, short, default_value = ".env")]
+	output: PathBuf,
+	/// Overwrite the output file if it already exists.
+	#[arg(long)]
+	force: bool,
+}
+
+pub(crate) fn run(args: &Args) -> Result<()> {
+	let schema = env_file::read_schema(&args.schema)?;
+	if args.output.exists() && !args.force {
+		return Err(
+			format!(
+				"{} already exists; pass --force to overwrite it",
+				args.output.display()
+			)
+			.into(),
+		);
+	}
+
+	let contents = prompt_all(&schema, &mut io::stdin().lock(), &mut io::stderr())?;
+	fs::write(&args.output, contents)?;
+	eprintln!("\nwrote {}", args.output.display());
+	Ok(())
+}
+
+/// Prompts for every key in `schema`, returning the `.env` file contents.
+///
+/// Keys left empty without a default are written commented out.
+fn prompt_all(schema: &Schema, input: &mut impl BufRead, out: &mut impl Write) -> Result<String> {
+	let mut file = String::new();
+	for spec in schema.keys() {
+		let value = prompt(spec, input, out)?;
+		if !file.is_empty() {
+			file.push('\n');
+		}
+		if let Some(description) = &spec.description {
+			writeln!(file, "# {description}")?;
+		}
+		match value {
+			Some(v) => writeln!(file, "{}={}", spec.name, quote_value(&v))?,
+			None => writeln!(file, "# {}=", spec.name)?,
+		}
+	}
+	Ok(file)
+}
+
+/// Asks for one key until the answer passes its checks.
+///
+/// Returns `None` if an optional key without a default was left empty.
+fn prompt(
+	spec: &KeySpec,
+	input: &mut impl BufRead,
+	out: &mut impl Write,
+) -> Result<Option<String>> {
+	let required = if spec.required { ", required" } else { "" };
+	writeln!(out, "\n{} ({}{required})", spec.name, spec.type_name)?;
+	if let Some(description) = &spec.description {
+		writeln!(out, "  {description}")?;
+	}
+	if !spec.validators.is_empty() {
+		writeln!(out, "  validate: {}", spec.validators.join(", "))?;
+	}
+
+	loop {
+		match &spec.default {
+			Some(d) => write!(out, "  value [{d}]: ")?,
+			None => write!(out, "  value: ")?,
+		}
+		out.flush()?;
+
+		let mut line = String::new();
+		if input.read_line(&mut line)? == 0 {
+			return Err(format!("input ended before {} was answered", spec.name).into());
+		}
+		let answer = line.trim_end_matches(['\n', '\r']);
+		let value = match (answer.is_empty(), &spec.default) {
+			(false, _) => answer.to_owned(),
+			(true, Some(d)) => d.clone(),
+			(true, None) if spec.required => {
+				writeln!(out, "  {} is required", spec.name)?;
+				continue;
+			}
+			(true, None) => return Ok(None),
+		};
+
+		match spec.check(&value) {
+			Ok(()) => return Ok(Some(value)),
+			Err(message) => writeln!(out, "  {message}")?,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn prompts_until_valid() {
+		let schema = Schema::new()
+			.key(
+				KeySpec::new("PORT")
+					.type_name("u16")
+					.default("8080")
+					.validator("is_port")
+					.description("Listen port"),
+			)
+			.key(KeySpec::new("DATABASE_URL").required())
+			.key(KeySpec::new("LOG_FILTER"));
+
+		// PORT: invalid, then invalid, then accept the default. DATABASE_URL:
+		// empty is refused. LOG_FILTER: left empty.
+		let mut input = "http\n0\n\n\npostgres://db\n\n".as_bytes();
+		let mut out = Vec::new();
+		let file = prompt_all(&schema, &mut input, &mut out).unwrap();
+
+		assert_eq!(
+			file,
+			"# Listen port\nPORT=8080\n\nDATABASE_URL=postgres://db\n\n# LOG_FILTER=\n"
+		);
+		let out = String::from_utf8(out).unwrap();
+		assert!(out.contains("does not pass is_port"));
+		assert!(out.contains("DATABASE_URL is required"));
+	}
+
+	#[test]
+	fn eof_is_an_error() {
+		let schema = Schema::new().key(KeySpec::new("A").required());
+		assert!(prompt_all(&schema, &mut "".as_bytes(), &mut Vec::new()).is_err());
+	}
+}
@@ -0,0 +1,67 @@
+/* src/bin/envflag/main.rs */
+
+//! The `envflag` command-line tool.
+
+mod check;
+mod completions;
+mod env_file;
+mod init;
+mod redact;
+mod render;
+mod verify;
+
+use std::error::Error;
+use std::process::ExitCode;
+
+use clap::{CommandFactory, Parser, Subcommand};
+
+/// Result type for subcommands; errors are printed and exit with status 1.
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// Companion tool for envflag configuration files.
+#[derive(Debug, Parser)]
+#[command(name = "envflag", version, about)]
+struct Cli {
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+	/// Prompt for every key declared in a schema and write a .env file.
+	Init(init::Args),
+	/// Render a .env template with answers from a TOML file.
+	Render(render::Args),
+	/// Check a .env file against a schema.
+	Check(check::Args),
+	/// Verify the current process environment against a schema.
+	Verify(verify::Args),
+	/// Print a .env file with secret values masked.
+	Redact(redact::Args),
+	/// Print a shell completion script.
+	Completions(completions::Args),
+}
+
+/// Returns the full command definition, used for completions.
+fn command() -> clap::Command {
+	Cli::command()
+}
+
+fn main() -> ExitCode {
+	let cli = Cli::parse();
+	let result = match &cli.command {
+		Command::Init(args) => init::run(args),
+		Command::Render(args) => render::run(args),
+		Command::Check(args) => check::run(args),
+		Command::Verify(args) => verify::run(args),
+		Command::Redact(args) => redact::run(args),
+		Command::Completions(args) => completions::run(args),
+	};
+	match result {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(e) => {
+			eprintln!("error: {e}");
+			ExitCode::FAILURE
+		}
+	}
+}
@@ -0,0 +1,117 @@
+/* src/bin/envflag/redact.rs */
+
+//! `envflag redact`: prints a `.env` file with secrets masked.
+
+use std::fs;
+use std::path::PathBuf;
+
+use envflag::redact;
+
+use crate::Result;
+
+/// Arguments for `envflag redact`.
+#[derive(Debug, clap::Args)]
+pub(crate) struct Args {
+	/// The .env file to print.
+	#[arg(default_value = ".env")]
+	file: PathBuf,
+}
+
+pub(crate) fn run(args: &Args) -> Result<()> {
+	let text = fs::read_to_string(&args.file)
+		.map_err(|e| format!("cannot read {}: {e}", args.file.display()))?;
+	print!("{}", redact_file(&text));
+	Ok(())
+}
+
+/// Masks the values of secret assignments, keeping comments and layout.
+///
+/// A quoted value that spans several lines is masked as a whole.
+fn redact_file(text: &str) -> String {
+	let mut out = String::with_capacity(text.len());
+	let mut lines = text.split_inclusive('\n');
+
+	while let Some(line) = lines.next() {
+		let Some((lead, key, value)) = assignment(line) else {
+			out.push_str(line);
+			continue;
+		};
+
+		// Gather continuation lines of an unterminated quoted value.
+		let mut value = value.to_owned();
+		if let Some(quote) = value.chars().next().filter(|c| matches!(c, '"' | '\''))
+			&& !is_closed(&value, quote)
+		{
+			for next in lines.by_ref() {
+				value.push_str(next);
+				if is_closed(&value, quote) {
+					break;
+				}
+			}
+		}
+
+		let newline = if value.ends_with('\n') { "\n" } else { "" };
+		let trimmed = value.trim_end_matches(['\n', '\r']);
+		out.push_str(lead);
+		out.push_str(key);
+		out.push('=');
+		out.push_str(&redact::redact(key, trimmed));
+		out.push_str(newline);
+	}
+	out
+}
+
+/// Splits `[export ]KEY=value` into the leading text, key, and raw value.
+fn assignment(line: &str) -> Option<(&str, &str, &str)> {
+	let body = line.trim_start();
+	let body = body.strip_prefix("export ").unwrap_or(body);
+	let lead = &line[..line.len() - body.len()];
+	let (key, value) = body.split_once('=')?;
+	let key = key.trim_end();
+	let valid = !key.is_empty()
+		&& key
+			.bytes()
+			.all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'.' | b'-'));
+	valid.then_some((lead, key, value))
+}
+
+/// Returns `true` if a value starting with `quote` has its closing quote.
+fn is_closed(value: &str, quote: char) -> bool {
+	let mut escaped = false;
+	for c in value.chars().skip(1) {
+		match c {
+			'\\' if quote == '"' && !escaped => escaped = true,
+			c if c == quote && !escaped => return true,
+			_ => escaped = false,
+		}
+	}
+	false
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn masks_secrets_and_keeps_layout() {
+		let text = "\
+# database
+DATABASE_URL=postgres://app:hunter2@db/app
+export API_TOKEN=\"abc\"
+PRIVATE_KEY=\"-----BEGIN KEY-----
+secret
+-----END KEY-----\"
+PORT=8080
+";
+		assert_eq!(
+			redact_file(text),
+			"\
+# database
+DATABASE_URL=postgres://app:********@db/app
+export API_TOKEN=********
+PRIVATE_KEY=********
+PORT=8080
+"
+		);
+	}
+}
@@ -0,0 +1,287 @@
+/* src/bin/envflag/render.rs */
+
+//! `envflag render`: stamps out a `.env` file from a template.
+//!
+//! Templates are plain `.env` text with two additions:
+//!
+//! - `${VAR}` is replaced by the answer for `VAR`; `$$` is a literal `$`.
+//! - Lines `#if COND`, `#else`, and `#endif` keep or drop the lines between
+//!   them. `COND` is `VAR` (set, non-empty, and not a false boolean),
+//!   `!VAR`, `VAR == value`, or `VAR != value`. Blocks may nest.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::Result;
+use crate::env_file;
+
+/// Arguments for `envflag render`.
+#[derive(Debug, clap::Args)]
+pub(crate) struct Args {
+	/// Template to render.
+	template: PathBuf,
+	/// TOML file with one `KEY = value` answer per placeholder.
+	#[arg(long)]
+	answers: PathBuf,
+	/// Schema the rendered file must satisfy.
+	#[arg(long)]
+	schema: Option<PathBuf>,
+	/// Where to write the rendered file; stdout if omitted.
+	#[arg(long, short)]
+	output: Option<PathBuf>,
+}
+
+pub(crate) fn run(args: &Args) -> Result<()> {
+	let template = read(&args.template)?;
+	let answers = parse_answers(&read(&args.answers)?)?;
+	let rendered = render(&template, &answers)?;
+
+	// Read the output back with the same parser the library uses.
+	let vars: HashMap<String, String> = dotenvy::from_read_iter(rendered.as_bytes())
+		.collect::<std::result::Result<_, _>>()
+		.map_err(|e| format!("rendered output is not a valid .env file: {e}"))?;
+	if let Some(path) = &args.schema {
+		let schema = env_file::read_schema(path)?;
+		let problems = env_file::check(&schema, &vars);
+		if !problems.is_empty() {
+			return Err(
+				format!(
+					"rendered output does not match the schema:\n  {}",
+					problems.join("\n  ")
+				)
+				.into(),
+			);
+		}
+	}
+
+	match &args.output {
+		Some(path) => fs::write(path, rendered)?,
+		None => print!("{rendered}"),
+	}
+	Ok(())
+}
+
+fn read(path: &PathBuf) -> Result<String> {
+	fs::read_to_string(path).map_err(|e| format!("cannot read {}: {e}", path.display()).into())
+}
+
+/// Parses a flat TOML table of answers.
+fn parse_answers(text: &str) -> Result<HashMap<String, String>> {
+	let table: toml::Table = text.parse()?;
+	table
+		.into_iter()
+		.map(|(k, v)| {
+			let value = match v {
+				toml::Value::String(s) => s,
+				toml::Value::Integer(i) => i.to_string(),
+				toml::Value::Float(f) => f.to_string(),
+				toml::Value::Boolean(b) => b.to_string(),
+				_ => return Err(format!("answer '{k}' must be a string, number, or boolean").into()),
+			};
+			Ok((k, value))
+		})
+		.collect()
+}
+
+/// One open `#if` block.
+struct Block {
+	line: usize,
+	outer_active: bool,
+	matched: bool,
+	in_else: bool,
+}
+
+impl Block {
+	fn active(&self) -> bool {
+		self.outer_active && self.matched != self.in_else
+	}
+}
+
+/// Renders `template`, failing on unknown placeholders and unbalanced blocks.
+fn render(
+	template: &str,
+	answers: &HashMap<String, String>,
+) -> std::result::Result<String, String> {
+	let mut out = String::with_capacity(template.len());
+	let mut blocks: Vec<Block> = Vec::new();
+
+	for (i, line) in template.split_inclusive('\n').enumerate() {
+		let n = i + 1;
+		let active = blocks.last().is_none_or(Block::active);
+		let directive = line.trim();
+
+		if let Some(cond) = directive.strip_prefix("#if ") {
+			let matched = condition(cond.trim(), answers).map_err(|e| format!("line {n}: {e}"))?;
+			blocks.push(Block {
+				line: n,
+				outer_active: active,
+				matched,
+				in_else: false,
+			});
+		} else if directive == "#else" {
+			match blocks.last_mut() {
+				Some(b) if !b.in_else => b.in_else = true,
+				Some(_) => return Err(format!("line {n}: second #else in the same block")),
+				None => return Err(format!("line {n}: #else without #if")),
+			}
+		} else if directive == "#endif" {
+			blocks
+				.pop()
+				.ok_or_else(|| format!("line {n}: #endif without #if"))?;
+		} else if active {
+			substitute(line, answers, &mut out).map_err(|e| format!("line {n}: {e}"))?;
+		}
+	}
+
+	if let Some(b) = blocks.last() {
+		return Err(format!("line {}: #if is never closed", b.line));
+	}
+	Ok(out)
+}
+
+/// Evaluates the condition of an `#if` line.
+fn condition(cond: &str, answers: &HashMap<String, String>) -> std::result::Result<bool, String> {
+	let lookup = |name: &str| {
+		let name = name.trim();
+		if is_name(name) {
+			Ok(answers.get(name).map(String::as_str))
+		} else {
+			Err(format!("invalid variable name '{name}' in condition"))
+		}
+	};
+
+	if let Some((name, value)) = cond.split_once("!=") {
+		return Ok(lookup(name)? != Some(unquote(value)));
+	}
+	if let Some((name, value)) = cond.split_once("==") {
+		return Ok(lookup(name)? == Some(unquote(value)));
+	}
+	if let Some(name) = cond.strip_prefix('!') {
+		return Ok(!truthy(lookup(name)?));
+	}
+	Ok(truthy(lookup(cond)?))
+}
+
+/// A variable is true if set, non-empty, and not a false boolean.
+fn truthy(value: Option<&str>) -> bool {
+	value.is_some_and(|v| {
+		let v = v.trim().to_lowercase();
+		!v.is_empty() && !matches!(v.as_str(), "false" | "0" | "no")
+	})
+}
+
+fn unquote(value: &str) -> &str {
+	let v = value.trim();
+	v.strip_prefix('"')
+		.and_then(|v| v.strip_suffix('"'))
+		.unwrap_or(v)
+}
+
+/// Appends `line` to `out` with placeholders replaced.
+fn substitute(
+	line: &str,
+	answers: &HashMap<String, String>,
+	out: &mut String,
+) -> std::result::Result<(), String> {
+	let mut rest = line;
+	while let Some(pos) = rest.find('$') {
+		out.push_str(&rest[..pos]);
+		rest = &rest[pos..];
+		if let Some(after) = rest.strip_prefix("$$") {
+			out.push('$');
+			rest = after;
+		} else if let Some(after) = rest.strip_prefix("${") {
+			let end = after.find('}').ok_or("unterminated ${")?;
+			let name = &after[..end];
+			if !is_name(name) {
+				return Err(format!("invalid placeholder ${{{name}}}"));
+			}
+			let value = answers
+				.get(name)
+				.ok_or_else(|| format!("no answer for ${{{name}}}"))?;
+			out.push_str(value);
+			rest = &after[end + 1..];
+		} else {
+			out.push('$');
+			rest = &rest[1..];
+		}
+	}
+	out.push_str(rest);
+	Ok(())
+}
+
+fn is_name(name: &str) -> bool {
+	!name.is_empty() && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn answers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+		pairs
+			.iter()
+			.map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+			.collect()
+	}
+
+	#[test]
+	fn placeholders_and_blocks() {
+		let template = "\
+HOST=${HOST}
+PRICE=$$5
+#if ENV == prod
+LOG=warn
+#if TLS
+TLS_CERT=/etc/tls/${HOST}.pem
+#endif
+#else
+LOG=debug
+#endif
+#if !DEBUG
+QUIET=1
+#endif
+";
+		let a = answers(&[
+			("HOST", "api"),
+			("ENV", "prod"),
+			("TLS", "yes"),
+			("DEBUG", "false"),
+		]);
+		assert_eq!(
+			render(template, &a).unwrap(),
+			"HOST=api\nPRICE=$5\nLOG=warn\nTLS_CERT=/etc/tls/api.pem\nQUIET=1\n"
+		);
+
+		let a = answers(&[("HOST", "api"), ("ENV", "dev"), ("DEBUG", "1")]);
+		assert_eq!(
+			render(template, &a).unwrap(),
+			"HOST=api\nPRICE=$5\nLOG=debug\n"
+		);
+	}
+
+	#[test]
+	fn template_errors() {
+		let a = answers(&[]);
+		assert!(
+			render("A=${MISSING}\n", &a)
+				.unwrap_err()
+				.contains("MISSING")
+		);
+		assert!(render("A=${OPEN\n", &a).is_err());
+		assert!(render("#if A\n", &a).unwrap_err().contains("never closed"));
+		assert!(render("#endif\n", &a).is_err());
+		assert!(render("#if A\n#else\n#else\n#endif\n", &a).is_err());
+		// Placeholders in dropped blocks are not resolved.
+		assert_eq!(render("#if A\nX=${MISSING}\n#endif\n", &a).unwrap(), "");
+	}
+
+	#[test]
+	fn answers_from_toml() {
+		let a = parse_answers("HOST = \"api\"\nPORT = 8080\nTLS = true\n").unwrap();
+		assert_eq!(a["PORT"], "8080");
+		assert_eq!(a["TLS"], "true");
+		assert!(parse_answers("NESTED = { a = 1 }").is_err());
+	}
+}
@@ -0,0 +1,82 @@
+/* src/bin/envflag/verify.rs */
+
+//! `envflag verify`: checks the process environment against a schema.
+//!
+//! Meant for deploy hooks and container entrypoints
+//! (`envflag verify && exec my-app`), so drift between the schema and the
+//! deployed environment fails before the application starts.
+
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+
+use crate::Result;
+use crate::env_file;
+
+/// Arguments for `envflag verify`.
+#[derive(Debug, clap::Args)]
+pub(crate) struct Args {
+	/// Schema file declaring the expected keys.
+	#[arg(long, default_value = "schema.toml")]
+	schema: PathBuf,
+	/// Prefix the application reads its keys under, e.g. `APP_`; may be
+	/// repeated. Schema names are matched with the prefix stripped.
+	#[arg(long = "prefix", value_name = "PREFIX")]
+	prefixes: Vec<String>,
+}
+
+pub(crate) fn run(args: &Args) -> Result<()> {
+	let schema = env_file::read_schema(&args.schema)?;
+	let vars = strip_prefixes(
+		env::vars_os().filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?))),
+		&args.prefixes,
+	);
+
+	let problems = env_file::check(&schema, &vars);
+	if !problems.is_empty() {
+		return Err(
+			format!(
+				"environment does not match {} ({} problems):\n  {}",
+				args.schema.display(),
+				problems.len(),
+				problems.join("\n  ")
+			)
+			.into(),
+		);
+	}
+	eprintln!("environment ok: {} keys verified", schema.keys().len());
+	Ok(())
+}
+
+/// Keeps the variables under one of `prefixes`, keyed by their short name.
+/// With no prefixes, every variable is kept as is.
+fn strip_prefixes(
+	vars: impl Iterator<Item = (String, String)>,
+	prefixes: &[String],
+) -> HashMap<String, String> {
+	if prefixes.is_empty() {
+		return vars.collect();
+	}
+	vars
+		.filter_map(|(k, v)| {
+			let short = prefixes.iter().find_map(|p| k.strip_prefix(p.as_str()))?;
+			Some((short.to_owned(), v))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn prefixes_are_stripped() {
+		let vars = [("APP_PORT", "1"), ("SVC_HOST", "h"), ("PATH", "/bin")]
+			.into_iter()
+			.map(|(k, v)| (k.to_owned(), v.to_owned()));
+		let short = strip_prefixes(vars, &["APP_".to_owned(), "SVC_".to_owned()]);
+		assert_eq!(short.len(), 2);
+		assert_eq!(short["PORT"], "1");
+		assert_eq!(short["HOST"], "h");
+	}
+}
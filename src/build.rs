@@ -0,0 +1,95 @@
+/* src/build.rs */
+
+//! Build-script helpers for compiling defaults into a binary.
+//!
+//! In `build.rs`:
+//!
+//! ```rust,no_run
+//! fn main() {
+//!     envflag::build::embed_defaults(".env.defaults").unwrap();
+//! }
+//! ```
+//!
+//! Then at startup:
+//!
+//! ```rust,ignore
+//! envflag::builder()
+//!     .defaults(envflag::embedded_defaults!())
+//!     .init()?;
+//! ```
+//!
+//! Add `envflag` with the `build` feature under `[build-dependencies]`.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::EnvflagError;
+
+/// Name of the generated file in `OUT_DIR`.
+pub const DEFAULTS_FILE: &str = "envflag_defaults.rs";
+
+/// Reads a `.env`-syntax file and writes its entries to
+/// `$OUT_DIR/envflag_defaults.rs` as a `&[(&str, &str)]` expression.
+///
+/// Also tells Cargo to re-run the build script when the file changes.
+/// Returns the path of the generated file.
+///
+/// # Errors
+///
+/// Returns an error if `OUT_DIR` is not set (i.e. not called from a build
+/// script), or the file cannot be read, parsed, or written.
+pub fn embed_defaults<P: AsRef<Path>>(path: P) -> Result<PathBuf, EnvflagError> {
+	let path = path.as_ref();
+	println!("cargo:rerun-if-changed={}", path.display());
+
+	let out_dir = env::var_os("OUT_DIR").ok_or_else(|| EnvflagError::Source {
+		origin: path.display().to_string(),
+		message: "OUT_DIR is not set; call embed_defaults from a build script".to_owned(),
+	})?;
+	let vars = dotenvy::from_path_iter(path)?.collect::<Result<BTreeMap<_, _>, _>>()?;
+
+	let out = Path::new(&out_dir).join(DEFAULTS_FILE);
+	fs::write(&out, render(&vars))?;
+	Ok(out)
+}
+
+/// Renders entries as a Rust slice expression, sorted by key.
+fn render(vars: &BTreeMap<String, String>) -> String {
+	let mut code = String::from("&[\n");
+	for (k, v) in vars {
+		// `{:?}` of a `str` is a valid Rust string literal.
+		let _ = writeln!(code, "\t({k:?}, {v:?}),");
+	}
+	code.push(']');
+	code
+}
+
+/// Expands to the defaults generated by
+/// [`build::embed_defaults`](crate::build::embed_defaults), for passing to
+/// [`InitBuilder::defaults`](crate::InitBuilder::defaults).
+#[macro_export]
+macro_rules! embedded_defaults {
+	() => {
+		include!(concat!(env!("OUT_DIR"), "/envflag_defaults.rs"))
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn renders_escaped_literals() {
+		let vars = BTreeMap::from([
+			("B".to_owned(), "line1\nline2 \"quoted\"".to_owned()),
+			("A".to_owned(), "plain".to_owned()),
+		]);
+		assert_eq!(
+			render(&vars),
+			"&[\n\t(\"A\", \"plain\"),\n\t(\"B\", \"line1\\nline2 \\\"quoted\\\"\"),\n]"
+		);
+	}
+}
@@ -3,17 +3,75 @@
 //! Chained query builder for environment variables.
 
 use crate::error::EnvflagError;
-use crate::store::EnvStore;
+use crate::parse;
+use crate::store::{EnvStore, StoreRef};
+use crate::usage::DefaultReason;
 use std::any::TypeId;
+use std::collections::HashMap;
 use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::ops::{Range, RangeInclusive};
 use std::str::FromStr;
 
+/// Per-query options carried from [`KeyBuilder`] into [`TypedKeyBuilder`].
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct KeyOptions {
+	/// Accept `1_000`, `1,5`, and surrounding whitespace for numeric types.
+	pub(crate) lenient_numbers: bool,
+	/// Read `KB`, `MB`, ... as powers of 1024 in byte sizes.
+	pub(crate) binary_units: bool,
+	/// Separator for list values; `,` if unset.
+	pub(crate) delimiter: Option<char>,
+	/// Keep the value out of errors and logs.
+	pub(crate) sensitive: bool,
+}
+
+impl KeyOptions {
+	/// Returns `value` as it may appear in errors and logs.
+	fn shown(self, value: String) -> String {
+		if self.sensitive {
+			crate::redact::MASK.to_owned()
+		} else {
+			value
+		}
+	}
+
+	/// Returns a parser's `message` as it may appear in errors and logs:
+	/// dropped for sensitive keys, since it may quote the value.
+	fn shown_message(self, message: String) -> Option<String> {
+		(!self.sensitive).then_some(message)
+	}
+}
+
+/// A string validator; `Err` carries an optional explanation.
+type Validator = Box<dyn Fn(&str) -> Result<(), Option<String>>>;
+
+/// Wraps a predicate as a [`Validator`] with no explanation.
+fn predicate(f: impl Fn(&str) -> bool + 'static) -> Validator {
+	Box::new(move |s| if f(s) { Ok(()) } else { Err(None) })
+}
+
+/// Wraps a validator returning a message as a [`Validator`].
+fn described(f: impl Fn(&str) -> Result<(), String> + 'static) -> Validator {
+	Box::new(move |s| f(s).map_err(Some))
+}
+
+/// Another name tried when the primary name of a query is not set.
+#[derive(Debug, Clone, Copy)]
+struct Fallback<'a> {
+	name: &'a str,
+	/// Warn and record the use when this name supplies the value.
+	deprecated: bool,
+}
+
 /// Builder for querying a specific environment variable.
 #[derive(Debug)]
 pub struct KeyBuilder<'a> {
 	name: &'a str,
 	prefix: Option<&'a str>,
 	store: Option<&'a EnvStore>,
+	options: KeyOptions,
+	fallbacks: Vec<Fallback<'a>>,
 }
 
 impl<'a> KeyBuilder<'a> {
@@ -23,6 +81,8 @@ impl<'a> KeyBuilder<'a> {
 			name,
 			prefix: None,
 			store: None,
+			options: KeyOptions::default(),
+			fallbacks: Vec::new(),
 		}
 	}
 
@@ -32,16 +92,14 @@ impl<'a> KeyBuilder<'a> {
 			name,
 			prefix: None,
 			store: Some(store),
+			options: KeyOptions::default(),
+			fallbacks: Vec::new(),
 		}
 	}
 
 	/// Resolves the store reference, falling back to the global instance.
-	fn resolve_store(&self) -> Result<&EnvStore, EnvflagError> {
-		if let Some(s) = self.store {
-			Ok(s)
-		} else {
-			EnvStore::get_instance()
-		}
+	fn resolve_store(&self) -> Result<StoreRef<'a>, EnvflagError> {
+		EnvStore::resolve(self.store)
 	}
 
 	/// Specifies which prefix to use for this lookup.
@@ -54,15 +112,123 @@ impl<'a> KeyBuilder<'a> {
 		self
 	}
 
+	/// Adds a fallback name, tried after the primary name and any earlier
+	/// fallbacks before falling back to the default.
+	///
+	/// Useful when renaming a variable while still accepting the old name.
+	/// Errors about a value read from a fallback report the fallback name.
+	#[must_use]
+	pub fn or_key(mut self, name: &'a str) -> Self {
+		self.fallbacks.push(Fallback {
+			name,
+			deprecated: false,
+		});
+		self
+	}
+
+	/// Adds a deprecated name, tried like [`or_key`](Self::or_key).
+	///
+	/// When the value comes from `name`, a warning telling operators to
+	/// rename it is logged (with the `tracing` feature, deduplicated per
+	/// [`WarnPolicy`](crate::WarnPolicy)), and the use is recorded for
+	/// [`deprecated_usage()`](crate::deprecated_usage), e.g. to report at
+	/// shutdown.
+	#[must_use]
+	pub fn deprecated_alias(mut self, name: &'a str) -> Self {
+		self.fallbacks.push(Fallback {
+			name,
+			deprecated: true,
+		});
+		self
+	}
+
+	/// Accepts human-authored numbers when parsing into a numeric type.
+	///
+	/// With this enabled, surrounding whitespace is ignored, `_` digit
+	/// separators are removed (`1_000_000`), and for floating-point types a
+	/// single `,` is read as the decimal point (`1,5`). Values of other types
+	/// are unaffected. Validators see the normalized value.
+	#[must_use]
+	pub fn lenient_numbers(mut self) -> Self {
+		self.options.lenient_numbers = true;
+		self
+	}
+
+	/// Reads `K`/`KB`, `M`/`MB`, ... as powers of 1024 instead of 1000 in
+	/// [`as_bytes`](Self::as_bytes). `KiB`, `MiB`, ... are always 1024-based.
+	#[must_use]
+	pub fn binary_units(mut self) -> Self {
+		self.options.binary_units = true;
+		self
+	}
+
+	/// Sets the separator used by [`list`](Self::list) and
+	/// [`map_values`](Self::map_values); the default is `,`.
+	#[must_use]
+	pub fn delimiter(mut self, delimiter: char) -> Self {
+		self.options.delimiter = Some(delimiter);
+		self
+	}
+
+	/// Marks the value as sensitive: parse and validation errors, and their
+	/// log lines, report [`MASK`](crate::redact::MASK) instead of the value.
+	#[must_use]
+	pub fn sensitive(mut self) -> Self {
+		self.options.sensitive = true;
+		self
+	}
+
 	/// Sets a default value and transitions to a typed builder.
 	#[must_use]
 	pub fn default<T: ToString>(self, val: T) -> TypedKeyBuilder<'a, T> {
+		self.typed(DefaultValue::Value(val))
+	}
+
+	/// Like [`default`](Self::default), but computes the default only if the
+	/// variable is not set, e.g. when it involves a hostname lookup or a file
+	/// read.
+	#[must_use]
+	pub fn default_fn<T: ToString>(self, f: impl FnOnce() -> T + 'a) -> TypedKeyBuilder<'a, T> {
+		self.typed(DefaultValue::Lazy(Box::new(f)))
+	}
+
+	fn typed<T>(self, default_val: DefaultValue<'a, T>) -> TypedKeyBuilder<'a, T> {
 		TypedKeyBuilder {
 			name: self.name,
 			prefix: self.prefix,
 			store: self.store,
-			default_val: val,
+			options: self.options,
+			fallbacks: self.fallbacks,
+			default_val,
 			validators: Vec::new(),
+			parsed_validators: Vec::new(),
+		}
+	}
+
+	/// Adds a validator and transitions to a builder for a required or
+	/// optional value, so mandatory variables get the same validation as
+	/// defaulted ones.
+	///
+	/// Validators see the value normalized for the target type, as with
+	/// [`TypedKeyBuilder::validate`].
+	#[must_use]
+	pub fn validate(self, f: impl Fn(&str) -> bool + 'static) -> RequiredKeyBuilder<'a> {
+		RequiredKeyBuilder {
+			key: self,
+			validators: vec![predicate(f)],
+		}
+	}
+
+	/// Like [`validate`](Self::validate), but the validator explains a
+	/// failure, e.g. `Err("must be between 1 and 65535".into())`.
+	#[must_use]
+	pub fn validate_with(
+		self,
+		f: impl Fn(&str) -> Result<(), String> + 'static,
+	) -> RequiredKeyBuilder<'a> {
+		RequiredKeyBuilder {
+			key: self,
+			validators: vec![described(f)],
 		}
 	}
 
@@ -74,41 +240,733 @@ impl<'a> KeyBuilder<'a> {
 	/// `EnvflagError::ParseFailed` if parsing fails, or
 	/// `EnvflagError::AmbiguousPrefix` if multiple prefixes are configured
 	/// without an explicit `with_prefix` call.
-	pub fn required<T: FromStr + 'static>(self) -> Result<T, EnvflagError> {
-		let store = self.resolve_store()?;
+	pub fn required<T: FromStr + 'static>(mut self) -> Result<T, EnvflagError> {
+		let (name, raw) = self.required_raw()?;
+		parse_raw(name, raw, self.options)
+	}
+
+	/// Parses the variable if it is set, returning `None` if it is not.
+	///
+	/// Unlike [`lookup`](crate::lookup), a value that is set but cannot be
+	/// parsed is an error rather than `None`.
+	///
+	/// # Errors
+	///
+	/// Returns `EnvflagError::ParseFailed` if parsing fails, or
+	/// `EnvflagError::AmbiguousPrefix` if multiple prefixes are configured
+	/// without an explicit `with_prefix` call.
+	pub fn optional<T: FromStr + 'static>(mut self) -> Result<Option<T>, EnvflagError> {
+		match self.required_raw() {
+			Ok((name, raw)) => parse_raw(name, raw, self.options).map(Some),
+			Err(EnvflagError::NotSet { .. }) => Ok(None),
+			Err(e) => Err(e),
+		}
+	}
+
+	/// Parses a required value with a custom parser instead of `FromStr`,
+	/// e.g. for types from other crates or bespoke formats.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required); an `Err` from `parse` is
+	/// reported as `EnvflagError::ParseFailed`.
+	pub fn parse_with<T>(
+		mut self,
+		parse: impl FnOnce(&str) -> Result<T, String>,
+	) -> Result<T, EnvflagError> {
+		let (name, raw) = self.required_raw()?;
+		parse(&raw).map_err(|message| EnvflagError::ParseFailed {
+			key: name.to_owned(),
+			value: self.options.shown(raw),
+			type_name: std::any::type_name::<T>(),
+			source_message: self.options.shown_message(message),
+		})
+	}
+
+	/// Parses a required delimited list, e.g. `HOSTS=a.example, b.example`.
+	///
+	/// Items are split on the [`delimiter`](Self::delimiter) (`,` by
+	/// default), trimmed, and parsed like [`required`](Self::required);
+	/// empty items are skipped.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required). A parse failure names the item
+	/// that failed, e.g. key `HOSTS[2]` with that item's value.
+	pub fn list<T: FromStr + 'static>(mut self) -> Result<Vec<T>, EnvflagError> {
+		let (name, raw) = self.required_raw()?;
+		raw
+			.split(self.options.delimiter.unwrap_or(','))
+			.map(str::trim)
+			.enumerate()
+			.filter(|(_, item)| !item.is_empty())
+			.map(|(i, item)| parse_raw(&format!("{name}[{i}]"), item.to_owned(), self.options))
+			.collect()
+	}
+
+	/// Parses a required list of `name=value` pairs, e.g.
+	/// `FEATURE_FLAGS=search=true,beta=false`, into a map.
+	///
+	/// Pairs are split on the [`delimiter`](Self::delimiter) (`,` by
+	/// default) and on the first `=`; names and values are trimmed and
+	/// empty pairs are skipped. Each value is parsed like
+	/// [`required`](Self::required).
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required). A value that fails to parse is
+	/// reported under its entry, e.g. key `FEATURE_FLAGS[beta]`; a pair
+	/// without `=`, with an empty name, or with a repeated name is reported
+	/// under the variable with that pair as the value.
+	pub fn map_values<T: FromStr + 'static>(mut self) -> Result<HashMap<String, T>, EnvflagError> {
+		let (name, raw) = self.required_raw()?;
+		let mut map = HashMap::new();
+		for pair in raw
+			.split(self.options.delimiter.unwrap_or(','))
+			.map(str::trim)
+			.filter(|pair| !pair.is_empty())
+		{
+			let malformed = || EnvflagError::ParseFailed {
+				key: name.to_owned(),
+				value: self.options.shown(pair.to_owned()),
+				type_name: "name=value pair",
+				source_message: None,
+			};
+			let (entry, value) = pair.split_once('=').ok_or_else(malformed)?;
+			let entry = entry.trim();
+			if entry.is_empty() || map.contains_key(entry) {
+				return Err(malformed());
+			}
+			let value = parse_raw(
+				&format!("{name}[{entry}]"),
+				value.trim().to_owned(),
+				self.options,
+			)?;
+			map.insert(entry.to_owned(), value);
+		}
+		Ok(map)
+	}
 
-		if store.prefixes().len() > 1 && self.prefix.is_none() {
+	/// Parses a required JSON value into any deserializable type, e.g. a
+	/// structured blob passed through a single variable.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required); invalid JSON or JSON of the
+	/// wrong shape is a parse failure.
+	#[cfg(feature = "json")]
+	pub fn json<T: serde::de::DeserializeOwned>(mut self) -> Result<T, EnvflagError> {
+		let (name, raw) = self.required_raw()?;
+		serde_json::from_str(&raw).map_err(|e| EnvflagError::ParseFailed {
+			key: name.to_owned(),
+			value: self.options.shown(raw),
+			type_name: std::any::type_name::<T>(),
+			source_message: self.options.shown_message(e.to_string()),
+		})
+	}
+
+	/// Collects the indexed variables `NAME_0`, `NAME_1`, ... in order until
+	/// the first missing index, parsing each like [`required`](Self::required).
+	///
+	/// Returns an empty list if `NAME_0` is not set. Fallback names are not
+	/// consulted.
+	///
+	/// # Errors
+	///
+	/// Returns `EnvflagError::ParseFailed` naming the item that failed, e.g.
+	/// `UPSTREAM_1`, or `EnvflagError::AmbiguousPrefix` if multiple prefixes
+	/// are configured without an explicit `with_prefix` call.
+	pub fn indexed<T: FromStr + 'static>(mut self) -> Result<Vec<T>, EnvflagError> {
+		let store = self.resolve_store()?;
+		self.options.sensitive |= store.redacts_values_in_errors();
+		if store.needs_prefix() && self.prefix.is_none() {
 			return Err(EnvflagError::AmbiguousPrefix {
 				key: self.name.to_owned(),
 			});
 		}
 
-		let raw = store
-			.lookup(self.name, self.prefix)
-			.ok_or_else(|| EnvflagError::NotSet {
-				key: self.name.to_owned(),
-			})?;
+		let mut items = Vec::new();
+		for i in 0.. {
+			let key = format!("{}_{i}", self.name);
+			let Some(raw) = store.lookup(&key, self.prefix) else {
+				break;
+			};
+			items.push(parse_raw(&key, raw, self.options)?);
+		}
+		Ok(items)
+	}
+
+	/// Parses a required `START-END` port range, e.g. `8000-8100`.
+	///
+	/// A single port is accepted as a range of one. Both ends must be valid
+	/// ports (1-65535) and `START` must not exceed `END`.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	pub fn port_range(self) -> Result<RangeInclusive<u16>, EnvflagError> {
+		self.required_with(parse::port_range)
+	}
+
+	/// Parses a required `START..END` range, e.g. `10..20`.
+	///
+	/// `START` must not exceed `END`. Use
+	/// [`range_inclusive`](Self::range_inclusive) for the `..=` syntax.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	pub fn range<T: FromStr + PartialOrd>(self) -> Result<Range<T>, EnvflagError> {
+		self.required_with(parse::range)
+	}
+
+	/// Parses a required `START..=END` range, e.g. `100..=500`.
+	///
+	/// `START` must not exceed `END`.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	pub fn range_inclusive<T: FromStr + PartialOrd>(self) -> Result<RangeInclusive<T>, EnvflagError> {
+		self.required_with(parse::range_inclusive)
+	}
+
+	/// Parses a required `HOST:PORT` pair, e.g. `upstream.example.com:9000`.
+	///
+	/// Unlike [`SocketAddr`](std::net::SocketAddr) parsing, the host may be
+	/// a DNS name. IPv6 addresses must be bracketed (`[::1]:9000`); the
+	/// brackets are stripped from the returned host. The port must be 1-65535.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	pub fn host_port(self) -> Result<(String, u16), EnvflagError> {
+		self.required_with(parse::host_port)
+	}
+
+	/// Parses a required list of `name=weight` pairs, e.g. `a=0.7,b=0.3`.
+	///
+	/// Names must be non-empty and unique; weights must be finite and
+	/// non-negative. Order is preserved.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	pub fn weighted_list(self) -> Result<Vec<(String, f64)>, EnvflagError> {
+		self.required_with(parse::weighted_list)
+	}
+
+	/// Like [`weighted_list`](Self::weighted_list), but also requires the
+	/// weights to sum to one, as for traffic splits and sampling ratios.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	pub fn weighted_list_sum_to_one(self) -> Result<Vec<(String, f64)>, EnvflagError> {
+		self.required_with(parse::weighted_list_sum_to_one)
+	}
+
+	/// Parses a required count with an optional decimal suffix, e.g.
+	/// `MAX_EVENTS=250k`.
+	///
+	/// Suffixes are `k`/`K` (thousand), `M` (million), `G` (billion), and
+	/// `T` (trillion). These are decimal multipliers for counts, not byte
+	/// sizes. A fractional value such as `1.5k` is accepted when the result
+	/// is a whole number.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required); overflowing `u64` is a parse
+	/// failure.
+	pub fn scaled_number(self) -> Result<u64, EnvflagError> {
+		self.required_with(parse::scaled_number)
+	}
+
+	/// Parses a required human-friendly duration, e.g. `30s`, `5m`, or
+	/// `1h 30m`.
+	///
+	/// Units from nanoseconds (`ns`) to years (`y`) are accepted; see
+	/// [`is_duration`](crate::validators::is_duration).
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	#[cfg(feature = "humantime")]
+	pub fn as_duration(self) -> Result<std::time::Duration, EnvflagError> {
+		self.required_with(|s| humantime::parse_duration(s).ok())
+	}
+
+	/// Parses a required byte size, e.g. `MAX_UPLOAD=512MB` or
+	/// `CACHE_SIZE=2GiB`, into a number of bytes.
+	///
+	/// Units are case-insensitive: `B`, `K`/`KB` through `P`/`PB` (powers of
+	/// 1000, or of 1024 with [`binary_units`](Self::binary_units)), and
+	/// `KiB` through `PiB` (powers of 1024). A bare number is bytes. A
+	/// fractional value such as `1.5GB` is accepted when the result is a
+	/// whole number of bytes.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required); overflowing `u64` is a parse
+	/// failure.
+	pub fn as_bytes(self) -> Result<u64, EnvflagError> {
+		let binary = self.options.binary_units;
+		self.required_with(|s| parse::byte_size(s, binary))
+	}
+
+	/// Parses a required log level such as `info` or `WARN` into a
+	/// `tracing` level filter, e.g. for `LOG_LEVEL`.
+	///
+	/// Accepts the names checked by
+	/// [`is_log_level`](crate::validators::is_log_level), in any case.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	#[cfg(feature = "tracing")]
+	pub fn log_level(self) -> Result<tracing::level_filters::LevelFilter, EnvflagError> {
+		self.validate(crate::validators::is_log_level).required()
+	}
+
+	/// Parses a required log level such as `info` or `WARN` into a
+	/// `tracing` [`Level`](tracing::Level).
+	///
+	/// Like [`log_level`](Self::log_level), but `off` is rejected, since it
+	/// is not a level.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	#[cfg(feature = "tracing")]
+	pub fn tracing_level(self) -> Result<tracing::Level, EnvflagError> {
+		self
+			.validate(|s| crate::validators::is_log_level(s) && !s.trim().eq_ignore_ascii_case("off"))
+			.required()
+	}
+
+	/// Parses a required log level such as `info` or `WARN` into a `log`
+	/// level filter, e.g. for `LOG_LEVEL`.
+	///
+	/// Accepts the names checked by
+	/// [`is_log_level`](crate::validators::is_log_level), in any case.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	#[cfg(feature = "log")]
+	pub fn log_level_filter(self) -> Result<log::LevelFilter, EnvflagError> {
+		self.validate(crate::validators::is_log_level).required()
+	}
+
+	/// Parses a required RFC 3339 timestamp, e.g.
+	/// `MAINTENANCE_START=2024-05-01T02:00:00Z`.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	#[cfg(feature = "chrono")]
+	pub fn as_datetime(self) -> Result<chrono::DateTime<chrono::FixedOffset>, EnvflagError> {
+		self.required_with(parse::datetime)
+	}
+
+	/// Parses a required 24-hour time of day, `HH:MM` or `HH:MM:SS`, e.g.
+	/// `BACKUP_AT=02:30`.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	#[cfg(feature = "chrono")]
+	pub fn as_time(self) -> Result<chrono::NaiveTime, EnvflagError> {
+		self.required_with(parse::time_of_day)
+	}
+
+	/// Parses a required UUID, e.g. `TENANT_ID`.
+	///
+	/// Accepts the forms `uuid` parses: hyphenated, simple, braced, and URN.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	#[cfg(feature = "uuid")]
+	pub fn as_uuid(self) -> Result<uuid::Uuid, EnvflagError> {
+		self.required_with(|s| uuid::Uuid::parse_str(s).ok())
+	}
+
+	/// Parses a required semantic version, e.g. `MIN_CLIENT_VERSION=1.4.0`.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	#[cfg(feature = "semver")]
+	pub fn as_version(self) -> Result<semver::Version, EnvflagError> {
+		self.required_with(|s| semver::Version::parse(s).ok())
+	}
+
+	/// Decodes a required hexadecimal value, e.g. a signing key, into bytes.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	pub fn decode_hex(self) -> Result<Vec<u8>, EnvflagError> {
+		self.required_with(parse::decode_hex)
+	}
+
+	/// Decodes a required base64 value into bytes. The standard and URL-safe
+	/// alphabets are accepted, with or without `=` padding.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required).
+	pub fn decode_base64(self) -> Result<Vec<u8>, EnvflagError> {
+		self.required_with(parse::decode_base64)
+	}
 
-		let val_str = if TypeId::of::<T>() == TypeId::of::<bool>() {
-			crate::validators::normalize_bool(&raw).into_owned()
+	/// Loads required PEM material, either inline or from a file.
+	///
+	/// If the value contains a `-----BEGIN` marker it is treated as inline
+	/// PEM, with literal `\n` escapes turned into newlines; otherwise it is
+	/// treated as a path and the file is read. Either way the result must
+	/// pass [`is_pem`](crate::validators::is_pem). The PEM text is returned
+	/// with a trailing newline.
+	///
+	/// To avoid leaking key material, a parse failure on inline PEM reports
+	/// `<inline PEM>` instead of the value.
+	///
+	/// # Errors
+	///
+	/// Same as [`required`](Self::required), plus `EnvflagError::Source`,
+	/// naming the variable, if the file cannot be read.
+	pub fn pem(mut self) -> Result<Vec<u8>, EnvflagError> {
+		let (name, raw) = self.required_raw()?;
+		let raw = raw.trim();
+
+		let (mut text, shown) = if raw.contains("-----BEGIN") {
+			(
+				parse::unescape_newlines(raw),
+				crate::redact::INLINE_PEM.to_owned(),
+			)
 		} else {
-			raw
+			let text = std::fs::read_to_string(raw).map_err(|e| EnvflagError::Source {
+				origin: name.to_owned(),
+				message: format!(
+					"cannot read PEM file '{}': {e}",
+					self.options.shown(raw.to_owned())
+				),
+			})?;
+			(text, raw.to_owned())
 		};
 
-		val_str.parse::<T>().map_err(|_| EnvflagError::ParseFailed {
-			key: self.name.to_owned(),
-			value: val_str,
+		if !crate::validators::is_pem(&text) {
+			return Err(EnvflagError::ParseFailed {
+				key: name.to_owned(),
+				value: self.options.shown(shown),
+				type_name: "PEM",
+				source_message: None,
+			});
+		}
+		if !text.ends_with('\n') {
+			text.push('\n');
+		}
+		Ok(text.into_bytes())
+	}
+
+	/// Returns the variable as a [`Secret`](crate::Secret), whose `Debug`
+	/// and `Display` print `[REDACTED]`.
+	///
+	/// # Errors
+	///
+	/// Returns `EnvflagError::NotSet` if the variable is missing, or
+	/// `EnvflagError::AmbiguousPrefix` if multiple prefixes are configured
+	/// without an explicit `with_prefix` call.
+	pub fn secret(mut self) -> Result<crate::Secret, EnvflagError> {
+		let (_, raw) = self.required_raw()?;
+		Ok(crate::Secret::new(raw))
+	}
+
+	/// Resolves the name and raw value of a required variable.
+	fn required_raw(&mut self) -> Result<(&'a str, String), EnvflagError> {
+		let store = self.resolve_store()?;
+		self.options.sensitive |= store.redacts_values_in_errors();
+
+		if store.needs_prefix() && self.prefix.is_none() {
+			return Err(EnvflagError::AmbiguousPrefix {
+				key: self.name.to_owned(),
+			});
+		}
+
+		lookup_first(&store, self.name, &self.fallbacks, self.prefix).ok_or_else(|| {
+			let names: Vec<&str> = std::iter::once(self.name)
+				.chain(self.fallbacks.iter().map(|f| f.name))
+				.collect();
+			store.not_set(&names, self.prefix)
+		})
+	}
+
+	/// Resolves a required variable and parses it with `parse`.
+	fn required_with<T>(mut self, parse: impl FnOnce(&str) -> Option<T>) -> Result<T, EnvflagError> {
+		let (name, raw) = self.required_raw()?;
+		parse(raw.trim()).ok_or_else(|| EnvflagError::ParseFailed {
+			key: name.to_owned(),
+			value: self.options.shown(raw),
+			type_name: std::any::type_name::<T>(),
+			source_message: None,
 		})
 	}
 }
 
+/// Looks up `name`, then each of `fallbacks` in order, returning the first
+/// name that is set with its value.
+fn lookup_first<'a>(
+	store: &EnvStore,
+	name: &'a str,
+	fallbacks: &[Fallback<'a>],
+	prefix: Option<&str>,
+) -> Option<(&'a str, String)> {
+	if let Some(raw) = store.lookup(name, prefix) {
+		return Some((name, raw));
+	}
+	fallbacks.iter().find_map(|f| {
+		let raw = store.lookup(f.name, prefix)?;
+		if f.deprecated {
+			crate::warn::deprecated(f.name, name);
+		}
+		Some((f.name, raw))
+	})
+}
+
+/// Normalizes a raw value for the target type, runs `validators` on it, and
+/// parses it.
+fn parse_validated<T: FromStr + 'static>(
+	name: &str,
+	raw: String,
+	options: KeyOptions,
+	validators: &[Validator],
+) -> Result<T, EnvflagError> {
+	// Normalize before validation so validators see the canonical
+	// form (e.g. "true"/"false" for booleans).
+	let val_str = normalize::<T>(raw, options);
+
+	// Run validators
+	if let Err(message) = validators.iter().try_for_each(|v| v(&val_str)) {
+		return Err(validation_failed(name, val_str, message, options));
+	}
+
+	// Parse
+	parse_normalized(name, val_str, options)
+}
+
+/// Logs (with the `tracing` feature) and returns a validation failure.
+fn validation_failed(
+	name: &str,
+	value: String,
+	message: Option<String>,
+	options: KeyOptions,
+) -> EnvflagError {
+	let value = options.shown(value);
+	#[cfg(feature = "tracing")]
+	if let Some(suppressed) = crate::warn::admit(name, "validate") {
+		tracing::warn!(
+			key = %name,
+			value = %value,
+			reason = message.as_deref().unwrap_or_default(),
+			suppressed,
+			"validation failed for environment variable"
+		);
+	}
+	EnvflagError::ValidationFailed {
+		key: name.to_owned(),
+		value,
+		message,
+	}
+}
+
+/// Normalizes a raw value for the target type and parses it.
+pub(crate) fn parse_raw<T: FromStr + 'static>(
+	key: &str,
+	raw: String,
+	options: KeyOptions,
+) -> Result<T, EnvflagError> {
+	parse_normalized(key, normalize::<T>(raw, options), options)
+}
+
+/// Rewrites a raw value into the canonical form expected by `T`'s `FromStr`;
+/// see [`normalize`](crate::normalize).
+pub(crate) fn normalize<T: 'static>(raw: String, options: KeyOptions) -> String {
+	let id = TypeId::of::<T>();
+	if let Some(f) = crate::normalize::lookup(id) {
+		return f(&raw);
+	}
+	if options.lenient_numbers {
+		if id == TypeId::of::<f32>() || id == TypeId::of::<f64>() {
+			return parse::lenient_number(&raw, true);
+		}
+		if is_integer_type(id) {
+			return parse::lenient_number(&raw, false);
+		}
+	}
+	raw
+}
+
+/// Parses an already-normalized value.
+fn parse_normalized<T: FromStr + 'static>(
+	key: &str,
+	val_str: String,
+	options: KeyOptions,
+) -> Result<T, EnvflagError> {
+	val_str.parse::<T>().map_err(|_| EnvflagError::ParseFailed {
+		key: key.to_owned(),
+		source_message: std_parse_message::<T>(&val_str).and_then(|m| options.shown_message(m)),
+		value: options.shown(val_str),
+		type_name: std::any::type_name::<T>(),
+	})
+}
+
+/// Returns the message the standard library's `FromStr` gives for `s` when
+/// `T` is a primitive or `std::net` type. Other types' errors need not
+/// implement `Display`, so their message is not available.
+fn std_parse_message<T: 'static>(s: &str) -> Option<String> {
+	macro_rules! message_for {
+		($($ty:ty),*) => {
+			$(
+				if TypeId::of::<T>() == TypeId::of::<$ty>() {
+					return s.parse::<$ty>().err().map(|e| e.to_string());
+				}
+			)*
+		};
+	}
+	message_for!(
+		i8,
+		i16,
+		i32,
+		i64,
+		i128,
+		isize,
+		u8,
+		u16,
+		u32,
+		u64,
+		u128,
+		usize,
+		f32,
+		f64,
+		bool,
+		char,
+		IpAddr,
+		Ipv4Addr,
+		Ipv6Addr,
+		SocketAddr,
+		SocketAddrV4,
+		SocketAddrV6
+	);
+	None
+}
+
+/// Returns `true` if `id` is one of the primitive integer types.
+fn is_integer_type(id: TypeId) -> bool {
+	[
+		TypeId::of::<i8>(),
+		TypeId::of::<i16>(),
+		TypeId::of::<i32>(),
+		TypeId::of::<i64>(),
+		TypeId::of::<i128>(),
+		TypeId::of::<isize>(),
+		TypeId::of::<u8>(),
+		TypeId::of::<u16>(),
+		TypeId::of::<u32>(),
+		TypeId::of::<u64>(),
+		TypeId::of::<u128>(),
+		TypeId::of::<usize>(),
+	]
+	.contains(&id)
+}
+
+/// A builder for a required or optional key with validators, created by
+/// [`KeyBuilder::validate`].
+pub struct RequiredKeyBuilder<'a> {
+	key: KeyBuilder<'a>,
+	validators: Vec<Validator>,
+}
+
+impl fmt::Debug for RequiredKeyBuilder<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("RequiredKeyBuilder")
+			.field("key", &self.key)
+			.field(
+				"validators",
+				&format!("[{} validator(s)]", self.validators.len()),
+			)
+			.finish()
+	}
+}
+
+impl RequiredKeyBuilder<'_> {
+	/// Adds another validator; all must pass.
+	#[must_use]
+	pub fn validate(mut self, f: impl Fn(&str) -> bool + 'static) -> Self {
+		self.validators.push(predicate(f));
+		self
+	}
+
+	/// Adds another validator that explains a failure; see
+	/// [`KeyBuilder::validate_with`].
+	#[must_use]
+	pub fn validate_with(mut self, f: impl Fn(&str) -> Result<(), String> + 'static) -> Self {
+		self.validators.push(described(f));
+		self
+	}
+
+	/// Validates and parses the variable, which must be set.
+	///
+	/// # Errors
+	///
+	/// Same as [`KeyBuilder::required`], plus `EnvflagError::ValidationFailed`
+	/// if any validator fails.
+	pub fn required<T: FromStr + 'static>(mut self) -> Result<T, EnvflagError> {
+		let (name, raw) = self.key.required_raw()?;
+		parse_validated(name, raw, self.key.options, &self.validators)
+	}
+
+	/// Validates and parses the variable if it is set, returning `None` if it
+	/// is not.
+	///
+	/// # Errors
+	///
+	/// Same as [`KeyBuilder::optional`], plus `EnvflagError::ValidationFailed`
+	/// if any validator fails.
+	pub fn optional<T: FromStr + 'static>(mut self) -> Result<Option<T>, EnvflagError> {
+		match self.key.required_raw() {
+			Ok((name, raw)) => parse_validated(name, raw, self.key.options, &self.validators).map(Some),
+			Err(EnvflagError::NotSet { .. }) => Ok(None),
+			Err(e) => Err(e),
+		}
+	}
+}
+
+/// The default of a [`TypedKeyBuilder`].
+enum DefaultValue<'a, T> {
+	Value(T),
+	Lazy(Box<dyn FnOnce() -> T + 'a>),
+}
+
+impl<T: fmt::Debug> fmt::Debug for DefaultValue<'_, T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Value(val) => val.fmt(f),
+			Self::Lazy(_) => f.write_str("<lazy>"),
+		}
+	}
+}
+
 /// A builder for a specific key with a default value and optional validators.
 pub struct TypedKeyBuilder<'a, T> {
 	name: &'a str,
 	prefix: Option<&'a str>,
 	store: Option<&'a EnvStore>,
-	default_val: T,
-	validators: Vec<Box<dyn Fn(&str) -> bool>>,
+	options: KeyOptions,
+	fallbacks: Vec<Fallback<'a>>,
+	default_val: DefaultValue<'a, T>,
+	validators: Vec<Validator>,
+	parsed_validators: Vec<Box<dyn Fn(&T) -> bool>>,
 }
 
 impl<T: fmt::Debug> fmt::Debug for TypedKeyBuilder<'_, T> {
@@ -116,10 +974,14 @@ impl<T: fmt::Debug> fmt::Debug for TypedKeyBuilder<'_, T> {
 		f.debug_struct("TypedKeyBuilder")
 			.field("name", &self.name)
 			.field("prefix", &self.prefix)
+			.field("fallbacks", &self.fallbacks)
 			.field("default_val", &self.default_val)
 			.field(
 				"validators",
-				&format!("[{} validator(s)]", self.validators.len()),
+				&format!(
+					"[{} validator(s)]",
+					self.validators.len() + self.parsed_validators.len()
+				),
 			)
 			.finish()
 	}
@@ -130,12 +992,8 @@ where
 	T: FromStr + ToString + 'static,
 {
 	/// Resolves the store reference, falling back to the global instance.
-	fn resolve_store(&self) -> Result<&EnvStore, EnvflagError> {
-		if let Some(s) = self.store {
-			Ok(s)
-		} else {
-			EnvStore::get_instance()
-		}
+	fn resolve_store(&self) -> Result<StoreRef<'a>, EnvflagError> {
+		EnvStore::resolve(self.store)
 	}
 
 	/// Adds a validator function to be run against the raw string value.
@@ -144,7 +1002,26 @@ where
 	/// Accepts any `Fn(&str) -> bool`, including closures and function pointers.
 	#[must_use]
 	pub fn validate(mut self, f: impl Fn(&str) -> bool + 'static) -> Self {
-		self.validators.push(Box::new(f));
+		self.validators.push(predicate(f));
+		self
+	}
+
+	/// Like [`validate`](Self::validate), but the validator explains a
+	/// failure, e.g. `Err("must be between 1 and 65535".into())`. The
+	/// message is carried in `EnvflagError::ValidationFailed`.
+	#[must_use]
+	pub fn validate_with(mut self, f: impl Fn(&str) -> Result<(), String> + 'static) -> Self {
+		self.validators.push(described(f));
+		self
+	}
+
+	/// Adds a validator run against the parsed value, e.g. `|port| *port > 1024`.
+	///
+	/// Runs after parsing and after the string validators; the default is not
+	/// validated. On failure the error reports the raw value.
+	#[must_use]
+	pub fn validate_parsed(mut self, f: impl Fn(&T) -> bool + 'static) -> Self {
+		self.parsed_validators.push(Box::new(f));
 		self
 	}
 
@@ -156,50 +1033,29 @@ where
 	/// - `EnvflagError::ParseFailed` if parsing fails.
 	/// - `EnvflagError::AmbiguousPrefix` if multiple prefixes are configured
 	///   without an explicit `with_prefix` call.
-	pub fn get(self) -> Result<T, EnvflagError> {
+	pub fn get(mut self) -> Result<T, EnvflagError> {
 		let store = self.resolve_store()?;
+		self.options.sensitive |= store.redacts_values_in_errors();
 
-		if store.prefixes().len() > 1 && self.prefix.is_none() {
+		if store.needs_prefix() && self.prefix.is_none() {
 			return Err(EnvflagError::AmbiguousPrefix {
 				key: self.name.to_owned(),
 			});
 		}
 
-		let val_str_opt = store.lookup(self.name, self.prefix);
-
-		match val_str_opt {
-			Some(raw) => {
-				// Normalize booleans before validation so validators see
-				// the canonical "true"/"false" form.
-				let val_str = if TypeId::of::<T>() == TypeId::of::<bool>() {
-					crate::validators::normalize_bool(&raw).into_owned()
-				} else {
-					raw
-				};
-
-				// Run validators
-				for v in &self.validators {
-					if !v(&val_str) {
-						#[cfg(feature = "tracing")]
-						tracing::warn!(
-							key = %self.name,
-							value = %val_str,
-							"validation failed for environment variable"
-						);
-						return Err(EnvflagError::ValidationFailed {
-							key: self.name.to_owned(),
-							value: val_str,
-						});
-					}
-				}
+		let Some((name, raw)) = lookup_first(&store, self.name, &self.fallbacks, self.prefix) else {
+			store.record_default(self.name, DefaultReason::Missing);
+			return Ok(match self.default_val {
+				DefaultValue::Value(val) => val,
+				DefaultValue::Lazy(f) => f(),
+			});
+		};
 
-				// Parse
-				val_str.parse::<T>().map_err(|_| EnvflagError::ParseFailed {
-					key: self.name.to_owned(),
-					value: val_str,
-				})
-			}
-			None => Ok(self.default_val),
+		let value = parse_validated(name, raw.clone(), self.options, &self.validators)?;
+		if self.parsed_validators.iter().all(|v| v(&value)) {
+			Ok(value)
+		} else {
+			Err(validation_failed(name, raw, None, self.options))
 		}
 	}
 }
@@ -0,0 +1,175 @@
+/* src/config.rs */
+
+//! Struct-based loading of configuration.
+
+use std::str::FromStr;
+
+use crate::builder::{KeyOptions, parse_raw};
+use crate::error::EnvflagError;
+use crate::schema::Schema;
+use crate::store::EnvStore;
+use crate::usage::DefaultReason;
+
+/// A configuration struct that can be loaded from an [`EnvStore`].
+///
+/// Usually implemented with `#[derive(EnvConfig)]` (requires the `derive`
+/// feature), which also adds inherent `from_env()` / `from_store()` /
+/// `schema()` methods so the trait does not need to be in scope.
+pub trait EnvConfig: Sized {
+	/// Loads the configuration, prepending `prefix` to every variable name.
+	///
+	/// The prefix is what nested structs use to scope their fields; it is
+	/// applied on top of any prefix the store itself was configured with.
+	///
+	/// # Errors
+	///
+	/// Returns the first error encountered while loading a field.
+	fn from_store_prefixed(store: &EnvStore, prefix: &str) -> Result<Self, EnvflagError>;
+
+	/// Describes the variables this configuration reads.
+	///
+	/// The default implementation returns an empty schema; the derive
+	/// generates a complete one.
+	#[must_use]
+	fn schema() -> Schema {
+		Schema::new()
+	}
+
+	/// Loads the configuration from the given store.
+	///
+	/// # Errors
+	///
+	/// Returns the first error encountered while loading a field.
+	fn from_store(store: &EnvStore) -> Result<Self, EnvflagError> {
+		Self::from_store_prefixed(store, "")
+	}
+
+	/// Loads the configuration from the global store.
+	///
+	/// # Errors
+	///
+	/// Returns `EnvflagError::NotInitialized` if the crate has not been
+	/// initialized, or the first error encountered while loading a field.
+	fn from_env() -> Result<Self, EnvflagError> {
+		let store = EnvStore::get_instance()?;
+		Self::from_store(&store)
+	}
+}
+
+/// Loads a required field.
+pub fn field<T: FromStr + 'static>(store: &EnvStore, name: &str) -> Result<T, EnvflagError> {
+	store.key(name).required()
+}
+
+/// Loads a field, parsing `default` when the variable is missing.
+pub fn field_or<T: FromStr + 'static>(
+	store: &EnvStore,
+	name: &str,
+	default: &str,
+) -> Result<T, EnvflagError> {
+	if let Some(value) = store.key(name).optional()? {
+		return Ok(value);
+	}
+	store.record_default(name, DefaultReason::Missing);
+	let options = KeyOptions {
+		sensitive: store.redacts_values_in_errors(),
+		..KeyOptions::default()
+	};
+	parse_raw(name, default.to_owned(), options)
+}
+
+/// Loads an optional field.
+pub fn optional_field<T: FromStr + 'static>(
+	store: &EnvStore,
+	name: &str,
+) -> Result<Option<T>, EnvflagError> {
+	store.key(name).optional()
+}
+
+/// Loads a list field from a comma-separated value, or from `NAME_0`,
+/// `NAME_1`, … (stopping at the first gap) when `NAME` itself is not set.
+pub fn list_field<T: FromStr + 'static>(
+	store: &EnvStore,
+	name: &str,
+) -> Result<Vec<T>, EnvflagError> {
+	match store.key(name).list() {
+		Err(EnvflagError::NotSet { .. }) => {}
+		result => return result,
+	}
+
+	(0..)
+		.map(|i| format!("{name}_{i}"))
+		.map_while(|k| store.key(&k).optional().transpose())
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(Debug)]
+	struct Db {
+		host: String,
+		port: u16,
+	}
+
+	impl EnvConfig for Db {
+		fn from_store_prefixed(store: &EnvStore, prefix: &str) -> Result<Self, EnvflagError> {
+			Ok(Self {
+				host: field(store, &format!("{prefix}HOST"))?,
+				port: field_or(store, &format!("{prefix}PORT"), "5432")?,
+			})
+		}
+	}
+
+	#[derive(Debug)]
+	struct App {
+		workers: Option<u8>,
+		hosts: Vec<String>,
+		db: Db,
+	}
+
+	impl EnvConfig for App {
+		fn from_store_prefixed(store: &EnvStore, prefix: &str) -> Result<Self, EnvflagError> {
+			Ok(Self {
+				workers: optional_field(store, &format!("{prefix}WORKERS"))?,
+				hosts: list_field(store, &format!("{prefix}HOSTS"))?,
+				db: Db::from_store_prefixed(store, &format!("{prefix}DB_"))?,
+			})
+		}
+	}
+
+	fn store(pairs: &[(&str, &str)]) -> EnvStore {
+		EnvStore::from_map(
+			pairs
+				.iter()
+				.map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+				.collect(),
+		)
+	}
+
+	#[test]
+	fn loads_option_vec_and_nested_fields() {
+		let app = App::from_store(&store(&[("HOSTS", "a, b,"), ("DB_HOST", "db")])).unwrap();
+		assert_eq!(app.workers, None);
+		assert_eq!(app.hosts, ["a", "b"]);
+		assert_eq!((app.db.host.as_str(), app.db.port), ("db", 5432));
+
+		let app = App::from_store(&store(&[
+			("WORKERS", "4"),
+			("HOSTS_0", "x"),
+			("HOSTS_1", "y"),
+			("DB_HOST", "db"),
+			("DB_PORT", "6432"),
+		]))
+		.unwrap();
+		assert_eq!(app.workers, Some(4));
+		assert_eq!(app.hosts, ["x", "y"]);
+		assert_eq!(app.db.port, 6432);
+
+		let err = App::from_store(&store(&[("WORKERS", "many"), ("DB_HOST", "db")])).unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { .. }), "{err}");
+		let err = App::from_store(&store(&[])).unwrap_err();
+		assert!(matches!(err, EnvflagError::NotSet { .. }), "{err}");
+	}
+}
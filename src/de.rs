@@ -0,0 +1,413 @@
+/* src/de.rs */
+
+//! A serde [`Deserializer`] over the loaded variables.
+//!
+//! Struct fields map to upper-cased variable names (`database_url` reads
+//! `DATABASE_URL`), resolved through [`EnvStore::lookup`] so configured
+//! prefixes and prefix chains apply. Maps receive every variable with its
+//! prefix stripped and its name lower-cased, matching the field mapping.
+//!
+//! Values are flat: numbers and booleans are parsed from the string (booleans
+//! accept the same spellings as the builder API), sequences split on commas,
+//! and unit enum variants match the raw value. Nested structs are not
+//! supported; use `#[serde(flatten)]` or [`EnvConfig`](crate::EnvConfig)
+//! with `#[env(prefix = ...)]` instead.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::vec;
+
+use serde::de::value::StringDeserializer;
+use serde::de::{
+	self, DeserializeOwned, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess,
+	Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+use crate::error::EnvflagError;
+use crate::store::EnvStore;
+use crate::validators::normalize_bool;
+
+impl de::Error for EnvflagError {
+	fn custom<T: fmt::Display>(msg: T) -> Self {
+		Self::Deserialize {
+			message: msg.to_string(),
+		}
+	}
+
+	fn missing_field(field: &'static str) -> Self {
+		Self::NotSet {
+			key: field.to_uppercase(),
+			tried: Vec::new(),
+			suggestion: None,
+		}
+	}
+}
+
+impl EnvStore {
+	/// Deserializes the loaded variables into any [`Deserialize`](serde::Deserialize)
+	/// type, envy-style.
+	///
+	/// # Errors
+	///
+	/// Returns `EnvflagError::NotSet` for a missing required field,
+	/// `EnvflagError::ParseFailed` for a value of the wrong type, and
+	/// `EnvflagError::Deserialize` for anything else the type rejects.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::collections::HashMap;
+	/// use envflag::store::EnvStore;
+	///
+	/// #[derive(serde::Deserialize)]
+	/// struct Config {
+	///     port: u16,
+	///     debug: Option<bool>,
+	/// }
+	///
+	/// let store = EnvStore::from_map_with_prefixes(
+	///     HashMap::from([("APP_PORT".into(), "8080".into())]),
+	///     vec!["APP_".into()],
+	/// );
+	/// let config: Config = store.deserialize().unwrap();
+	/// assert_eq!(config.port, 8080);
+	/// assert_eq!(config.debug, None);
+	/// ```
+	pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, EnvflagError> {
+		T::deserialize(self).map_err(|e| match e {
+			EnvflagError::NotSet { key, .. } => self.not_set(&[&key], None),
+			e => e,
+		})
+	}
+
+	/// Deserializes the variables under a sub-prefix, e.g. `DB_HOST` and
+	/// `DB_PORT` into the `host` and `port` fields of a `DbConfig`.
+	///
+	/// The sub-prefix applies after the store's own prefixes, so with an
+	/// `APP_` prefix, `nested("DB_")` reads `APP_DB_HOST`.
+	///
+	/// # Errors
+	///
+	/// Same as [`deserialize`](Self::deserialize); missing and invalid fields
+	/// are named with the sub-prefix, and report the full variable name such
+	/// as `APP_DB_PORT`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::collections::HashMap;
+	/// use envflag::store::EnvStore;
+	///
+	/// #[derive(serde::Deserialize)]
+	/// struct DbConfig {
+	///     host: String,
+	///     port: u16,
+	/// }
+	///
+	/// let store = EnvStore::from_map(HashMap::from([
+	///     ("DB_HOST".into(), "localhost".into()),
+	///     ("DB_PORT".into(), "5432".into()),
+	/// ]));
+	/// let db: DbConfig = store.nested("DB_").unwrap();
+	/// assert_eq!(db.port, 5432);
+	/// ```
+	pub fn nested<T: DeserializeOwned>(&self, prefix: &str) -> Result<T, EnvflagError> {
+		let prefix = prefix.to_uppercase();
+		let map = self
+			.short_entries()
+			.into_iter()
+			.map(|(k, v)| (k.to_uppercase(), v))
+			.filter(|(k, _)| k.starts_with(&prefix))
+			.collect();
+		Self::from_map_with_prefixes(map, vec![prefix.clone()])
+			.with_redacted_errors(self.redacts_values_in_errors())
+			.deserialize()
+			.map_err(|e| match e {
+				EnvflagError::NotSet { key, .. } => self.not_set(&[&format!("{prefix}{key}")], None),
+				EnvflagError::ParseFailed {
+					key,
+					value,
+					type_name,
+					source_message,
+				} => EnvflagError::ParseFailed {
+					key: self.storage_key(&key),
+					value,
+					type_name,
+					source_message,
+				},
+				e => e,
+			})
+	}
+
+	/// Returns every variable under its short name, lower-cased. Earlier
+	/// prefixes in a chain win over later ones.
+	fn short_entries(&self) -> BTreeMap<String, String> {
+		let mut out = BTreeMap::new();
+		if self.prefixes().is_empty() {
+			for (k, v) in self.entries() {
+				out.insert(k.to_lowercase(), v);
+			}
+			return out;
+		}
+		for p in self.prefixes().iter().rev() {
+			for (k, v) in self.entries() {
+				if let Some(short) = k.strip_prefix(p.as_str()) {
+					out.insert(short.to_lowercase(), v);
+				}
+			}
+		}
+		out
+	}
+}
+
+impl<'de> Deserializer<'de> for &EnvStore {
+	type Error = EnvflagError;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		self.deserialize_map(visitor)
+	}
+
+	fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let entries: Vec<_> = self
+			.short_entries()
+			.into_iter()
+			.map(|(name, raw)| {
+				let key = self.storage_key(&name.to_uppercase());
+				(name, key, raw)
+			})
+			.collect();
+		visitor.visit_map(Entries {
+			iter: entries.into_iter(),
+			value: None,
+			redact: self.redacts_values_in_errors(),
+		})
+	}
+
+	fn deserialize_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		let found: Vec<_> = fields
+			.iter()
+			.filter_map(|field| {
+				let key = field.to_uppercase();
+				let raw = self.lookup(&key, None)?;
+				Some((
+					(*field).to_owned(),
+					Value {
+						key: self.storage_key(&key),
+						raw,
+						redact: self.redacts_values_in_errors(),
+					},
+				))
+			})
+			.collect();
+		visitor.visit_map(Fields {
+			iter: found.into_iter(),
+			value: None,
+		})
+	}
+
+	forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf option unit unit_struct newtype_struct seq tuple
+		tuple_struct enum identifier ignored_any
+	}
+}
+
+/// Map access over every variable, for maps and flattened structs.
+struct Entries {
+	/// Short name, full variable name, and value of each variable.
+	iter: vec::IntoIter<(String, String, String)>,
+	value: Option<Value>,
+	/// Whether errors omit values; see [`InitBuilder::redact_values_in_errors`](crate::InitBuilder::redact_values_in_errors).
+	redact: bool,
+}
+
+impl<'de> MapAccess<'de> for Entries {
+	type Error = EnvflagError;
+
+	fn next_key_seed<K: DeserializeSeed<'de>>(
+		&mut self,
+		seed: K,
+	) -> Result<Option<K::Value>, Self::Error> {
+		let Some((name, key, raw)) = self.iter.next() else {
+			return Ok(None);
+		};
+		self.value = Some(Value {
+			key,
+			raw,
+			redact: self.redact,
+		});
+		seed.deserialize(name.into_deserializer()).map(Some)
+	}
+
+	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+		let value = self
+			.value
+			.take()
+			.ok_or_else(|| <EnvflagError as de::Error>::custom("value requested before key"))?;
+		seed.deserialize(value)
+	}
+}
+
+/// Map access over the fields of a struct that are set.
+struct Fields {
+	iter: vec::IntoIter<(String, Value)>,
+	value: Option<Value>,
+}
+
+impl<'de> MapAccess<'de> for Fields {
+	type Error = EnvflagError;
+
+	fn next_key_seed<K: DeserializeSeed<'de>>(
+		&mut self,
+		seed: K,
+	) -> Result<Option<K::Value>, Self::Error> {
+		let Some((field, value)) = self.iter.next() else {
+			return Ok(None);
+		};
+		self.value = Some(value);
+		seed.deserialize(field.into_deserializer()).map(Some)
+	}
+
+	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+		let value = self
+			.value
+			.take()
+			.ok_or_else(|| <EnvflagError as de::Error>::custom("value requested before key"))?;
+		seed.deserialize(value)
+	}
+}
+
+/// A single variable's value, with its full name for error messages.
+struct Value {
+	key: String,
+	raw: String,
+	/// Report [`MASK`](crate::redact::MASK) instead of the value in errors.
+	redact: bool,
+}
+
+impl Value {
+	fn parse<T: std::str::FromStr<Err: fmt::Display>>(&self, raw: &str) -> Result<T, EnvflagError> {
+		raw
+			.trim()
+			.parse()
+			.map_err(|e: T::Err| EnvflagError::ParseFailed {
+				key: self.key.clone(),
+				value: if self.redact {
+					crate::redact::MASK.to_owned()
+				} else {
+					self.raw.clone()
+				},
+				type_name: std::any::type_name::<T>(),
+				source_message: (!self.redact).then(|| e.to_string()),
+			})
+	}
+}
+
+/// Implements the numeric `deserialize_*` methods by parsing the raw value.
+macro_rules! deserialize_parsed {
+	($($method:ident => $visit:ident,)*) => {
+		$(
+			fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+				visitor.$visit(self.parse(&self.raw)?)
+			}
+		)*
+	};
+}
+
+impl<'de> Deserializer<'de> for Value {
+	type Error = EnvflagError;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_string(self.raw)
+	}
+
+	fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_bool(self.parse(&normalize_bool(&self.raw))?)
+	}
+
+	deserialize_parsed! {
+		deserialize_i8 => visit_i8,
+		deserialize_i16 => visit_i16,
+		deserialize_i32 => visit_i32,
+		deserialize_i64 => visit_i64,
+		deserialize_i128 => visit_i128,
+		deserialize_u8 => visit_u8,
+		deserialize_u16 => visit_u16,
+		deserialize_u32 => visit_u32,
+		deserialize_u64 => visit_u64,
+		deserialize_u128 => visit_u128,
+		deserialize_f32 => visit_f32,
+		deserialize_f64 => visit_f64,
+		deserialize_char => visit_char,
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_some(self)
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		let items: Vec<_> = self
+			.raw
+			.split(',')
+			.map(str::trim)
+			.filter(|item| !item.is_empty())
+			.map(|item| Self {
+				key: self.key.clone(),
+				raw: item.to_owned(),
+				redact: self.redact,
+			})
+			.collect();
+		visitor.visit_seq(Items {
+			iter: items.into_iter(),
+		})
+	}
+
+	fn deserialize_enum<V: Visitor<'de>>(
+		self,
+		name: &'static str,
+		variants: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		let raw: StringDeserializer<EnvflagError> = self.raw.into_deserializer();
+		raw.deserialize_enum(name, variants, visitor)
+	}
+
+	forward_to_deserialize_any! {
+		str string bytes byte_buf unit unit_struct tuple tuple_struct map
+		struct identifier ignored_any
+	}
+}
+
+/// Sequence access over the comma-separated items of a value.
+struct Items {
+	iter: vec::IntoIter<Value>,
+}
+
+impl<'de> SeqAccess<'de> for Items {
+	type Error = EnvflagError;
+
+	fn next_element_seed<T: DeserializeSeed<'de>>(
+		&mut self,
+		seed: T,
+	) -> Result<Option<T::Value>, Self::Error> {
+		self
+			.iter
+			.next()
+			.map(|item| seed.deserialize(item))
+			.transpose()
+	}
+}
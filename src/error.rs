@@ -5,7 +5,11 @@
 use thiserror::Error;
 
 /// Errors that can occur when using the envflag crate.
+///
+/// New variants may be added in minor releases, so matches need a wildcard
+/// arm.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum EnvflagError {
 	/// The crate has already been initialized.
 	#[error("envflag is already initialized")]
@@ -25,11 +29,72 @@ pub enum EnvflagError {
 	#[error("dotenv error: {0}")]
 	Dotenv(#[from] dotenvy::Error),
 
+	/// A source produced input that could not be loaded.
+	#[error("failed to load {origin}: {message}")]
+	Source {
+		/// Describes the source, e.g. `stdin`.
+		origin: String,
+		/// What went wrong.
+		message: String,
+	},
+
+	/// A schema document could not be parsed.
+	#[error("invalid schema: {message}")]
+	InvalidSchema {
+		/// What was wrong with the document.
+		message: String,
+	},
+
+	/// Keys passed to [`InitBuilder::require`](crate::InitBuilder::require)
+	/// are not set.
+	#[error("missing required environment variables: {}", keys.join(", "))]
+	MissingKeys {
+		/// Every required key that is not set, in declaration order.
+		keys: Vec<String>,
+	},
+
+	/// The loaded configuration does not match the schema passed to
+	/// [`InitBuilder::schema`](crate::InitBuilder::schema).
+	#[error("configuration does not match the schema: {}", problems.join("; "))]
+	SchemaViolation {
+		/// One line per missing or invalid key, with values redacted.
+		problems: Vec<String>,
+	},
+
+	/// The registered migrations do not form a valid chain.
+	#[error("invalid migration: {message}")]
+	InvalidMigration {
+		/// What was wrong with the migrations.
+		message: String,
+	},
+
+	/// The builder combines options that cannot be used together.
+	#[error("incompatible builder options: {message}")]
+	IncompatibleOptions {
+		/// Which options conflict.
+		message: String,
+	},
+
+	/// A command-line override was not of the form `KEY=VALUE`.
+	#[error("invalid command-line override '{arg}': expected KEY=VALUE")]
+	InvalidOverride {
+		/// The offending argument.
+		arg: String,
+	},
+
 	/// The requested environment variable is not set.
-	#[error("environment variable '{key}' is not set")]
+	#[error(
+		"environment variable '{key}' is not set{}",
+		not_set_hints(key, tried, suggestion.as_deref())
+	)]
 	NotSet {
 		/// The key that was not found.
 		key: String,
+		/// The full variable names that were looked up, with prefixes and
+		/// fallback names, in order.
+		tried: Vec<String>,
+		/// A loaded variable with a similar name, likely the intended one.
+		suggestion: Option<String>,
 	},
 
 	/// Multiple prefixes are configured but no explicit prefix was specified.
@@ -42,20 +107,58 @@ pub enum EnvflagError {
 	},
 
 	/// Validation failed for the environment variable.
-	#[error("validation failed for key '{key}' with value '{value}'")]
+	#[error(
+		"validation failed for key '{key}' with value '{value}'{}",
+		message.as_ref().map(|m| format!(": {m}")).unwrap_or_default()
+	)]
 	ValidationFailed {
 		/// The key that failed validation.
 		key: String,
 		/// The value that failed validation.
 		value: String,
+		/// Why the value was rejected, if the validator said.
+		message: Option<String>,
+	},
+
+	/// The store could not be deserialized into the requested type.
+	#[cfg(feature = "serde")]
+	#[error("failed to deserialize configuration: {message}")]
+	Deserialize {
+		/// What the target type rejected.
+		message: String,
 	},
 
 	/// Parsing failed for the environment variable.
-	#[error("failed to parse key '{key}' with value '{value}'")]
+	#[error(
+		"failed to parse key '{key}' with value '{value}' as {type_name}{}",
+		source_message.as_ref().map(|m| format!(": {m}")).unwrap_or_default()
+	)]
 	ParseFailed {
 		/// The key that failed parsing.
 		key: String,
 		/// The value that failed parsing.
 		value: String,
+		/// The type the value was parsed into, from [`std::any::type_name`].
+		type_name: &'static str,
+		/// The parser's own error, e.g. `number too large to fit in target
+		/// type`, if it gave one.
+		source_message: Option<String>,
 	},
 }
+
+/// Formats the parenthesized hints of a `NotSet` error: the names tried,
+/// unless that was just the key itself, and the suggestion.
+fn not_set_hints(key: &str, tried: &[String], suggestion: Option<&str>) -> String {
+	let mut hints = Vec::new();
+	if tried.iter().any(|t| t != key) {
+		hints.push(format!("tried {}", tried.join(", ")));
+	}
+	if let Some(s) = suggestion {
+		hints.push(format!("did you mean {s}?"));
+	}
+	if hints.is_empty() {
+		String::new()
+	} else {
+		format!(" ({})", hints.join("; "))
+	}
+}
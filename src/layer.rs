@@ -0,0 +1,72 @@
+/* src/layer.rs */
+
+//! Introspection of the source layers a store was assembled from.
+
+use std::fmt;
+use std::time::Duration;
+
+/// The kind of source a layer was loaded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LayerKind {
+	/// Defaults compiled into the binary with
+	/// [`InitBuilder::defaults`](crate::InitBuilder::defaults).
+	Defaults,
+	/// A `.env` file parsed by `dotenvy`.
+	DotenvFile,
+	/// A directory holding one variable per file, such as Docker secrets.
+	Directory,
+	/// The process environment.
+	ProcessEnv,
+	/// Records read from a stream such as stdin.
+	Reader,
+	/// A [`Source`](crate::Source) registered with
+	/// [`InitBuilder::source`](crate::InitBuilder::source).
+	Custom,
+	/// Keys fetched through callbacks registered with
+	/// [`InitBuilder::refresh_key`](crate::InitBuilder::refresh_key).
+	Remote,
+	/// `--env KEY=VALUE` command-line overrides.
+	CommandLine,
+}
+
+impl fmt::Display for LayerKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::Defaults => "defaults",
+			Self::DotenvFile => "dotenv",
+			Self::Directory => "dir",
+			Self::ProcessEnv => "env",
+			Self::Reader => "reader",
+			Self::Custom => "source",
+			Self::Remote => "remote",
+			Self::CommandLine => "args",
+		})
+	}
+}
+
+/// Describes one source layer that contributed to a store.
+///
+/// Layers are reported in the order they were applied: later layers take
+/// precedence over earlier ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerInfo {
+	/// The kind of source.
+	pub kind: LayerKind,
+	/// Where the layer was loaded from (a file path or URL), if applicable.
+	pub origin: Option<String>,
+	/// Number of keys this layer contributed after prefix filtering.
+	pub keys: usize,
+	/// Time spent loading the layer.
+	pub load_time: Duration,
+}
+
+impl fmt::Display for LayerInfo {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.kind)?;
+		if let Some(origin) = &self.origin {
+			write!(f, " ({origin})")?;
+		}
+		write!(f, ": {} key(s) in {:?}", self.keys, self.load_time)
+	}
+}
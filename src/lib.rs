@@ -7,33 +7,129 @@
 //! ## Initialization
 //!
 //! Call [`init()`] (or use the [`builder()`]) early in `main()`, **before**
-//! spawning threads. All query functions will panic if the crate has not been
-//! initialized.
+//! spawning threads. The convenience query functions panic, naming the
+//! queried key, if the crate has not been initialized; [`key()`] returns
+//! `EnvflagError::NotInitialized` instead, and [`catch_uninitialized()`]
+//! turns the panics into that error for library code. Code that must not
+//! panic at all, such as FFI callbacks and plugins, can use the `try_`
+//! variants ([`try_get()`], [`try_lookup()`], ...), which return that error
+//! directly.
 //!
 //! ## Testing
 //!
 //! For unit tests, construct an [`EnvStore`](store::EnvStore) directly via
 //! [`EnvStore::from_map`](store::EnvStore::from_map) and call its instance
 //! methods ([`get`](store::EnvStore::get), [`key`](store::EnvStore::key),
-//! etc.) instead of the global functions. This avoids the `OnceLock` and
-//! gives each test its own isolated store.
+//! etc.) instead of the global functions. This avoids the global instance
+//! and gives each test its own isolated store.
+//!
+//! Code that calls the global functions internally can be tested without
+//! arranging initialization by enabling the `test-default` feature for tests
+//! only: queries made before initialization then see an empty per-thread
+//! store instead of panicking.
+//!
+//! ```toml
+//! [dev-dependencies]
+//! envflag = { version = "*", features = ["test-default"] }
+//! ```
+//!
+//! Code that calls the global functions can also be tested in parallel, each
+//! test with its own configuration: [`with_store`] runs a closure with a
+//! given store standing in for the global on the current thread. With the
+//! `derive` feature, `#[envflag::test(vars(PORT = "8080"))]` in place of
+//! `#[test]` runs the whole test that way.
+//!
+//! Integration tests of the global API itself can share one test binary with
+//! the `test-util` feature: `envflag::reset_for_tests()` returns envflag to its
+//! uninitialized state between tests.
 
+/// Loading parameters from AWS Systems Manager Parameter Store.
+#[cfg(feature = "aws")]
+pub mod aws;
+/// Build-script helpers for compiling defaults into a binary.
+#[cfg(feature = "build")]
+pub mod build;
 /// Chained query builder for environment variables.
 pub mod builder;
+/// Struct-based loading of configuration.
+pub mod config;
+/// A serde `Deserializer` over the loaded variables.
+#[cfg(feature = "serde")]
+mod de;
+/// Rich error reports through `miette`.
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
 /// Error types for the crate.
 pub mod error;
+/// Introspection of the source layers a store was assembled from.
+pub mod layer;
+/// Versioned rewrites of the loaded variables.
+pub mod migration;
+/// Rewriting raw values into the form a type's `FromStr` accepts.
+pub mod normalize;
+/// Parsers for structured values used by the builder terminals.
+mod parse;
+/// Masking secret values for logs, diagnostics, and bug reports.
+pub mod redact;
+/// Reloading the global store and reporting what changed.
+pub mod reload;
+/// The effective configuration, for printing at startup.
+pub mod report;
+/// Declarative descriptions of the variables an application expects.
+pub mod schema;
+/// A wrapper that keeps secret values out of logs.
+mod secret;
+/// Shadow reads: comparing a secondary configuration against the primary.
+pub mod shadow;
+/// Source layers beyond the `.env` file and the process environment.
+mod source;
 /// Internal environment storage and initialization.
 pub mod store;
+/// Typed `static` key declarations with cached parsing.
+mod typed_key;
+/// Diagnostics for queries made before initialization.
+mod uninit;
+/// Tracking which variables the application actually read.
+mod usage;
 /// Built-in validation functions.
 pub mod validators;
+/// Decrypting `.env.vault` files.
+#[cfg(feature = "vault")]
+mod vault;
+/// Deduplication of per-key warnings.
+pub mod warn;
+/// Reloading when a `.env` file changes on disk.
+#[cfg(feature = "watch")]
+mod watch;
 
-use std::any::TypeId;
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::{Arc, PoisonError};
 
-pub use builder::{KeyBuilder, TypedKeyBuilder};
+pub use builder::{KeyBuilder, RequiredKeyBuilder, TypedKeyBuilder};
+pub use config::EnvConfig;
+#[cfg(feature = "diagnostics")]
+pub use diagnostics::SourcedError;
+#[cfg(feature = "derive")]
+pub use envflag_derive::{EnvConfig, static_schema, test};
 pub use error::EnvflagError;
+pub use layer::{LayerInfo, LayerKind};
+pub use reload::{ReloadDiff, ReloadSubscription};
+pub use report::{ConfigReport, ReportEntry};
+pub use schema::{KeySpec, Schema};
+pub use secret::{Secret, SecretValue};
+pub use source::{Format, Source};
 pub use store::InitBuilder;
+pub use typed_key::Key;
+pub use uninit::catch_uninitialized;
+pub use usage::DefaultReason;
+pub use warn::{WarnPolicy, deprecated_usage, set_warn_policy};
+
+/// Support code for `#[derive(EnvConfig)]`. Not public API.
+#[doc(hidden)]
+pub mod __private {
+	pub use crate::config::{field, field_or, list_field, optional_field};
+}
 
 /// Initializes the environment loader using the default `.env` file and system env.
 ///
@@ -73,14 +169,94 @@ pub fn builder() -> InitBuilder {
 	InitBuilder::new()
 }
 
-/// Starts a chained query for an environment variable.
+/// Returns `true` once the application has initialized envflag, so library
+/// code can check before using the panicking convenience functions.
 ///
-/// This is the **recommended** API. Use it with `.default()` / `.required()`
-/// and optional `.validate()` calls.
+/// # Examples
 ///
-/// # Panics
+/// ```rust
+/// assert!(!envflag::is_initialized());
+/// envflag::builder().args_overrides(["-e", "PORT=80"]).init().unwrap();
+/// assert!(envflag::is_initialized());
+/// assert_eq!(envflag::try_store().unwrap().lookup_string("PORT").as_deref(), Some("80"));
+/// ```
+#[must_use]
+pub fn is_initialized() -> bool {
+	store::INSTANCE
+		.read()
+		.unwrap_or_else(PoisonError::into_inner)
+		.is_some()
+}
+
+/// Returns the global store, or `None` instead of panicking if envflag has
+/// not been initialized.
 ///
-/// Panics if the crate has not been initialized.
+/// The store is a snapshot: [`reload()`] installs a new one, which the
+/// next call returns.
+#[must_use]
+pub fn try_store() -> Option<Arc<store::EnvStore>> {
+	store::EnvStore::get_instance().ok()
+}
+
+/// Runs `f` with `store` standing in for the global configuration on the
+/// current thread.
+///
+/// Every global query inside `f` ([`get`], [`key`], [`Key::get`], …) reads
+/// `store` instead of the initialized instance, or instead of panicking if
+/// there is none. Other threads, including ones spawned by `f`, are not
+/// affected, so tests can run in parallel with a configuration each. Calls
+/// nest; the innermost store wins. [`reload()`] and file watching only
+/// update the global.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use envflag::store::EnvStore;
+///
+/// let store = EnvStore::from_map(HashMap::from([("WORKERS".into(), "8".into())]));
+/// let workers = envflag::with_store(store, || envflag::get("WORKERS", 1u8));
+/// assert_eq!(workers, 8);
+/// assert!(!envflag::is_initialized());
+/// ```
+pub fn with_store<R>(store: store::EnvStore, f: impl FnOnce() -> R) -> R {
+	store::EnvStore::scoped(Arc::new(store), f)
+}
+
+/// Returns envflag to its uninitialized state, so the next [`init()`] or
+/// [`InitBuilder::init`] succeeds again.
+///
+/// Also forgets reload callbacks and deprecated alias usage. Background watchers and refreshers of the old configuration
+/// stop on their next tick. This lets integration tests of the global API
+/// share one test binary; run such tests serially, since the global is still
+/// shared between threads. Requires the `test-util` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// envflag::builder().args_overrides(["-e", "MODE=a"]).init().unwrap();
+/// envflag::reset_for_tests();
+/// assert!(!envflag::is_initialized());
+///
+/// envflag::builder().args_overrides(["-e", "MODE=b"]).init().unwrap();
+/// assert_eq!(envflag::get_string("MODE", ""), "b");
+/// ```
+#[cfg(feature = "test-util")]
+pub fn reset_for_tests() {
+	*store::INSTANCE
+		.write()
+		.unwrap_or_else(PoisonError::into_inner) = None;
+	reload::clear_subscribers();
+	warn::clear_deprecated();
+	warn::reset();
+}
+
+/// Starts a chained query for an environment variable.
+///
+/// This is the **recommended** API. Use it with `.default()` / `.required()`
+/// and optional `.validate()` calls. Unlike the convenience functions, it does
+/// not panic before initialization: terminal methods return
+/// `EnvflagError::NotInitialized`.
 ///
 /// # Examples
 ///
@@ -109,7 +285,7 @@ pub fn key(name: &str) -> KeyBuilder<'_> {
 ///
 /// Panics if the crate has not been initialized.
 pub fn get<T: FromStr + 'static>(name: &str, default: T) -> T {
-	let store = store::EnvStore::get_instance().expect("envflag is not initialized");
+	let store = uninit::global("get", Some(name));
 	store.get(name, default)
 }
 
@@ -120,7 +296,7 @@ pub fn get<T: FromStr + 'static>(name: &str, default: T) -> T {
 /// Panics if the crate has not been initialized.
 #[must_use]
 pub fn get_string(name: &str, default: &str) -> String {
-	let store = store::EnvStore::get_instance().expect("envflag is not initialized");
+	let store = uninit::global("get_string", Some(name));
 	store.get_string(name, default)
 }
 
@@ -131,7 +307,7 @@ pub fn get_string(name: &str, default: &str) -> String {
 /// Panics if the crate has not been initialized.
 #[must_use]
 pub fn lookup<T: FromStr + 'static>(name: &str) -> Option<T> {
-	let store = store::EnvStore::get_instance().expect("envflag is not initialized");
+	let store = uninit::global("lookup", Some(name));
 	store.lookup_parsed(name)
 }
 
@@ -142,7 +318,7 @@ pub fn lookup<T: FromStr + 'static>(name: &str) -> Option<T> {
 /// Panics if the crate has not been initialized.
 #[must_use]
 pub fn lookup_string(name: &str) -> Option<String> {
-	let store = store::EnvStore::get_instance().expect("envflag is not initialized");
+	let store = uninit::global("lookup_string", Some(name));
 	store.lookup_string(name)
 }
 
@@ -153,25 +329,404 @@ pub fn lookup_string(name: &str) -> Option<String> {
 /// Panics if the crate has not been initialized.
 #[must_use]
 pub fn is_set(name: &str) -> bool {
-	let store = store::EnvStore::get_instance().expect("envflag is not initialized");
+	let store = uninit::global("is_set", Some(name));
 	store.is_set(name)
 }
 
+/// Like [`get`], but returns an error instead of panicking before initialization.
+///
+/// # Errors
+///
+/// Returns `EnvflagError::NotInitialized` if the crate has not been
+/// initialized.
+pub fn try_get<T: FromStr + 'static>(name: &str, default: T) -> Result<T, EnvflagError> {
+	Ok(store::EnvStore::get_instance()?.get(name, default))
+}
+
+/// Like [`get_string`], but returns an error instead of panicking before initialization.
+///
+/// # Errors
+///
+/// Returns `EnvflagError::NotInitialized` if the crate has not been
+/// initialized.
+pub fn try_get_string(name: &str, default: &str) -> Result<String, EnvflagError> {
+	Ok(store::EnvStore::get_instance()?.get_string(name, default))
+}
+
+/// Like [`lookup`], but returns an error instead of panicking before initialization.
+///
+/// # Errors
+///
+/// Returns `EnvflagError::NotInitialized` if the crate has not been
+/// initialized.
+pub fn try_lookup<T: FromStr + 'static>(name: &str) -> Result<Option<T>, EnvflagError> {
+	Ok(store::EnvStore::get_instance()?.lookup_parsed(name))
+}
+
+/// Like [`lookup_string`], but returns an error instead of panicking before initialization.
+///
+/// # Errors
+///
+/// Returns `EnvflagError::NotInitialized` if the crate has not been
+/// initialized.
+pub fn try_lookup_string(name: &str) -> Result<Option<String>, EnvflagError> {
+	Ok(store::EnvStore::get_instance()?.lookup_string(name))
+}
+
+/// Like [`is_set`], but returns an error instead of panicking before initialization.
+///
+/// # Errors
+///
+/// Returns `EnvflagError::NotInitialized` if the crate has not been
+/// initialized.
+pub fn try_is_set(name: &str) -> Result<bool, EnvflagError> {
+	Ok(store::EnvStore::get_instance()?.is_set(name))
+}
+
 /// Returns all environment variables in the store.
 ///
 /// **Warning:** This returns all stored entries including potentially sensitive
 /// values (e.g. `DATABASE_URL`, `SECRET_KEY`). If you need to expose these
-/// (e.g. for debugging), consider filtering or redacting secrets yourself.
+/// (e.g. for debugging), use [`entries_redacted`] instead.
 ///
 /// # Panics
 ///
 /// Panics if the crate has not been initialized.
 #[must_use]
 pub fn entries() -> Vec<(String, String)> {
-	let store = store::EnvStore::get_instance().expect("envflag is not initialized");
+	let store = uninit::global("entries", None);
 	store.entries()
 }
 
+/// Returns all environment variables in the store with secrets masked.
+///
+/// Keys that look secret (`*_KEY`, `*_TOKEN`, `*_PASSWORD`, `*_SECRET`, ...)
+/// and keys matching any of `patterns`, such as `"*_DSN"`, have their values
+/// replaced by [`redact::MASK`]; passwords in URLs are masked everywhere.
+///
+/// # Panics
+///
+/// Panics if the crate has not been initialized.
+///
+/// # Examples
+///
+/// ```rust
+/// envflag::builder()
+///     .args_overrides(["-e", "API_TOKEN=abc", "-e", "SENTRY_DSN=https://x@sentry"])
+///     .init()
+///     .unwrap();
+/// let entries = envflag::entries_redacted(&["*_DSN"]);
+/// let value = |key: &str| entries.iter().find(|(k, _)| k == key).unwrap().1.clone();
+/// assert_eq!(value("API_TOKEN"), envflag::redact::MASK);
+/// assert_eq!(value("SENTRY_DSN"), envflag::redact::MASK);
+/// ```
+#[must_use]
+pub fn entries_redacted(patterns: &[&str]) -> Vec<(String, String)> {
+	let store = uninit::global("entries_redacted", None);
+	store.entries_redacted(patterns)
+}
+
+/// Returns the loaded variables that were never read, by full name, e.g.
+/// `APP_PROT` when the application queries `PORT`.
+///
+/// Call it once startup has read its configuration; see
+/// [`EnvStore::unused_keys`](store::EnvStore::unused_keys).
+///
+/// # Panics
+///
+/// Panics if the crate has not been initialized.
+///
+/// # Examples
+///
+/// ```rust
+/// envflag::builder()
+///     .prefix("APP_")
+///     .args_overrides(["-e", "APP_PORT=80", "-e", "APP_PROT=81"])
+///     .init()
+///     .unwrap();
+/// let _port: u16 = envflag::key("PORT").required().unwrap();
+/// assert_eq!(envflag::unused_keys(), ["APP_PROT"]);
+/// ```
+#[cfg(feature = "usage")]
+#[must_use]
+pub fn unused_keys() -> Vec<String> {
+	let store = uninit::global("unused_keys", None);
+	store.unused_keys()
+}
+
+/// Returns every key whose query on the global store fell back to its
+/// default, with the reason, sorted by key; see
+/// [`EnvStore::defaulted_keys`](store::EnvStore::defaulted_keys).
+///
+/// # Panics
+///
+/// Panics if the crate has not been initialized.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::DefaultReason;
+///
+/// envflag::builder().prefix("DEFAULTED_").init().unwrap();
+/// assert_eq!(envflag::get("WORKERS", 4u8), 4);
+/// assert_eq!(
+///     envflag::defaulted_keys(),
+///     [("WORKERS".to_owned(), DefaultReason::Missing)]
+/// );
+/// ```
+#[cfg(feature = "usage")]
+#[must_use]
+pub fn defaulted_keys() -> Vec<(String, DefaultReason)> {
+	let store = uninit::global("defaulted_keys", None);
+	store.defaulted_keys()
+}
+
+/// Returns every variable the application has read from the global store,
+/// with the number of reads, sorted by name; see
+/// [`EnvStore::usage_report`](store::EnvStore::usage_report).
+///
+/// # Panics
+///
+/// Panics if the crate has not been initialized.
+///
+/// # Examples
+///
+/// ```rust
+/// envflag::builder()
+///     .args_overrides(["-e", "USAGE_PORT=80"])
+///     .init()
+///     .unwrap();
+/// let _port: u16 = envflag::key("USAGE_PORT").required().unwrap();
+/// assert_eq!(envflag::usage_report(), [("USAGE_PORT".to_owned(), 1)]);
+/// ```
+#[cfg(feature = "usage")]
+#[must_use]
+pub fn usage_report() -> Vec<(String, u64)> {
+	let store = uninit::global("usage_report", None);
+	store.usage_report()
+}
+
+/// Returns every loaded variable with the layer it came from and its value
+/// redacted; see [`ConfigReport`].
+///
+/// # Panics
+///
+/// Panics if the crate has not been initialized.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::LayerKind;
+///
+/// envflag::builder()
+///     .prefix("BOOT_")
+///     .args_overrides(["-e", "BOOT_PORT=9000"])
+///     .init()
+///     .unwrap();
+/// let report = envflag::report();
+/// assert_eq!(report.entries[0].key, "BOOT_PORT");
+/// assert_eq!(report.entries[0].source, Some(LayerKind::CommandLine));
+/// ```
+#[must_use]
+pub fn report() -> ConfigReport {
+	let store = uninit::global("report", None);
+	ConfigReport::of(&store)
+}
+
+/// Prints the [`report()`] to standard error as an aligned table, the
+/// "effective configuration" banner to show at startup.
+///
+/// # Panics
+///
+/// Panics if the crate has not been initialized.
+pub fn print_report() {
+	eprintln!("{}", report());
+}
+
+/// Returns the source layers the global store was assembled from.
+///
+/// # Panics
+///
+/// Panics if the crate has not been initialized.
+#[must_use]
+pub fn layers() -> Vec<LayerInfo> {
+	let store = uninit::global("layers", None);
+	store.layers().to_vec()
+}
+
+/// Returns the active profile, e.g. `"production"`, so application code can
+/// branch on the environment it runs in.
+///
+/// Set with [`InitBuilder::profile`], or detected from `APP_ENV` or
+/// `RUST_ENV`.
+///
+/// # Panics
+///
+/// Panics if the crate has not been initialized.
+#[must_use]
+pub fn profile() -> Option<String> {
+	let store = uninit::global("profile", None);
+	store.profile().map(str::to_owned)
+}
+
+/// Returns the layer that supplied the value of `name`, e.g. to tell whether
+/// a setting came from `.env.local` or the process environment.
+///
+/// # Panics
+///
+/// Panics if the crate has not been initialized.
+///
+/// # Examples
+///
+/// ```rust
+/// # fn main() -> Result<(), envflag::EnvflagError> {
+/// use envflag::LayerKind;
+///
+/// envflag::builder().args_overrides(["-e", "PORT=9000"]).init()?;
+/// let layer = envflag::layer_of("PORT").unwrap();
+/// assert_eq!(layer.kind, LayerKind::CommandLine);
+/// # Ok(())
+/// # }
+/// ```
+#[must_use]
+pub fn layer_of(name: &str) -> Option<LayerInfo> {
+	let store = uninit::global("layer_of", Some(name));
+	store.layer_of(name).cloned()
+}
+
+/// Checks every key declared in `schema` against the global store, so all
+/// misconfigurations are reported in one run instead of one at a time.
+///
+/// # Errors
+///
+/// Returns `EnvflagError::SchemaViolation` listing every missing or invalid
+/// key, with values redacted.
+///
+/// # Panics
+///
+/// Panics if the crate has not been initialized.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::{EnvflagError, KeySpec, Schema};
+///
+/// envflag::builder()
+///     .args_overrides(["-e", "CHECK_PORT=http"])
+///     .init()
+///     .unwrap();
+/// let schema = Schema::new()
+///     .key(KeySpec::new("CHECK_PORT").type_name("u16"))
+///     .key(KeySpec::new("CHECK_URL").required());
+/// let Err(EnvflagError::SchemaViolation { problems }) = envflag::check(&schema) else {
+///     panic!("expected a schema violation");
+/// };
+/// assert_eq!(problems.len(), 2);
+/// ```
+pub fn check(schema: &Schema) -> Result<(), EnvflagError> {
+	let store = uninit::global("check", None);
+	store.check(schema)
+}
+
+/// Returns how the shadow configuration disagreed with the global store at
+/// the last load, or `None` if no shadow is configured.
+///
+/// # Panics
+///
+/// Panics if the crate has not been initialized.
+#[must_use]
+pub fn shadow_report() -> Option<shadow::ShadowReport> {
+	let store = uninit::global("shadow_report", None);
+	store.shadow_report().cloned()
+}
+
+/// Reloads the global store from the same sources it was initialized with.
+///
+/// The new snapshot replaces the old one atomically; queries already in
+/// flight keep the snapshot they started with. Every callback registered via
+/// [`on_reload()`] is invoked with the resulting diff, and with the `tracing`
+/// feature the changed key names (never values) are logged.
+///
+/// # Errors
+///
+/// Returns `EnvflagError::NotInitialized` if the crate has not been
+/// initialized, or any error the sources produce while loading. On error the
+/// current store is left in place.
+pub fn reload() -> Result<ReloadDiff, EnvflagError> {
+	reload::reload()
+}
+
+/// Registers a callback to be run after every [`reload()`].
+///
+/// Callbacks run on the reloading thread, in registration order. A callback
+/// registered while others are running is first called on the next reload.
+/// Use the returned handle to remove it again.
+pub fn on_reload(f: impl Fn(&ReloadDiff) + Send + Sync + 'static) -> ReloadSubscription {
+	reload::subscribe(Arc::new(move |diff| {
+		f(diff);
+		true
+	}))
+}
+
+/// Returns a channel that tracks the parsed value of a variable across
+/// reloads.
+///
+/// The receiver starts with the current value and is updated after every
+/// [`reload()`] that changes it. If a reload leaves the variable missing or
+/// unparseable, the last good value is kept.
+///
+/// # Errors
+///
+/// Returns the same errors as [`KeyBuilder::required`] if the variable
+/// cannot be resolved initially.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # async fn run() -> Result<(), envflag::EnvflagError> {
+/// let mut rate_limit = envflag::watch_key::<u64>("RATE_LIMIT")?;
+/// while rate_limit.changed().await.is_ok() {
+///     println!("rate limit is now {}", *rate_limit.borrow());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "tokio")]
+pub fn watch_key<T>(name: &str) -> Result<tokio::sync::watch::Receiver<T>, EnvflagError>
+where
+	T: FromStr + PartialEq + Send + Sync + 'static,
+{
+	let initial = key(name).required::<T>()?;
+	let (tx, rx) = tokio::sync::watch::channel(initial);
+
+	let name = name.to_owned();
+	// Unregisters itself once every receiver is gone.
+	reload::subscribe(Arc::new(move |_| {
+		if tx.is_closed() {
+			return false;
+		}
+		match key(&name).required::<T>() {
+			Ok(v) => {
+				tx.send_if_modified(|cur| {
+					if *cur == v {
+						false
+					} else {
+						*cur = v;
+						true
+					}
+				});
+			}
+			Err(_e) => {
+				#[cfg(feature = "tracing")]
+				tracing::warn!(key = %name, error = %_e, "keeping last value for watched key");
+			}
+		}
+		!tx.is_closed()
+	}));
+
+	Ok(rx)
+}
+
 // ---------------------------------------------------------------------------
 // Instance methods on EnvStore — the real logic lives here.
 // ---------------------------------------------------------------------------
@@ -203,26 +758,30 @@ impl store::EnvStore {
 	///
 	/// If the variable is missing or cannot be parsed, returns `default`.
 	pub fn get<T: FromStr + 'static>(&self, name: &str, default: T) -> T {
-		match self.lookup(name, None) {
-			Some(val) => {
-				let val = if TypeId::of::<T>() == TypeId::of::<bool>() {
-					crate::validators::normalize_bool(&val)
+		let Some(val) = self.lookup(name, None) else {
+			self.record_default(name, DefaultReason::Missing);
+			return default;
+		};
+		let val = builder::normalize::<T>(val, builder::KeyOptions::default());
+		if let Ok(v) = val.parse::<T>() {
+			v
+		} else {
+			#[cfg(feature = "tracing")]
+			if let Some(suppressed) = warn::admit(name, "parse") {
+				let value: &str = if self.redacts_values_in_errors() {
+					redact::MASK
 				} else {
-					std::borrow::Cow::Borrowed(val.as_str())
+					&val
 				};
-				if let Ok(v) = val.parse::<T>() {
-					v
-				} else {
-					#[cfg(feature = "tracing")]
-					tracing::warn!(
-						key = %name,
-						value = %val,
-						"failed to parse environment variable, using default"
-					);
-					default
-				}
+				tracing::warn!(
+					key = %name,
+					value = %value,
+					suppressed,
+					"failed to parse environment variable, using default"
+				);
 			}
-			None => default,
+			self.record_default(name, DefaultReason::ParseFailed);
+			default
 		}
 	}
 
@@ -231,9 +790,10 @@ impl store::EnvStore {
 	/// If not set, returns `default`.
 	#[must_use]
 	pub fn get_string(&self, name: &str, default: &str) -> String {
-		self
-			.lookup(name, None)
-			.unwrap_or_else(|| default.to_owned())
+		self.lookup(name, None).unwrap_or_else(|| {
+			self.record_default(name, DefaultReason::Missing);
+			default.to_owned()
+		})
 	}
 
 	/// Retrieves an environment variable and parses it, returning `None` if
@@ -241,21 +801,25 @@ impl store::EnvStore {
 	#[must_use]
 	pub fn lookup_parsed<T: FromStr + 'static>(&self, name: &str) -> Option<T> {
 		self.lookup(name, None).and_then(|s| {
-			let s = if TypeId::of::<T>() == TypeId::of::<bool>() {
-				crate::validators::normalize_bool(&s)
-			} else {
-				std::borrow::Cow::Borrowed(s.as_str())
-			};
+			let s = builder::normalize::<T>(s, builder::KeyOptions::default());
 			#[allow(clippy::manual_ok_err)]
 			if let Ok(v) = s.parse::<T>() {
 				Some(v)
 			} else {
 				#[cfg(feature = "tracing")]
-				tracing::warn!(
-					key = %name,
-					value = %s,
-					"failed to parse environment variable, returning None"
-				);
+				if let Some(suppressed) = warn::admit(name, "parse") {
+					let value: &str = if self.redacts_values_in_errors() {
+						redact::MASK
+					} else {
+						&s
+					};
+					tracing::warn!(
+						key = %name,
+						value = %value,
+						suppressed,
+						"failed to parse environment variable, returning None"
+					);
+				}
 				None
 			}
 		})
@@ -278,12 +842,16 @@ impl store::EnvStore {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	// The built-in attribute, not the `envflag::test` re-exported above.
+	#[cfg(feature = "derive")]
+	use core::prelude::v1::test;
 	use std::collections::HashMap;
 	use store::EnvStore;
 
-	// This test must run in a separate process because OnceLock cannot be
-	// reset.  `cargo test` runs each test binary once; as long as no other
+	// This test must run in a separate process because the global instance
+	// cannot be reset.  `cargo test` runs each test binary once; as long as no other
 	// test in *this* binary calls init() before this test, it works.
+	#[cfg(not(feature = "test-default"))]
 	#[test]
 	#[should_panic(expected = "envflag is not initialized")]
 	fn test_panic_uninitialized() {
@@ -291,6 +859,62 @@ mod tests {
 		let _ = is_set("ANY");
 	}
 
+	#[cfg(not(feature = "test-default"))]
+	#[test]
+	fn try_getters_error_before_init() {
+		assert!(matches!(
+			try_get("PORT", 8080u16),
+			Err(EnvflagError::NotInitialized)
+		));
+		assert!(matches!(
+			try_lookup_string("PORT"),
+			Err(EnvflagError::NotInitialized)
+		));
+		assert!(matches!(
+			try_is_set("PORT"),
+			Err(EnvflagError::NotInitialized)
+		));
+	}
+
+	#[cfg(not(feature = "test-default"))]
+	#[test]
+	#[should_panic(expected = "`envflag::get(\"PORT\")` was called before initialization")]
+	fn uninitialized_panic_names_key() {
+		let _ = get("PORT", 8080u16);
+	}
+
+	#[cfg(not(feature = "test-default"))]
+	#[test]
+	fn catch_uninitialized_returns_error() {
+		assert!(matches!(
+			catch_uninitialized(|| lookup_string("ANY")),
+			Err(EnvflagError::NotInitialized)
+		));
+		assert!(matches!(
+			catch_uninitialized(|| catch_uninitialized(entries)),
+			Ok(Err(EnvflagError::NotInitialized))
+		));
+		assert_eq!(catch_uninitialized(|| 7).unwrap(), 7);
+	}
+
+	#[cfg(feature = "test-default")]
+	#[test]
+	fn test_default_queries_empty_store() {
+		assert!(!is_set("ANY"));
+		assert_eq!(get("PORT", 8080u16), 8080);
+		assert!(entries().is_empty());
+		assert!(matches!(
+			key("PORT").required::<u16>(),
+			Err(EnvflagError::NotSet { .. })
+		));
+	}
+
+	#[test]
+	#[should_panic(expected = "unrelated")]
+	fn catch_uninitialized_propagates_other_panics() {
+		let _ = catch_uninitialized(|| panic!("unrelated"));
+	}
+
 	// ---- EnvStore instance tests (no OnceLock needed) --------------------
 
 	fn make_store(pairs: &[(&str, &str)]) -> EnvStore {
@@ -327,6 +951,17 @@ mod tests {
 		assert!(!store.get::<bool>("VERBOSE", true));
 	}
 
+	#[test]
+	fn get_trims_addresses() {
+		use std::net::SocketAddr;
+
+		let store = make_store(&[("ADDR", " 127.0.0.1:80 ")]);
+		let fallback: SocketAddr = "0.0.0.0:1".parse().unwrap();
+		let addr: SocketAddr = "127.0.0.1:80".parse().unwrap();
+		assert_eq!(store.get("ADDR", fallback), addr);
+		assert_eq!(store.lookup_parsed::<SocketAddr>("ADDR"), Some(addr));
+	}
+
 	#[test]
 	fn get_string_existing() {
 		let store = make_store(&[("HOST", "localhost")]);
@@ -395,12 +1030,55 @@ mod tests {
 		);
 	}
 
-	// ---- Builder API via store.key() ------------------------------------
-
 	#[test]
-	fn key_default_existing() {
-		let store = make_store(&[("PORT", "3000")]);
-		let v: u16 = store.key("PORT").default(8080u16).get().unwrap();
+	fn entries_redacted_masks_secrets() {
+		let store = make_store(&[
+			("PORT", "8080"),
+			("DB_PASSWORD", "hunter2"),
+			("SENTRY_DSN", "https://abc@sentry.example/1"),
+		]);
+		let mut e = store.entries_redacted(&["*_dsn"]);
+		e.sort();
+		assert_eq!(
+			e,
+			vec![
+				("DB_PASSWORD".to_owned(), redact::MASK.to_owned()),
+				("PORT".to_owned(), "8080".to_owned()),
+				("SENTRY_DSN".to_owned(), redact::MASK.to_owned()),
+			]
+		);
+	}
+
+	#[test]
+	fn subset_keeps_only_named_keys() {
+		let store = EnvStore::from_map_with_prefixes(
+			HashMap::from([
+				("APP_PORT".into(), "1".into()),
+				("SVC_PORT".into(), "2".into()),
+				("APP_SECRET".into(), "x".into()),
+			]),
+			vec!["APP_".into(), "SVC_".into()],
+		);
+		let sub = store.subset(["PORT", "MISSING"]);
+		let mut e = sub.entries();
+		e.sort();
+		assert_eq!(
+			e,
+			vec![
+				("APP_PORT".to_owned(), "1".to_owned()),
+				("SVC_PORT".to_owned(), "2".to_owned()),
+			]
+		);
+		let v: u8 = sub.key("PORT").with_prefix("SVC_").required().unwrap();
+		assert_eq!(v, 2);
+	}
+
+	// ---- Builder API via store.key() ------------------------------------
+
+	#[test]
+	fn key_default_existing() {
+		let store = make_store(&[("PORT", "3000")]);
+		let v: u16 = store.key("PORT").default(8080u16).get().unwrap();
 		assert_eq!(v, 3000);
 	}
 
@@ -463,6 +1141,779 @@ mod tests {
 		assert!(v);
 	}
 
+	#[test]
+	fn key_port_range() {
+		let store = make_store(&[("PORTS", "8000-8100"), ("BAD", "8100-8000")]);
+		assert_eq!(store.key("PORTS").port_range().unwrap(), 8000..=8100);
+		let err = store.key("BAD").port_range().unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { .. }));
+	}
+
+	#[test]
+	fn key_ranges() {
+		let store = make_store(&[("JITTER", "10..250"), ("RETRY", "1..=5")]);
+		assert_eq!(store.key("JITTER").range::<u64>().unwrap(), 10..250);
+		assert_eq!(store.key("RETRY").range_inclusive::<u8>().unwrap(), 1..=5);
+		let err = store.key("RETRY").range::<u8>().unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { .. }));
+	}
+
+	#[test]
+	fn key_host_port() {
+		let store = make_store(&[
+			("UPSTREAM", "cache.internal:6379"),
+			("BAD", "cache.internal"),
+		]);
+		let (host, port) = store.key("UPSTREAM").host_port().unwrap();
+		assert_eq!((host.as_str(), port), ("cache.internal", 6379));
+		let err = store.key("BAD").host_port().unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { .. }));
+	}
+
+	#[test]
+	fn key_weighted_list() {
+		let store = make_store(&[("SPLIT", "blue=0.9,green=0.1"), ("RAW", "a=2,b=1")]);
+		let split = store.key("SPLIT").weighted_list_sum_to_one().unwrap();
+		assert_eq!(split[1], ("green".to_owned(), 0.1));
+		assert_eq!(store.key("RAW").weighted_list().unwrap().len(), 2);
+		let err = store.key("RAW").weighted_list_sum_to_one().unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { .. }));
+	}
+
+	#[test]
+	fn key_pem_inline_and_path() {
+		let pem = "-----BEGIN CERTIFICATE-----\\nMIIBszCCAVmgAwIBAgIU\\n-----END CERTIFICATE-----";
+		let path = std::env::temp_dir().join(format!("envflag-pem-{}.pem", std::process::id()));
+		std::fs::write(
+			&path,
+			"-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n",
+		)
+		.unwrap();
+
+		let store = make_store(&[
+			("INLINE", pem),
+			("FILE", path.to_str().unwrap()),
+			("BAD", "-----BEGIN KEY-----\\nsecret\\n"),
+		]);
+		let inline = store.key("INLINE").pem().unwrap();
+		assert!(inline.starts_with(b"-----BEGIN CERTIFICATE-----\nMIIB"));
+		assert!(inline.ends_with(b"-----END CERTIFICATE-----\n"));
+		assert!(store.key("FILE").pem().is_ok());
+		std::fs::remove_file(&path).unwrap();
+
+		let err = store.key("BAD").pem().unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { value, .. } if value == "<inline PEM>"));
+
+		let store = make_store(&[("DER", "MIIBszCCAVmgAwIBAgIU")]);
+		let err = store.key("DER").pem().unwrap_err();
+		assert!(
+			matches!(&err, EnvflagError::Source { origin, .. } if origin == "DER"),
+			"{err}"
+		);
+		assert!(err.to_string().contains("MIIBszCCAVmgAwIBAgIU"), "{err}");
+		let err = store.key("DER").sensitive().pem().unwrap_err();
+		assert!(!err.to_string().contains("MIIBszCCAVmgAwIBAgIU"), "{err}");
+	}
+
+	#[test]
+	fn key_lenient_numbers() {
+		let store = make_store(&[("MAX", " 1_000_000 "), ("RATIO", "0,25")]);
+		let max: u64 = store.key("MAX").lenient_numbers().required().unwrap();
+		assert_eq!(max, 1_000_000);
+		let ratio = store
+			.key("RATIO")
+			.lenient_numbers()
+			.default(1.0f64)
+			.validate(|s| s == "0.25")
+			.get()
+			.unwrap();
+		assert!((ratio - 0.25).abs() < f64::EPSILON);
+		assert!(store.key("MAX").required::<u64>().is_err());
+	}
+
+	#[test]
+	fn key_or_key_tries_names_in_order() {
+		let store = make_store(&[("LEGACY_PORT", "8000"), ("OLD_PORT", "7000")]);
+		let port: u16 = store
+			.key("PORT")
+			.or_key("LEGACY_PORT")
+			.or_key("OLD_PORT")
+			.required()
+			.unwrap();
+		assert_eq!(port, 8000);
+		let port = store
+			.key("PORT")
+			.or_key("MISSING")
+			.default(80u16)
+			.get()
+			.unwrap();
+		assert_eq!(port, 80);
+
+		let store = make_store(&[("PORT", "9000"), ("LEGACY_PORT", "http")]);
+		let port: u16 = store.key("PORT").or_key("LEGACY_PORT").required().unwrap();
+		assert_eq!(port, 9000);
+		let err = store
+			.key("NEW_PORT")
+			.or_key("LEGACY_PORT")
+			.required::<u16>()
+			.unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { key, .. } if key == "LEGACY_PORT"));
+		let err = store.key("A").or_key("B").required::<u16>().unwrap_err();
+		assert!(matches!(err, EnvflagError::NotSet { key, .. } if key == "A"));
+	}
+
+	#[test]
+	fn key_deprecated_alias_is_recorded() {
+		let store = make_store(&[("DEPRECATED_OLD", "5"), ("DEPRECATED_NEW2", "6")]);
+		let n: u8 = store
+			.key("DEPRECATED_NEW")
+			.deprecated_alias("DEPRECATED_OLD")
+			.required()
+			.unwrap();
+		assert_eq!(n, 5);
+		let n = store
+			.key("DEPRECATED_NEW2")
+			.deprecated_alias("DEPRECATED_OLD2")
+			.default(0u8)
+			.get()
+			.unwrap();
+		assert_eq!(n, 6);
+
+		let usage = deprecated_usage();
+		assert!(usage.contains(&("DEPRECATED_OLD".to_owned(), "DEPRECATED_NEW".to_owned())));
+		assert!(!usage.iter().any(|(alias, _)| alias == "DEPRECATED_OLD2"));
+	}
+
+	#[test]
+	fn key_optional_distinguishes_missing_from_invalid() {
+		let store = make_store(&[("PORT", "8080"), ("BAD", "http")]);
+		assert_eq!(store.key("PORT").optional::<u16>().unwrap(), Some(8080));
+		assert_eq!(store.key("MISSING").optional::<u16>().unwrap(), None);
+		let err = store.key("BAD").optional::<u16>().unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { .. }));
+	}
+
+	#[test]
+	fn key_validate_then_required() {
+		let store = make_store(&[("PORT", "8080"), ("ZERO", "0")]);
+		let port: u16 = store
+			.key("PORT")
+			.validate(validators::is_port)
+			.validate(|s| s.starts_with('8'))
+			.required()
+			.unwrap();
+		assert_eq!(port, 8080);
+
+		let err = store
+			.key("ZERO")
+			.validate(validators::is_port)
+			.required::<u16>()
+			.unwrap_err();
+		assert!(matches!(err, EnvflagError::ValidationFailed { key, .. } if key == "ZERO"));
+		let err = store
+			.key("MISSING")
+			.validate(validators::is_port)
+			.required::<u16>()
+			.unwrap_err();
+		assert!(matches!(err, EnvflagError::NotSet { .. }));
+
+		let port = store
+			.key("MISSING")
+			.validate(validators::is_port)
+			.optional::<u16>();
+		assert_eq!(port.unwrap(), None);
+		let err = store
+			.key("ZERO")
+			.validate(validators::is_port)
+			.optional::<u16>()
+			.unwrap_err();
+		assert!(matches!(err, EnvflagError::ValidationFailed { .. }));
+	}
+
+	#[test]
+	fn key_parse_with_custom_parser() {
+		let store = make_store(&[("SIZE", "640x480"), ("BAD", "640")]);
+		let parse = |s: &str| -> Result<(u32, u32), String> {
+			let (w, h) = s.split_once('x').ok_or("expected WxH")?;
+			Ok((
+				w.parse().map_err(|_| "bad width")?,
+				h.parse().map_err(|_| "bad height")?,
+			))
+		};
+		assert_eq!(store.key("SIZE").parse_with(parse).unwrap(), (640, 480));
+		let err = store.key("BAD").parse_with(parse).unwrap_err();
+		assert!(
+			matches!(err, EnvflagError::ParseFailed { key, value, .. } if key == "BAD" && value == "640")
+		);
+		let err = store.key("MISSING").parse_with(parse).unwrap_err();
+		assert!(matches!(err, EnvflagError::NotSet { .. }));
+	}
+
+	#[test]
+	fn key_default_fn_runs_only_when_unset() {
+		let store = make_store(&[("WORKERS", "4")]);
+		let workers = store
+			.key("WORKERS")
+			.default_fn(|| -> u8 { panic!("default computed for a set key") })
+			.get()
+			.unwrap();
+		assert_eq!(workers, 4);
+		let host = store
+			.key("HOST")
+			.default_fn(|| "computed".to_owned())
+			.get()
+			.unwrap();
+		assert_eq!(host, "computed");
+	}
+
+	#[test]
+	fn key_validate_parsed_checks_typed_value() {
+		let store = make_store(&[("PORT", "8080"), ("LOW", "80")]);
+		let port = store
+			.key("PORT")
+			.default(9000u16)
+			.validate_parsed(|p| *p > 1024)
+			.get()
+			.unwrap();
+		assert_eq!(port, 8080);
+		let err = store
+			.key("LOW")
+			.default(9000u16)
+			.validate_parsed(|p| *p > 1024)
+			.get()
+			.unwrap_err();
+		assert!(
+			matches!(err, EnvflagError::ValidationFailed { key, value, message: None } if key == "LOW" && value == "80")
+		);
+		let port = store
+			.key("MISSING")
+			.default(80u16)
+			.validate_parsed(|p| *p > 1024)
+			.get()
+			.unwrap();
+		assert_eq!(port, 80);
+	}
+
+	#[test]
+	fn key_validate_with_carries_message() {
+		let store = make_store(&[("PORT", "70000")]);
+		let in_range = |s: &str| {
+			s.parse::<u32>()
+				.is_ok_and(|p| (1..=65535).contains(&p))
+				.then_some(())
+				.ok_or_else(|| "must be between 1 and 65535".to_owned())
+		};
+		let err = store
+			.key("PORT")
+			.default(80u16)
+			.validate_with(in_range)
+			.get()
+			.unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"validation failed for key 'PORT' with value '70000': must be between 1 and 65535"
+		);
+		let err = store
+			.key("PORT")
+			.validate(|s| !s.is_empty())
+			.validate_with(in_range)
+			.required::<u32>()
+			.unwrap_err();
+		assert!(matches!(
+			err,
+			EnvflagError::ValidationFailed {
+				message: Some(_),
+				..
+			}
+		));
+		let err = store
+			.key("PORT")
+			.default(80u32)
+			.validate(|_| false)
+			.get()
+			.unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"validation failed for key 'PORT' with value '70000'"
+		);
+	}
+
+	#[cfg(feature = "humantime")]
+	#[test]
+	fn key_as_duration() {
+		use std::time::Duration;
+
+		let store = make_store(&[("TIMEOUT", "30s"), ("TTL", "1h 30m"), ("BAD", "30")]);
+		assert_eq!(
+			store.key("TIMEOUT").as_duration().unwrap(),
+			Duration::from_secs(30)
+		);
+		assert_eq!(
+			store.key("TTL").as_duration().unwrap(),
+			Duration::from_secs(5400)
+		);
+		let err = store.key("BAD").as_duration().unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { .. }));
+	}
+
+	#[test]
+	fn key_as_bytes() {
+		let store = make_store(&[("UPLOAD", "512MB"), ("CACHE", "2GiB"), ("BAD", "lots")]);
+		assert_eq!(store.key("UPLOAD").as_bytes().unwrap(), 512_000_000);
+		assert_eq!(
+			store.key("UPLOAD").binary_units().as_bytes().unwrap(),
+			512 << 20
+		);
+		assert_eq!(store.key("CACHE").as_bytes().unwrap(), 2 << 30);
+		let err = store.key("BAD").as_bytes().unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { .. }));
+	}
+
+	#[test]
+	fn key_list_parses_each_item() {
+		let store = make_store(&[
+			("HOSTS", "a.example, b.example,"),
+			("PORTS", "80;443; 8080"),
+			("BAD", "1,2,x"),
+		]);
+		let hosts: Vec<String> = store.key("HOSTS").list().unwrap();
+		assert_eq!(hosts, ["a.example", "b.example"]);
+		let ports: Vec<u16> = store.key("PORTS").delimiter(';').list().unwrap();
+		assert_eq!(ports, [80, 443, 8080]);
+		let err = store.key("BAD").list::<u8>().unwrap_err();
+		assert!(
+			matches!(err, EnvflagError::ParseFailed { key, value, .. } if key == "BAD[2]" && value == "x")
+		);
+		let err = store.key("MISSING").list::<u8>().unwrap_err();
+		assert!(matches!(err, EnvflagError::NotSet { .. }));
+	}
+
+	#[test]
+	fn key_map_values_parses_each_entry() {
+		let store = make_store(&[
+			("FLAGS", "search=true, beta = no,"),
+			("LIMITS", "a=1;b=2"),
+			("BAD", "a=1,b=x"),
+			("NO_EQ", "a=1,b"),
+			("DUP", "a=1,a=2"),
+		]);
+		let flags: HashMap<String, bool> = store.key("FLAGS").map_values().unwrap();
+		assert_eq!(
+			flags,
+			HashMap::from([("search".to_owned(), true), ("beta".to_owned(), false)])
+		);
+		let limits: HashMap<String, u8> = store.key("LIMITS").delimiter(';').map_values().unwrap();
+		assert_eq!(limits.len(), 2);
+		let err = store.key("BAD").map_values::<u8>().unwrap_err();
+		assert!(
+			matches!(err, EnvflagError::ParseFailed { key, value, .. } if key == "BAD[b]" && value == "x")
+		);
+		let err = store.key("NO_EQ").map_values::<u8>().unwrap_err();
+		assert!(
+			matches!(err, EnvflagError::ParseFailed { key, value, .. } if key == "NO_EQ" && value == "b")
+		);
+		let err = store.key("DUP").map_values::<u8>().unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { value, .. } if value == "a=2"));
+	}
+
+	#[cfg(feature = "json")]
+	#[test]
+	fn key_json_deserializes_value() {
+		#[derive(serde::Deserialize, Debug, PartialEq)]
+		struct Extra {
+			retries: u8,
+			tags: Vec<String>,
+		}
+
+		let store = make_store(&[
+			("EXTRA", r#"{"retries": 3, "tags": ["a", "b"]}"#),
+			("BAD", r#"{"retries": "three"}"#),
+		]);
+		let extra: Extra = store.key("EXTRA").json().unwrap();
+		assert_eq!(
+			extra,
+			Extra {
+				retries: 3,
+				tags: vec!["a".to_owned(), "b".to_owned()],
+			}
+		);
+		let err = store.key("BAD").json::<Extra>().unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { .. }));
+	}
+
+	#[test]
+	fn key_indexed_collects_until_gap() {
+		let store = make_store(&[
+			("UPSTREAM_0", "a:80"),
+			("UPSTREAM_1", "b:80"),
+			("UPSTREAM_3", "d:80"),
+			("WEIGHT_0", "1"),
+			("WEIGHT_1", "x"),
+		]);
+		let upstreams: Vec<String> = store.key("UPSTREAM").indexed().unwrap();
+		assert_eq!(upstreams, ["a:80", "b:80"]);
+		assert!(store.key("MISSING").indexed::<u8>().unwrap().is_empty());
+		let err = store.key("WEIGHT").indexed::<u8>().unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { key, .. } if key == "WEIGHT_1"));
+	}
+
+	#[test]
+	fn key_socket_addr_default_and_validators() {
+		use std::net::{IpAddr, SocketAddr};
+
+		let store = make_store(&[
+			("BIND", " 127.0.0.1:9000 "),
+			("HOST_IP", "::1"),
+			("BAD", "localhost:80"),
+		]);
+		let default: SocketAddr = "0.0.0.0:8080".parse().unwrap();
+		let bind = store
+			.key("BIND")
+			.default(default)
+			.validate(validators::is_socket_addr)
+			.get()
+			.unwrap();
+		assert_eq!(bind, "127.0.0.1:9000".parse::<SocketAddr>().unwrap());
+		assert_eq!(
+			store.key("MISSING").default(default).get().unwrap(),
+			default
+		);
+		let ip: IpAddr = store
+			.key("HOST_IP")
+			.validate(validators::is_ip)
+			.required()
+			.unwrap();
+		assert!(ip.is_loopback());
+		let err = store.key("BAD").required::<SocketAddr>().unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { .. }));
+		assert!(!validators::is_ip("10.0.0"));
+	}
+
+	#[cfg(feature = "tracing")]
+	#[test]
+	fn key_log_level() {
+		use tracing::level_filters::LevelFilter;
+
+		let store = make_store(&[("LOG_LEVEL", "Warn"), ("QUIET", "off"), ("BAD", "3")]);
+		assert_eq!(
+			store.key("LOG_LEVEL").log_level().unwrap(),
+			LevelFilter::WARN
+		);
+		assert_eq!(store.key("QUIET").log_level().unwrap(), LevelFilter::OFF);
+		let err = store.key("BAD").log_level().unwrap_err();
+		assert!(matches!(err, EnvflagError::ValidationFailed { .. }));
+		assert!(validators::is_log_level(" ERROR "));
+		assert!(!validators::is_log_level("verbose"));
+
+		let store = make_store(&[("LOG_LEVEL", " Debug\n"), ("QUIET", "off")]);
+		assert_eq!(
+			store.key("LOG_LEVEL").log_level().unwrap(),
+			LevelFilter::DEBUG
+		);
+		assert_eq!(
+			store.key("LOG_LEVEL").tracing_level().unwrap(),
+			tracing::Level::DEBUG
+		);
+		assert!(store.key("QUIET").tracing_level().is_err());
+	}
+
+	#[cfg(feature = "log")]
+	#[test]
+	fn key_log_level_filter() {
+		let store = make_store(&[("LOG_LEVEL", " warn "), ("BAD", "2")]);
+		assert_eq!(
+			store.key("LOG_LEVEL").log_level_filter().unwrap(),
+			log::LevelFilter::Warn
+		);
+		assert!(store.key("BAD").log_level_filter().is_err());
+	}
+
+	#[cfg(feature = "chrono")]
+	#[test]
+	fn key_as_datetime_and_time() {
+		let store = make_store(&[
+			("START", "2024-05-01T02:00:00Z"),
+			("BACKUP_AT", "02:30"),
+			("BAD", "tomorrow"),
+		]);
+		assert_eq!(
+			store.key("START").as_datetime().unwrap().timestamp(),
+			1_714_528_800
+		);
+		assert_eq!(
+			store.key("BACKUP_AT").as_time().unwrap().to_string(),
+			"02:30:00"
+		);
+		assert!(matches!(
+			store.key("BAD").as_datetime().unwrap_err(),
+			EnvflagError::ParseFailed { .. }
+		));
+		assert!(validators::is_time_of_day("23:59"));
+		assert!(!validators::is_rfc3339("2024-05-01"));
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn key_as_uuid() {
+		let store = make_store(&[
+			("TENANT_ID", "67E55044-10B1-426F-9247-BB680E5FE0C8"),
+			("BAD", "67e55044-10b1-426f-9247"),
+		]);
+		let id = store.key("TENANT_ID").as_uuid().unwrap();
+		assert_eq!(id.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+		assert!(matches!(
+			store.key("BAD").as_uuid().unwrap_err(),
+			EnvflagError::ParseFailed { .. }
+		));
+	}
+
+	#[test]
+	fn is_semver_follows_the_grammar() {
+		for good in [
+			"0.0.0",
+			"1.4.0",
+			"1.0.0-alpha",
+			"1.0.0-0.3.7",
+			"1.0.0-x-y.z+001",
+			"1.0.0+20130313",
+		] {
+			assert!(validators::is_semver(good), "{good}");
+		}
+		for bad in [
+			"1",
+			"1.2",
+			"1.2.3.4",
+			"1.02.3",
+			"1.0.0-01",
+			"1.0.0-",
+			"1.0.0+",
+			"1.0.0-a..b",
+			"v1.0.0",
+			"a.b.c",
+		] {
+			assert!(!validators::is_semver(bad), "{bad}");
+		}
+	}
+
+	#[cfg(feature = "semver")]
+	#[test]
+	fn key_as_version() {
+		let store = make_store(&[("MIN_CLIENT_VERSION", "1.4.0-rc.1"), ("BAD", "1.4")]);
+		let version = store.key("MIN_CLIENT_VERSION").as_version().unwrap();
+		assert_eq!((version.major, version.minor), (1, 4));
+		assert!(matches!(
+			store.key("BAD").as_version().unwrap_err(),
+			EnvflagError::ParseFailed { .. }
+		));
+	}
+
+	#[test]
+	fn path_validators_check_the_file_system() {
+		let dir = std::env::temp_dir();
+		let file = dir.join(format!("envflag-path-validators-{}", std::process::id()));
+		std::fs::write(&file, "x").unwrap();
+		let file_str = file.to_str().unwrap();
+		let dir_str = dir.to_str().unwrap();
+
+		assert!(validators::file_exists(file_str));
+		assert!(validators::is_readable(file_str));
+		assert!(!validators::file_exists(dir_str));
+		assert!(!validators::is_readable(dir_str));
+		assert!(validators::dir_exists(dir_str));
+		assert!(!validators::dir_exists(file_str));
+		assert!(validators::path_is_absolute(file_str));
+		assert!(!validators::path_is_absolute("certs/tls.pem"));
+		std::fs::remove_file(&file).unwrap();
+		assert!(!validators::file_exists(file_str));
+		assert!(!validators::is_readable(file_str));
+	}
+
+	#[test]
+	fn key_decodes_hex_and_base64() {
+		let store = make_store(&[
+			("KEY_HEX", "deadBEEF"),
+			("KEY_B64", " 3q2+7w== "),
+			("BAD", "xyz"),
+		]);
+		assert_eq!(
+			store.key("KEY_HEX").decode_hex().unwrap(),
+			[0xde, 0xad, 0xbe, 0xef]
+		);
+		assert_eq!(
+			store.key("KEY_B64").decode_base64().unwrap(),
+			[0xde, 0xad, 0xbe, 0xef]
+		);
+		assert!(matches!(
+			store.key("BAD").decode_hex().unwrap_err(),
+			EnvflagError::ParseFailed { .. }
+		));
+		assert!(validators::is_base64("3q2-7w"));
+		assert!(!validators::is_hex("abc"));
+	}
+
+	#[test]
+	fn key_secret_is_redacted() {
+		let store = make_store(&[("API_TOKEN", "hunter2")]);
+		let token = store.key("API_TOKEN").secret().unwrap();
+		assert_eq!(token.expose(), "hunter2");
+		assert_eq!(format!("{token:?}"), "[REDACTED]");
+		assert!(matches!(
+			store.key("MISSING").secret().unwrap_err(),
+			EnvflagError::NotSet { .. }
+		));
+	}
+
+	#[test]
+	fn key_sensitive_hides_value_in_errors() {
+		let store = make_store(&[("API_KEY", "sk-live-123")]);
+		let err = store
+			.key("API_KEY")
+			.sensitive()
+			.validate(|v| v.starts_with("pk-"))
+			.required::<String>()
+			.unwrap_err();
+		assert!(!err.to_string().contains("sk-live-123"), "{err}");
+		let err = store
+			.key("API_KEY")
+			.sensitive()
+			.required::<u64>()
+			.unwrap_err();
+		assert!(
+			matches!(&err, EnvflagError::ParseFailed { value, .. } if value == redact::MASK),
+			"{err}"
+		);
+	}
+
+	#[test]
+	fn key_not_set_suggests_similar_name() {
+		let store = EnvStore::from_map_with_prefixes(
+			HashMap::from([
+				("APP_PROT".into(), "80".into()),
+				("APP_HOST".into(), "h".into()),
+			]),
+			vec!["APP_".into()],
+		);
+		let err = store.key("PORT").required::<u16>().unwrap_err();
+		assert!(
+			matches!(&err, EnvflagError::NotSet { suggestion: Some(s), .. } if s == "APP_PROT"),
+			"{err}"
+		);
+		assert_eq!(
+			err.to_string(),
+			"environment variable 'PORT' is not set (tried APP_PORT; did you mean APP_PROT?)"
+		);
+		let err = store.key("DATABASE_URL").required::<String>().unwrap_err();
+		assert!(
+			matches!(
+				&err,
+				EnvflagError::NotSet {
+					suggestion: None,
+					..
+				}
+			),
+			"{err}"
+		);
+	}
+
+	#[test]
+	fn key_not_set_lists_names_tried() {
+		let store =
+			EnvStore::from_map_with_prefix_chain(HashMap::new(), vec!["APP_".into(), "SVC_".into()]);
+		let err = store
+			.key("PORT")
+			.or_key("LISTEN_PORT")
+			.required::<u16>()
+			.unwrap_err();
+		assert!(
+			matches!(&err, EnvflagError::NotSet { tried, .. }
+				if tried == &["APP_PORT", "SVC_PORT", "APP_LISTEN_PORT", "SVC_LISTEN_PORT"]),
+			"{err}"
+		);
+
+		let err = make_store(&[]).key("PORT").required::<u16>().unwrap_err();
+		assert_eq!(err.to_string(), "environment variable 'PORT' is not set");
+	}
+
+	#[test]
+	fn key_parse_failed_names_type() {
+		let store = make_store(&[("PORT", "70000")]);
+		let err = store.key("PORT").required::<u16>().unwrap_err();
+		assert!(
+			matches!(&err, EnvflagError::ParseFailed { type_name, .. } if *type_name == "u16"),
+			"{err}"
+		);
+		assert_eq!(
+			err.to_string(),
+			"failed to parse key 'PORT' with value '70000' as u16: number too large to fit in target type"
+		);
+		let err = make_store(&[("PORT", "80a")])
+			.key("PORT")
+			.required::<u16>()
+			.unwrap_err();
+		assert!(
+			matches!(&err, EnvflagError::ParseFailed { source_message: Some(m), .. } if m == "invalid digit found in string"),
+			"{err}"
+		);
+
+		// Errors of other types need not be displayable.
+		#[derive(Debug)]
+		struct Level;
+		impl FromStr for Level {
+			type Err = ();
+			fn from_str(_: &str) -> Result<Self, ()> {
+				Err(())
+			}
+		}
+		let err = make_store(&[("LEVEL", "loud")])
+			.key("LEVEL")
+			.required::<Level>()
+			.unwrap_err();
+		assert!(
+			matches!(
+				&err,
+				EnvflagError::ParseFailed {
+					source_message: None,
+					..
+				}
+			),
+			"{err}"
+		);
+
+		// A parser's message may quote the value, so sensitive keys drop it.
+		let err = make_store(&[("TOKEN", "abc")])
+			.key("TOKEN")
+			.sensitive()
+			.parse_with(|s| Err::<u8, _>(format!("bad token {s}")))
+			.unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"failed to parse key 'TOKEN' with value '********' as u8"
+		);
+	}
+
+	#[test]
+	fn with_store_is_per_thread_and_nests() {
+		let outer = make_store(&[("SCOPED_MODE", "outer")]);
+		let seen = with_store(outer, || {
+			let inner = make_store(&[("SCOPED_MODE", "inner")]);
+			let nested = with_store(inner, || get_string("SCOPED_MODE", ""));
+			let other = std::thread::spawn(|| try_lookup_string("SCOPED_MODE"))
+				.join()
+				.unwrap();
+			assert!(!matches!(other, Ok(Some(_))));
+			(nested, get_string("SCOPED_MODE", ""))
+		});
+		assert_eq!(seen, ("inner".to_owned(), "outer".to_owned()));
+	}
+
+	#[test]
+	fn key_scaled_number() {
+		let store = make_store(&[("MAX_EVENTS", "250k"), ("HUGE", "99999999T")]);
+		assert_eq!(store.key("MAX_EVENTS").scaled_number().unwrap(), 250_000);
+		let err = store.key("HUGE").scaled_number().unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { .. }));
+	}
+
 	// ---- Prefix tests ---------------------------------------------------
 
 	#[test]
@@ -475,6 +1926,32 @@ mod tests {
 		assert_eq!(v, 3000);
 	}
 
+	#[test]
+	fn prefix_chain_priority() {
+		let store = InitBuilder::new()
+			.prefix_chain(["CHAIN_NEW_", "CHAIN_OLD_"])
+			.args_overrides([
+				"-e",
+				"CHAIN_OLD_PORT=1",
+				"-e",
+				"CHAIN_NEW_PORT=2",
+				"-e",
+				"CHAIN_OLD_HOST=old",
+			])
+			.build()
+			.unwrap();
+		assert_eq!(store.key("PORT").required::<u16>().unwrap(), 2);
+		assert_eq!(store.get_string("HOST", "none"), "old");
+		assert!(!store.is_set("MISSING"));
+		// An explicit prefix still selects exactly one variant.
+		let v: u16 = store
+			.key("PORT")
+			.with_prefix("CHAIN_OLD_")
+			.required()
+			.unwrap();
+		assert_eq!(v, 1);
+	}
+
 	#[test]
 	fn multi_prefix_explicit() {
 		let store = EnvStore::from_map_with_prefixes(
@@ -0,0 +1,205 @@
+/* src/migration.rs */
+
+//! Versioned rewrites of the loaded variables.
+//!
+//! Deployments declare the version of their env contract in
+//! `CONFIG_VERSION`. Migrations registered with
+//! [`InitBuilder::migration`](crate::InitBuilder::migration) are chained from
+//! that version up to the newest one at load time, so applications only read
+//! the current names while older manifests keep working.
+
+use std::fmt;
+use std::num::ParseIntError;
+use std::sync::Arc;
+
+use crate::error::EnvflagError;
+use crate::store::EnvStore;
+
+/// The key holding the version of a deployment's env contract.
+pub const VERSION_KEY: &str = "CONFIG_VERSION";
+
+/// A registered migration callback.
+pub(crate) type MigrateFn = Arc<dyn Fn(&mut Migration<'_>) + Send + Sync>;
+
+/// One migration step from `from` to `to`.
+#[derive(Clone)]
+pub(crate) struct MigrationStep {
+	pub(crate) from: u32,
+	pub(crate) to: u32,
+	pub(crate) f: MigrateFn,
+}
+
+impl fmt::Debug for MigrationStep {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("MigrationStep")
+			.field("from", &self.from)
+			.field("to", &self.to)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Mutable access to the loaded variables during a migration.
+///
+/// Names are short names, resolved like [`key()`](crate::key): with a
+/// single prefix configured it is added automatically; names that already
+/// start with a configured prefix are used as-is.
+#[derive(Debug)]
+pub struct Migration<'a> {
+	store: &'a mut EnvStore,
+}
+
+impl Migration<'_> {
+	/// Returns the current value of `name`.
+	#[must_use]
+	pub fn get(&self, name: &str) -> Option<&str> {
+		self
+			.store
+			.entry_map()
+			.get(&self.store.storage_key(name))
+			.map(String::as_str)
+	}
+
+	/// Sets `name` to `value`.
+	pub fn set(&mut self, name: &str, value: &str) {
+		let key = self.store.storage_key(name);
+		self.store.map_mut().insert(key, value.to_owned());
+	}
+
+	/// Removes `name`, returning its value.
+	pub fn remove(&mut self, name: &str) -> Option<String> {
+		let key = self.store.storage_key(name);
+		self.store.map_mut().remove(&key)
+	}
+
+	/// Moves the value of `from` to `to`.
+	///
+	/// If `to` is already set, the deployment has adopted the new name and
+	/// its value wins; `from` is still removed.
+	pub fn rename(&mut self, from: &str, to: &str) {
+		if let Some(value) = self.remove(from) {
+			let key = self.store.storage_key(to);
+			self.store.map_mut().entry(key).or_insert(value);
+		}
+	}
+}
+
+/// Runs every migration from the store's `CONFIG_VERSION` onwards.
+///
+/// A missing version counts as the oldest `from` of any step. The final
+/// version is written back to `CONFIG_VERSION`.
+pub(crate) fn apply(store: &mut EnvStore, steps: &[MigrationStep]) -> Result<(), EnvflagError> {
+	let Some(oldest) = steps.iter().map(|s| s.from).min() else {
+		return Ok(());
+	};
+
+	let key = store.storage_key(VERSION_KEY);
+	let mut version = match store.entry_map().get(&key) {
+		Some(v) => v
+			.trim()
+			.parse()
+			.map_err(|e: ParseIntError| EnvflagError::ParseFailed {
+				key: VERSION_KEY.to_owned(),
+				value: v.clone(),
+				type_name: std::any::type_name_of_val(&oldest),
+				source_message: Some(e.to_string()),
+			})?,
+		None => oldest,
+	};
+
+	while let Some(step) = steps.iter().find(|s| s.from == version) {
+		(step.f)(&mut Migration { store: &mut *store });
+		version = step.to;
+	}
+	store.map_mut().insert(key, version.to_string());
+	Ok(())
+}
+
+/// Checks that steps only move forward and never start from the same
+/// version twice, which would make the chain ambiguous or endless.
+pub(crate) fn check_steps(steps: &[MigrationStep]) -> Result<(), EnvflagError> {
+	for (i, s) in steps.iter().enumerate() {
+		if s.to <= s.from {
+			return Err(EnvflagError::InvalidMigration {
+				message: format!("migration {} -> {} must move forward", s.from, s.to),
+			});
+		}
+		if steps[..i].iter().any(|o| o.from == s.from) {
+			return Err(EnvflagError::InvalidMigration {
+				message: format!("two migrations start from version {}", s.from),
+			});
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn step(
+		from: u32,
+		to: u32,
+		f: impl Fn(&mut Migration<'_>) + Send + Sync + 'static,
+	) -> MigrationStep {
+		MigrationStep {
+			from,
+			to,
+			f: Arc::new(f),
+		}
+	}
+
+	fn store(pairs: &[(&str, &str)]) -> EnvStore {
+		EnvStore::from_map_with_prefixes(
+			pairs
+				.iter()
+				.map(|(k, v)| ((*k).into(), (*v).into()))
+				.collect(),
+			vec!["APP_".into()],
+		)
+	}
+
+	fn steps() -> Vec<MigrationStep> {
+		vec![
+			step(2, 3, |m| m.rename("TIMEOUT", "TIMEOUT_SECS")),
+			step(1, 2, |m| m.rename("DB_HOST", "DATABASE_HOST")),
+		]
+	}
+
+	#[test]
+	fn chains_from_missing_version() {
+		let mut s = store(&[("APP_DB_HOST", "db"), ("APP_TIMEOUT", "5")]);
+		apply(&mut s, &steps()).unwrap();
+		assert_eq!(s.lookup("DATABASE_HOST", None), Some("db".to_owned()));
+		assert_eq!(s.lookup("TIMEOUT_SECS", None), Some("5".to_owned()));
+		assert_eq!(s.lookup("DB_HOST", None), None);
+		assert_eq!(s.lookup(VERSION_KEY, None), Some("3".to_owned()));
+	}
+
+	#[test]
+	fn starts_at_declared_version() {
+		let mut s = store(&[
+			("APP_CONFIG_VERSION", "2"),
+			("APP_DB_HOST", "old"),
+			("APP_TIMEOUT", "5"),
+			("APP_TIMEOUT_SECS", "9"),
+		]);
+		apply(&mut s, &steps()).unwrap();
+		// 1 -> 2 did not run; the new name wins over the old one.
+		assert_eq!(s.lookup("DB_HOST", None), Some("old".to_owned()));
+		assert_eq!(s.lookup("TIMEOUT_SECS", None), Some("9".to_owned()));
+		assert_eq!(s.lookup("TIMEOUT", None), None);
+	}
+
+	#[test]
+	fn invalid_chains() {
+		assert!(check_steps(&[step(2, 2, |_| {})]).is_err());
+		assert!(check_steps(&[step(1, 2, |_| {}), step(1, 3, |_| {})]).is_err());
+		assert!(check_steps(&steps()).is_ok());
+
+		let mut s = store(&[("APP_CONFIG_VERSION", "v2")]);
+		assert!(matches!(
+			apply(&mut s, &steps()),
+			Err(EnvflagError::ParseFailed { .. })
+		));
+	}
+}
@@ -0,0 +1,95 @@
+/* src/normalize.rs */
+
+//! Rewriting raw values into the form a type's `FromStr` accepts.
+//!
+//! Before a value is parsed as `T`, it is passed through the normalizer
+//! registered for `T`, if any. Built in are `bool` (`yes`/`no` and `1`/`0`
+//! in any case become `true`/`false`), and the `std::net` address types and
+//! the `tracing` and `log` level types (surrounding whitespace is trimmed);
+//! [`register`] adds more, or replaces a built-in one.
+
+use std::any::TypeId;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::sync::{PoisonError, RwLock};
+
+/// Rewrites a raw value into the form a type's `FromStr` accepts.
+pub type Normalizer = fn(&str) -> String;
+
+/// Normalizers added with [`register`], by target type.
+static REGISTERED: RwLock<Vec<(TypeId, Normalizer)>> = RwLock::new(Vec::new());
+
+/// Registers `f` to rewrite every raw value before it is parsed as `T`,
+/// replacing any earlier normalizer for `T`, including a built-in one.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use std::str::FromStr;
+/// use envflag::store::EnvStore;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Region(String);
+///
+/// impl FromStr for Region {
+///     type Err = String;
+///     fn from_str(s: &str) -> Result<Self, String> {
+///         if s.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-') {
+///             Ok(Region(s.to_owned()))
+///         } else {
+///             Err(format!("not a region: {s}"))
+///         }
+///     }
+/// }
+///
+/// envflag::normalize::register::<Region>(|s| s.trim().to_ascii_lowercase());
+///
+/// let store = EnvStore::from_map(HashMap::from([("REGION".into(), " EU-West-1 ".into())]));
+/// let region: Region = store.key("REGION").required().unwrap();
+/// assert_eq!(region, Region("eu-west-1".to_owned()));
+/// ```
+pub fn register<T: 'static>(f: Normalizer) {
+	let id = TypeId::of::<T>();
+	let mut registered = REGISTERED.write().unwrap_or_else(PoisonError::into_inner);
+	registered.retain(|(t, _)| *t != id);
+	registered.push((id, f));
+}
+
+/// Returns the normalizer for the type `id`: the registered one, or else
+/// the built-in one.
+pub(crate) fn lookup(id: TypeId) -> Option<Normalizer> {
+	let registered = REGISTERED
+		.read()
+		.unwrap_or_else(PoisonError::into_inner)
+		.iter()
+		.find(|(t, _)| *t == id)
+		.map(|(_, f)| *f);
+	registered.or_else(|| builtin(id))
+}
+
+/// Returns the built-in normalizer for the type `id`, if any.
+fn builtin(id: TypeId) -> Option<Normalizer> {
+	if id == TypeId::of::<bool>() {
+		return Some(|raw| crate::validators::normalize_bool(raw).into_owned());
+	}
+	// These parsers reject any surrounding whitespace.
+	let trimmed = [
+		TypeId::of::<IpAddr>(),
+		TypeId::of::<Ipv4Addr>(),
+		TypeId::of::<Ipv6Addr>(),
+		TypeId::of::<SocketAddr>(),
+		TypeId::of::<SocketAddrV4>(),
+		TypeId::of::<SocketAddrV6>(),
+		#[cfg(feature = "tracing")]
+		TypeId::of::<tracing::Level>(),
+		#[cfg(feature = "tracing")]
+		TypeId::of::<tracing::level_filters::LevelFilter>(),
+		#[cfg(feature = "log")]
+		TypeId::of::<log::Level>(),
+		#[cfg(feature = "log")]
+		TypeId::of::<log::LevelFilter>(),
+	];
+	trimmed
+		.contains(&id)
+		.then_some((|raw| raw.trim().to_owned()) as Normalizer)
+}
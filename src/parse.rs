@@ -0,0 +1,421 @@
+/* src/parse.rs */
+
+//! Parsers for structured values used by the builder terminals.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::ops::{Range, RangeInclusive};
+use std::str::FromStr;
+
+/// Parses `START-END` (or a single port) into an inclusive port range.
+pub(crate) fn port_range(s: &str) -> Option<RangeInclusive<u16>> {
+	let (start, end) = s.split_once('-').unwrap_or((s, s));
+	let start = port(start)?;
+	let end = port(end)?;
+	(start <= end).then_some(start..=end)
+}
+
+/// Parses `START..END` into a half-open range.
+pub(crate) fn range<T: FromStr + PartialOrd>(s: &str) -> Option<Range<T>> {
+	if s.contains("..=") {
+		return None;
+	}
+	let (start, end) = bounds(s.split_once("..")?)?;
+	Some(start..end)
+}
+
+/// Parses `START..=END` into an inclusive range.
+pub(crate) fn range_inclusive<T: FromStr + PartialOrd>(s: &str) -> Option<RangeInclusive<T>> {
+	let (start, end) = bounds(s.split_once("..=")?)?;
+	Some(start..=end)
+}
+
+/// Parses both ends of a range, requiring `start <= end`.
+fn bounds<T: FromStr + PartialOrd>((start, end): (&str, &str)) -> Option<(T, T)> {
+	let start = start.trim().parse::<T>().ok()?;
+	let end = end.trim().parse::<T>().ok()?;
+	(start <= end).then_some((start, end))
+}
+
+/// Parses `HOST:PORT`, where `HOST` is a DNS name, an IPv4 address, or a
+/// bracketed IPv6 address (`[::1]:8080`). Brackets are stripped from the
+/// returned host.
+pub(crate) fn host_port(s: &str) -> Option<(String, u16)> {
+	if let Some(rest) = s.strip_prefix('[') {
+		let (host, port_str) = rest.split_once("]:")?;
+		host.parse::<Ipv6Addr>().ok()?;
+		return Some((host.to_owned(), port(port_str)?));
+	}
+
+	let (host, port_str) = s.rsplit_once(':')?;
+	if host.parse::<Ipv4Addr>().is_err() && !is_dns_name(host) {
+		return None;
+	}
+	Some((host.to_owned(), port(port_str)?))
+}
+
+/// Checks a host name against RFC 1123 label rules.
+pub(crate) fn is_dns_name(s: &str) -> bool {
+	let s = s.strip_suffix('.').unwrap_or(s);
+	!s.is_empty()
+		&& s.len() <= 253
+		&& s.split('.').all(|label| {
+			!label.is_empty()
+				&& label.len() <= 63
+				&& !label.starts_with('-')
+				&& !label.ends_with('-')
+				&& label
+					.bytes()
+					.all(|b| b.is_ascii_alphanumeric() || b == b'-')
+		})
+}
+
+/// Parses `name=weight` pairs separated by commas.
+///
+/// Names must be non-empty and unique; weights must be finite and
+/// non-negative.
+pub(crate) fn weighted_list(s: &str) -> Option<Vec<(String, f64)>> {
+	let mut list: Vec<(String, f64)> = Vec::new();
+	for entry in s.split(',') {
+		let (name, weight) = entry.split_once('=')?;
+		let name = name.trim();
+		let weight = weight.trim().parse::<f64>().ok()?;
+		if name.is_empty() || !weight.is_finite() || weight < 0.0 {
+			return None;
+		}
+		if list.iter().any(|(n, _)| n == name) {
+			return None;
+		}
+		list.push((name.to_owned(), weight));
+	}
+	Some(list)
+}
+
+/// Parses a weighted list whose weights sum to one (within `1e-6`).
+pub(crate) fn weighted_list_sum_to_one(s: &str) -> Option<Vec<(String, f64)>> {
+	let list = weighted_list(s)?;
+	let sum: f64 = list.iter().map(|(_, w)| w).sum();
+	((sum - 1.0).abs() <= 1e-6).then_some(list)
+}
+
+/// Parses a count with an optional decimal suffix: `k`/`K` (10^3), `M`
+/// (10^6), `G` (10^9), or `T` (10^12).
+///
+/// A fractional mantissa is allowed as long as the result is a whole number
+/// (`1.5k` → 1500). Returns `None` on overflow.
+pub(crate) fn scaled_number(s: &str) -> Option<u64> {
+	let (digits, scale) = match s.char_indices().last()? {
+		(i, 'k' | 'K') => (&s[..i], 1_000u128),
+		(i, 'M') => (&s[..i], 1_000_000),
+		(i, 'G') => (&s[..i], 1_000_000_000),
+		(i, 'T') => (&s[..i], 1_000_000_000_000),
+		_ => (s, 1),
+	};
+	apply_scale(digits.trim_end(), scale)
+}
+
+/// Parses a byte size such as `512MB`, `2GiB`, or `1.5 KB`.
+///
+/// Units are case-insensitive. `KiB`, `MiB`, ... are always powers of 1024;
+/// `K`/`KB`, `M`/`MB`, ... are powers of 1000, or of 1024 if `binary`. A bare
+/// number or `B` is a count of bytes. Fractions must come out to whole bytes.
+pub(crate) fn byte_size(s: &str, binary: bool) -> Option<u64> {
+	let split = s
+		.find(|c: char| !c.is_ascii_digit() && c != '.')
+		.unwrap_or(s.len());
+	let (digits, unit) = s.split_at(split);
+	let unit = unit.trim_start().to_ascii_lowercase();
+	let (prefix, base) = match unit.strip_suffix("ib") {
+		Some(prefix) if !prefix.is_empty() => (prefix, 1024u128),
+		_ => (
+			unit.strip_suffix('b').unwrap_or(&unit),
+			if binary { 1024 } else { 1000 },
+		),
+	};
+	let power = match prefix {
+		"" => 0,
+		"k" => 1,
+		"m" => 2,
+		"g" => 3,
+		"t" => 4,
+		"p" => 5,
+		_ => return None,
+	};
+	apply_scale(digits, base.pow(power))
+}
+
+/// Multiplies a decimal number such as `1.5` by `scale`, requiring a whole
+/// result that fits in `u64`.
+fn apply_scale(digits: &str, scale: u128) -> Option<u64> {
+	let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+	if int_part.is_empty() || !int_part.bytes().all(|b| b.is_ascii_digit()) {
+		return None;
+	}
+	if !frac_part.bytes().all(|b| b.is_ascii_digit())
+		|| (digits.contains('.') && frac_part.is_empty())
+	{
+		return None;
+	}
+
+	let int = int_part.parse::<u128>().ok()?.checked_mul(scale)?;
+	let frac_digits = u32::try_from(frac_part.len()).ok()?;
+	let frac = if frac_part.is_empty() {
+		0
+	} else {
+		let denom = 10u128.checked_pow(frac_digits)?;
+		let scaled = frac_part.parse::<u128>().ok()?.checked_mul(scale)?;
+		if scaled % denom != 0 {
+			return None;
+		}
+		scaled / denom
+	};
+	u64::try_from(int.checked_add(frac)?).ok()
+}
+
+/// Parses an RFC 3339 timestamp such as `2024-05-01T02:00:00Z`.
+#[cfg(feature = "chrono")]
+pub(crate) fn datetime(s: &str) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+	chrono::DateTime::parse_from_rfc3339(s).ok()
+}
+
+/// Parses a 24-hour time of day, `HH:MM` or `HH:MM:SS`.
+#[cfg(feature = "chrono")]
+pub(crate) fn time_of_day(s: &str) -> Option<chrono::NaiveTime> {
+	["%H:%M", "%H:%M:%S"]
+		.iter()
+		.find_map(|format| chrono::NaiveTime::parse_from_str(s, format).ok())
+}
+
+/// Decodes hexadecimal digits, in either case, two per byte.
+pub(crate) fn decode_hex(s: &str) -> Option<Vec<u8>> {
+	// `from_str_radix` alone would also take a leading `+`.
+	if !s.len().is_multiple_of(2) || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+		return None;
+	}
+	(0..s.len())
+		.step_by(2)
+		.map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+		.collect()
+}
+
+/// Decodes base64 in the standard or URL-safe alphabet, with or without
+/// `=` padding.
+pub(crate) fn decode_base64(s: &str) -> Option<Vec<u8>> {
+	let data = s.trim_end_matches('=');
+	let padding = s.len() - data.len();
+	if padding > 2 || (padding > 0 && !s.len().is_multiple_of(4)) || data.len() % 4 == 1 {
+		return None;
+	}
+
+	let mut out = Vec::with_capacity(data.len() * 3 / 4);
+	let (mut acc, mut bits) = (0u32, 0);
+	for b in data.bytes() {
+		let sextet = match b {
+			b'A'..=b'Z' => b - b'A',
+			b'a'..=b'z' => b - b'a' + 26,
+			b'0'..=b'9' => b - b'0' + 52,
+			b'+' | b'-' => 62,
+			b'/' | b'_' => 63,
+			_ => return None,
+		};
+		acc = (acc << 6) | u32::from(sextet);
+		bits += 6;
+		if bits >= 8 {
+			bits -= 8;
+			out.push(u8::try_from(acc >> bits).ok()?);
+			acc &= (1 << bits) - 1;
+		}
+	}
+	// Leftover bits must be zero, so each value has one encoding.
+	(acc == 0).then_some(out)
+}
+
+/// Cleans up a human-authored number: trims whitespace, drops `_` digit
+/// separators, and (for floats) reads a lone `,` as the decimal point.
+pub(crate) fn lenient_number(s: &str, float: bool) -> String {
+	let mut out: String = s.trim().chars().filter(|c| *c != '_').collect();
+	if float && !out.contains('.') && out.matches(',').count() == 1 {
+		out = out.replace(',', ".");
+	}
+	out
+}
+
+/// Replaces literal `\n` (and `\r\n`) escapes with real newlines.
+pub(crate) fn unescape_newlines(s: &str) -> String {
+	s.replace("\\r\\n", "\n").replace("\\n", "\n")
+}
+
+/// Parses a non-zero port number.
+fn port(s: &str) -> Option<u16> {
+	s.trim().parse::<u16>().ok().filter(|p| *p > 0)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn port_range_forms() {
+		assert_eq!(port_range("8000-8100"), Some(8000..=8100));
+		assert_eq!(port_range("8000 - 8100"), Some(8000..=8100));
+		assert_eq!(port_range("9000"), Some(9000..=9000));
+	}
+
+	#[test]
+	fn range_forms() {
+		assert_eq!(range::<u32>("10..20"), Some(10..20));
+		assert_eq!(range::<i64>("-5 .. 5"), Some(-5..5));
+		assert_eq!(range::<f64>("0.1..0.5"), Some(0.1..0.5));
+		assert_eq!(range_inclusive::<u32>("10..=20"), Some(10..=20));
+	}
+
+	#[test]
+	fn range_rejects_invalid() {
+		assert_eq!(range::<u32>("10..=20"), None);
+		assert_eq!(range::<u32>("20..10"), None);
+		assert_eq!(range::<u32>("10-20"), None);
+		assert_eq!(range_inclusive::<u32>("10..20"), None);
+		assert_eq!(range_inclusive::<u8>("1..=300"), None);
+	}
+
+	#[test]
+	fn host_port_forms() {
+		assert_eq!(
+			host_port("upstream.example.com:9000"),
+			Some(("upstream.example.com".to_owned(), 9000))
+		);
+		assert_eq!(host_port("10.0.0.1:80"), Some(("10.0.0.1".to_owned(), 80)));
+		assert_eq!(host_port("[::1]:8080"), Some(("::1".to_owned(), 8080)));
+		assert_eq!(host_port("localhost:1"), Some(("localhost".to_owned(), 1)));
+	}
+
+	#[test]
+	fn host_port_rejects_invalid() {
+		for bad in [
+			"example.com",
+			"example.com:0",
+			"example.com:http",
+			"::1:8080",
+			"[example.com]:80",
+			"http://example.com:80",
+			"-bad.com:80",
+			":80",
+		] {
+			assert_eq!(host_port(bad), None, "{bad}");
+		}
+	}
+
+	#[test]
+	fn weighted_list_forms() {
+		assert_eq!(
+			weighted_list("a=0.7, b = 0.3"),
+			Some(vec![("a".to_owned(), 0.7), ("b".to_owned(), 0.3)])
+		);
+		assert_eq!(weighted_list("a=3,b=1").map(|l| l.len()), Some(2));
+		assert!(weighted_list_sum_to_one("a=0.7,b=0.2,c=0.1").is_some());
+	}
+
+	#[test]
+	fn weighted_list_rejects_invalid() {
+		for bad in ["a", "a=x", "=1", "a=-1", "a=inf", "a=1,a=2", "a=1,"] {
+			assert_eq!(weighted_list(bad), None, "{bad}");
+		}
+		assert_eq!(weighted_list_sum_to_one("a=3,b=1"), None);
+	}
+
+	#[test]
+	fn lenient_number_forms() {
+		assert_eq!(lenient_number(" 1_000_000 ", false), "1000000");
+		assert_eq!(lenient_number("1,5", true), "1.5");
+		assert_eq!(lenient_number("1,5", false), "1,5");
+		assert_eq!(lenient_number("1,000,000", true), "1,000,000");
+		assert_eq!(lenient_number("1.5", true), "1.5");
+	}
+
+	#[test]
+	fn scaled_number_forms() {
+		assert_eq!(scaled_number("250k"), Some(250_000));
+		assert_eq!(scaled_number("2M"), Some(2_000_000));
+		assert_eq!(scaled_number("1.5K"), Some(1_500));
+		assert_eq!(scaled_number("3 G"), Some(3_000_000_000));
+		assert_eq!(scaled_number("42"), Some(42));
+		assert_eq!(scaled_number("18446744073709551615"), Some(u64::MAX));
+	}
+
+	#[test]
+	fn scaled_number_rejects_invalid() {
+		for bad in [
+			"",
+			"k",
+			"1.2345k",
+			"1m",
+			"-1k",
+			"1.k",
+			".5k",
+			"20000000T",
+			"1e3",
+		] {
+			assert_eq!(scaled_number(bad), None, "{bad}");
+		}
+	}
+
+	#[test]
+	fn byte_size_forms() {
+		assert_eq!(byte_size("512MB", false), Some(512_000_000));
+		assert_eq!(byte_size("512MB", true), Some(512 << 20));
+		assert_eq!(byte_size("2GiB", false), Some(2 << 30));
+		assert_eq!(byte_size("1.5 kb", false), Some(1_500));
+		assert_eq!(byte_size("1.5K", true), Some(1_536));
+		assert_eq!(byte_size("4096", false), Some(4_096));
+		assert_eq!(byte_size("10B", false), Some(10));
+		assert_eq!(byte_size("1PiB", false), Some(1 << 50));
+	}
+
+	#[test]
+	fn byte_size_rejects_invalid() {
+		for bad in ["", "MB", "1.5B", "1XB", "1 ib", "-1KB", "1.KB", "20000PB"] {
+			assert_eq!(byte_size(bad, false), None, "{bad}");
+		}
+	}
+
+	#[cfg(feature = "chrono")]
+	#[test]
+	fn date_and_time_forms() {
+		let at = datetime("2024-05-01T02:00:00+02:00").unwrap();
+		assert_eq!(at.timestamp(), 1_714_521_600);
+		assert!(datetime("2024-05-01 02:00").is_none());
+
+		assert_eq!(time_of_day("02:30").unwrap().to_string(), "02:30:00");
+		assert_eq!(time_of_day("23:59:59").unwrap().to_string(), "23:59:59");
+		for bad in ["24:00", "2:3pm", "12", ""] {
+			assert!(time_of_day(bad).is_none(), "{bad}");
+		}
+	}
+
+	#[test]
+	fn decodes_hex() {
+		assert_eq!(decode_hex("00fFa5"), Some(vec![0x00, 0xff, 0xa5]));
+		assert_eq!(decode_hex(""), Some(vec![]));
+		for bad in ["abc", "zz", "0x00", "é0", "+f"] {
+			assert_eq!(decode_hex(bad), None, "{bad}");
+		}
+	}
+
+	#[test]
+	fn decodes_base64() {
+		assert_eq!(decode_base64("aGVsbG8="), Some(b"hello".to_vec()));
+		assert_eq!(decode_base64("aGVsbG8"), Some(b"hello".to_vec()));
+		assert_eq!(decode_base64("aGVsbG8h"), Some(b"hello!".to_vec()));
+		assert_eq!(decode_base64("+/8="), Some(vec![0xfb, 0xff]));
+		assert_eq!(decode_base64("-_8"), Some(vec![0xfb, 0xff]));
+		for bad in ["aGVsbG8===", "aGVsbG9=", "a", "aGVs bG8=", "aGVsbG8=x"] {
+			assert_eq!(decode_base64(bad), None, "{bad}");
+		}
+	}
+
+	#[test]
+	fn port_range_rejects_invalid() {
+		for bad in ["8100-8000", "0-10", "1-70000", "a-b", "-", "1-2-3"] {
+			assert_eq!(port_range(bad), None, "{bad}");
+		}
+	}
+}
@@ -0,0 +1,333 @@
+/* src/reload.rs */
+
+//! Reloading the global store and reporting what changed.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::EnvflagError;
+use crate::store::{EnvStore, INSTANCE, InitBuilder};
+
+/// A registered reload callback. Returning `false` unregisters it.
+pub(crate) type Subscriber = Arc<dyn Fn(&ReloadDiff) -> bool + Send + Sync>;
+
+/// Fetches the current value of a single key from its origin.
+pub(crate) type FetchFn = Arc<dyn Fn(&str) -> Result<Option<String>, EnvflagError> + Send + Sync>;
+
+/// A key whose value is periodically re-fetched.
+#[derive(Clone)]
+pub(crate) struct RefreshKey {
+	pub(crate) key: String,
+	pub(crate) ttl: Duration,
+	pub(crate) fetch: FetchFn,
+}
+
+impl fmt::Debug for RefreshKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("RefreshKey")
+			.field("key", &self.key)
+			.field("ttl", &self.ttl)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Callbacks registered via [`on_reload`](crate::on_reload), keyed by id.
+static SUBSCRIBERS: Mutex<Vec<(u64, Subscriber)>> = Mutex::new(Vec::new());
+
+/// The id handed to the next subscriber.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A callback registered with [`on_reload`](crate::on_reload).
+///
+/// Dropping the handle keeps the callback registered; call
+/// [`unsubscribe`](Self::unsubscribe) to remove it.
+#[derive(Debug)]
+pub struct ReloadSubscription {
+	id: u64,
+}
+
+impl ReloadSubscription {
+	/// Removes the callback. It is not run for any later reload.
+	pub fn unsubscribe(self) {
+		unsubscribe(self.id);
+	}
+}
+
+/// The keys that changed between two snapshots of the store.
+///
+/// Only key names are recorded, never values, so a diff is always safe to
+/// log. Each list is sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadDiff {
+	/// Keys present only in the new snapshot.
+	pub added: Vec<String>,
+	/// Keys present only in the old snapshot.
+	pub removed: Vec<String>,
+	/// Keys present in both snapshots with different values.
+	pub changed: Vec<String>,
+}
+
+impl ReloadDiff {
+	/// Computes the difference between two stores.
+	#[must_use]
+	pub fn between(old: &EnvStore, new: &EnvStore) -> Self {
+		let old_entries = old.entry_map();
+		let new_entries = new.entry_map();
+		let keys: BTreeSet<&String> = old_entries.keys().chain(new_entries.keys()).collect();
+
+		let mut diff = Self::default();
+		for k in keys {
+			match (old_entries.get(k), new_entries.get(k)) {
+				(None, Some(_)) => diff.added.push(k.clone()),
+				(Some(_), None) => diff.removed.push(k.clone()),
+				(Some(a), Some(b)) if a != b => diff.changed.push(k.clone()),
+				_ => {}
+			}
+		}
+		diff
+	}
+
+	/// Returns `true` if nothing changed.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+	}
+}
+
+/// Registers a callback to be run after every reload.
+pub(crate) fn subscribe(f: Subscriber) -> ReloadSubscription {
+	let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+	SUBSCRIBERS
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.push((id, f));
+	ReloadSubscription { id }
+}
+
+/// Removes the callback registered under `id`, if any.
+fn unsubscribe(id: u64) {
+	SUBSCRIBERS
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.retain(|(i, _)| *i != id);
+}
+
+/// Drops every registered callback.
+#[cfg(feature = "test-util")]
+pub(crate) fn clear_subscribers() {
+	SUBSCRIBERS
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.clear();
+}
+
+/// Rebuilds the global store from its original configuration and swaps it in.
+pub(crate) fn reload() -> Result<ReloadDiff, EnvflagError> {
+	let builder = INSTANCE
+		.read()
+		.unwrap_or_else(PoisonError::into_inner)
+		.as_ref()
+		.map(|i| Arc::clone(&i.builder))
+		.ok_or(EnvflagError::NotInitialized)?;
+
+	// Load outside the lock so readers are never blocked on I/O.
+	let new = builder.build()?;
+
+	let diff = {
+		let mut instance = INSTANCE.write().unwrap_or_else(PoisonError::into_inner);
+		let instance = instance.as_mut().ok_or(EnvflagError::NotInitialized)?;
+		let diff = ReloadDiff::between(&instance.store, &new);
+		#[cfg(feature = "usage")]
+		let new = new.inheriting_usage(&instance.store);
+		instance.store = Arc::new(new);
+		diff
+	};
+
+	notify(&diff);
+	Ok(diff)
+}
+
+/// Returns `true` if `builder` backs the global instance.
+#[cfg(feature = "watch")]
+pub(crate) fn is_current(builder: &Arc<InitBuilder>) -> bool {
+	INSTANCE
+		.read()
+		.unwrap_or_else(PoisonError::into_inner)
+		.as_ref()
+		.is_some_and(|i| Arc::ptr_eq(&i.builder, builder))
+}
+
+/// Rebuilds the store from `builder` and swaps it in, notifying subscribers
+/// if anything changed. A failed load keeps the current store.
+///
+/// Returns `false` if `builder` no longer backs the global instance.
+#[cfg(feature = "watch")]
+pub(crate) fn rebuild(builder: &Arc<InitBuilder>) -> bool {
+	let new = match builder.build() {
+		Ok(store) => store,
+		Err(_e) => {
+			#[cfg(feature = "tracing")]
+			tracing::warn!(error = %_e, "reload failed, keeping current configuration");
+			return is_current(builder);
+		}
+	};
+
+	let diff = {
+		let mut instance = INSTANCE.write().unwrap_or_else(PoisonError::into_inner);
+		let Some(instance) = instance.as_mut() else {
+			return false;
+		};
+		if !Arc::ptr_eq(&instance.builder, builder) {
+			return false;
+		}
+		let diff = ReloadDiff::between(&instance.store, &new);
+		#[cfg(feature = "usage")]
+		let new = new.inheriting_usage(&instance.store);
+		instance.store = Arc::new(new);
+		diff
+	};
+
+	if !diff.is_empty() {
+		notify(&diff);
+	}
+	true
+}
+
+/// Logs a diff and passes it to every subscriber.
+fn notify(diff: &ReloadDiff) {
+	// New values deserve fresh warnings.
+	crate::warn::reset();
+
+	#[cfg(feature = "tracing")]
+	tracing::info!(
+		added = ?diff.added,
+		removed = ?diff.removed,
+		changed = ?diff.changed,
+		"envflag configuration reloaded"
+	);
+
+	// Call a snapshot outside the lock so callbacks can subscribe,
+	// unsubscribe, or reload.
+	let subscribers: Vec<(u64, Subscriber)> = SUBSCRIBERS
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.iter()
+		.map(|(id, f)| (*id, Arc::clone(f)))
+		.collect();
+	for (id, f) in subscribers {
+		if !f(diff) {
+			unsubscribe(id);
+		}
+	}
+}
+
+/// Starts the background thread that re-fetches refreshable keys.
+///
+/// The thread exits once `builder` is no longer the one backing the global
+/// instance.
+pub(crate) fn spawn_refresher(builder: Arc<InitBuilder>) -> Result<(), EnvflagError> {
+	thread::Builder::new()
+		.name("envflag-refresh".to_owned())
+		.spawn(move || {
+			let keys = builder.refresh_keys();
+			let now = Instant::now();
+			let mut due: Vec<Instant> = keys.iter().map(|k| now + k.ttl).collect();
+
+			loop {
+				let Some((i, at)) = due.iter().copied().enumerate().min_by_key(|(_, at)| *at) else {
+					return;
+				};
+				thread::sleep(at.saturating_duration_since(Instant::now()));
+				if !refresh(&builder, &keys[i]) {
+					return;
+				}
+				due[i] = Instant::now() + keys[i].ttl;
+			}
+		})?;
+	Ok(())
+}
+
+/// Re-fetches one key and swaps it into the global store.
+///
+/// Returns `false` if `builder` no longer backs the global instance.
+fn refresh(builder: &Arc<InitBuilder>, key: &RefreshKey) -> bool {
+	let value = match (key.fetch)(&key.key) {
+		Ok(v) => v,
+		Err(_e) => {
+			#[cfg(feature = "tracing")]
+			tracing::warn!(key = %key.key, error = %_e, "refresh failed, keeping current value");
+			return true;
+		}
+	};
+
+	let diff = {
+		let mut instance = INSTANCE.write().unwrap_or_else(PoisonError::into_inner);
+		let Some(instance) = instance.as_mut() else {
+			return false;
+		};
+		if !Arc::ptr_eq(&instance.builder, builder) {
+			return false;
+		}
+		if !builder.accepts(&key.key) {
+			return true;
+		}
+		let new = instance.store.with_refreshed(&key.key, value);
+		let diff = ReloadDiff::between(&instance.store, &new);
+		instance.store = Arc::new(new);
+		diff
+	};
+
+	if !diff.is_empty() {
+		notify(&diff);
+	}
+	true
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn make_store(pairs: &[(&str, &str)]) -> EnvStore {
+		EnvStore::from_map(
+			pairs
+				.iter()
+				.map(|(k, v)| ((*k).into(), (*v).into()))
+				.collect(),
+		)
+	}
+
+	#[test]
+	fn diff_between_stores() {
+		let old = make_store(&[("A", "1"), ("B", "2"), ("C", "3")]);
+		let new = make_store(&[("A", "1"), ("B", "20"), ("D", "4")]);
+		let diff = ReloadDiff::between(&old, &new);
+		assert_eq!(diff.added, vec!["D".to_owned()]);
+		assert_eq!(diff.removed, vec!["C".to_owned()]);
+		assert_eq!(diff.changed, vec!["B".to_owned()]);
+	}
+
+	#[test]
+	fn diff_identical_is_empty() {
+		let store = make_store(&[("A", "1")]);
+		assert!(ReloadDiff::between(&store, &store).is_empty());
+	}
+
+	#[test]
+	fn subscriber_returning_false_is_removed() {
+		use std::sync::atomic::AtomicUsize;
+
+		let calls = Arc::new(AtomicUsize::new(0));
+		let count = Arc::clone(&calls);
+		subscribe(Arc::new(move |_| {
+			count.fetch_add(1, Ordering::Relaxed);
+			false
+		}));
+		notify(&ReloadDiff::default());
+		notify(&ReloadDiff::default());
+		assert_eq!(calls.load(Ordering::Relaxed), 1);
+	}
+}
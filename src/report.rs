@@ -0,0 +1,128 @@
+/* src/report.rs */
+
+//! The effective configuration, for printing at startup.
+
+use std::fmt;
+
+use crate::layer::LayerKind;
+use crate::redact;
+use crate::store::EnvStore;
+
+/// Every loaded variable with its source and redacted value.
+///
+/// The `Display` output is an aligned table, the "effective configuration"
+/// banner services print at boot. Values pass through
+/// [`redact`](crate::redact::redact), so a report is always safe to log.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ConfigReport {
+	/// One entry per variable, sorted by key.
+	pub entries: Vec<ReportEntry>,
+}
+
+/// One variable in a [`ConfigReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ReportEntry {
+	/// The full variable name, including any prefix.
+	pub key: String,
+	/// The kind of layer that supplied the value, or `None` for stores built
+	/// directly from a map.
+	pub source: Option<LayerKind>,
+	/// Where that layer was loaded from (a file path or URL), if applicable.
+	pub origin: Option<String>,
+	/// The value with secrets masked.
+	pub value: String,
+}
+
+impl ConfigReport {
+	/// Builds the report for `store`.
+	#[must_use]
+	pub fn of(store: &EnvStore) -> Self {
+		let mut entries: Vec<ReportEntry> = store
+			.entry_map()
+			.iter()
+			.map(|(key, value)| {
+				let layer = store.source_layer(key);
+				ReportEntry {
+					key: key.clone(),
+					source: layer.map(|l| l.kind),
+					origin: layer.and_then(|l| l.origin.clone()),
+					value: redact::redact(key, value).into_owned(),
+				}
+			})
+			.collect();
+		entries.sort_by(|a, b| a.key.cmp(&b.key));
+		Self { entries }
+	}
+}
+
+impl ReportEntry {
+	/// The source column: the layer kind and origin, or `-` if unknown.
+	fn source_label(&self) -> String {
+		match (self.source, &self.origin) {
+			(Some(kind), Some(origin)) => format!("{kind} ({origin})"),
+			(Some(kind), None) => kind.to_string(),
+			(None, _) => "-".to_owned(),
+		}
+	}
+}
+
+impl fmt::Display for ConfigReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let sources: Vec<String> = self.entries.iter().map(ReportEntry::source_label).collect();
+		let key_width = self
+			.entries
+			.iter()
+			.map(|e| e.key.len())
+			.max()
+			.unwrap_or(0)
+			.max("KEY".len());
+		let source_width = sources
+			.iter()
+			.map(String::len)
+			.max()
+			.unwrap_or(0)
+			.max("SOURCE".len());
+
+		write!(f, "{:key_width$}  {:source_width$}  VALUE", "KEY", "SOURCE")?;
+		for (entry, source) in self.entries.iter().zip(&sources) {
+			write!(
+				f,
+				"\n{:key_width$}  {source:source_width$}  {}",
+				entry.key, entry.value
+			)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::InitBuilder;
+
+	#[test]
+	fn lists_sources_and_redacts_secrets() {
+		static DEFAULTS: &[(&str, &str)] = &[("REPORT_PORT", "8080")];
+		let store = InitBuilder::new()
+			.prefix("REPORT_")
+			.defaults(DEFAULTS)
+			.args_overrides(["-e", "REPORT_API_TOKEN=hunter2"])
+			.build()
+			.unwrap();
+		let report = ConfigReport::of(&store);
+		let keys: Vec<_> = report.entries.iter().map(|e| e.key.as_str()).collect();
+		assert_eq!(keys, ["REPORT_API_TOKEN", "REPORT_PORT"]);
+		assert_eq!(report.entries[0].source, Some(LayerKind::CommandLine));
+		assert_eq!(report.entries[0].value, redact::MASK);
+		assert_eq!(report.entries[1].source, Some(LayerKind::Defaults));
+
+		assert_eq!(
+			report.to_string(),
+			"KEY               SOURCE    VALUE\n\
+			 REPORT_API_TOKEN  args      ********\n\
+			 REPORT_PORT       defaults  8080"
+		);
+	}
+}
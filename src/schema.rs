@@ -0,0 +1,766 @@
+/* src/schema.rs */
+
+//! Declarative descriptions of the variables an application expects.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::error::EnvflagError;
+use crate::{redact, validators};
+
+/// Describes a single environment variable.
+///
+/// Names are short names, exactly as passed to [`key()`](crate::key): any
+/// prefix configured on the store is applied at lookup time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct KeySpec {
+	/// The variable name.
+	pub name: String,
+	/// The name of the type the value is parsed into.
+	pub type_name: String,
+	/// The value used when the variable is missing.
+	pub default: Option<String>,
+	/// Whether the variable must be set.
+	pub required: bool,
+	/// A human-readable description.
+	pub description: Option<String>,
+	/// Names of built-in validators the value must pass, e.g. `is_port`.
+	pub validators: Vec<String>,
+}
+
+impl KeySpec {
+	/// Declares an optional `String` variable with no default.
+	#[must_use]
+	pub fn new(name: &str) -> Self {
+		Self {
+			name: name.to_owned(),
+			type_name: "String".to_owned(),
+			default: None,
+			required: false,
+			description: None,
+			validators: Vec::new(),
+		}
+	}
+
+	/// Sets the name of the type the value is parsed into.
+	#[must_use]
+	pub fn type_name(mut self, type_name: &str) -> Self {
+		self.type_name = type_name.to_owned();
+		self
+	}
+
+	/// Sets the value used when the variable is missing.
+	#[must_use]
+	pub fn default(mut self, default: &str) -> Self {
+		self.default = Some(default.to_owned());
+		self
+	}
+
+	/// Marks the variable as required.
+	#[must_use]
+	pub fn required(mut self) -> Self {
+		self.required = true;
+		self
+	}
+
+	/// Sets a human-readable description.
+	#[must_use]
+	pub fn description(mut self, description: &str) -> Self {
+		self.description = Some(description.to_owned());
+		self
+	}
+
+	/// Adds a built-in validator by name; see
+	/// [`validators::by_name`](crate::validators::by_name).
+	#[must_use]
+	pub fn validator(mut self, name: &str) -> Self {
+		self.validators.push(name.to_owned());
+		self
+	}
+
+	/// Checks a raw value against the declared type and validators.
+	///
+	/// Primitive types (integers, floats, `bool`, `char`) must parse; other
+	/// type names are accepted as-is.
+	///
+	/// # Errors
+	///
+	/// Returns the first check that failed.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use envflag::KeySpec;
+	///
+	/// let spec = KeySpec::new("PORT").type_name("u16").validator("is_port");
+	/// assert!(spec.check("8080").is_ok());
+	/// assert!(spec.check("0").is_err());
+	/// assert!(spec.check("http").is_err());
+	/// ```
+	pub fn check(&self, value: &str) -> Result<(), CheckError> {
+		if !parses_as(&self.type_name, value) {
+			return Err(CheckError::InvalidType {
+				value: value.to_owned(),
+				type_name: self.type_name.clone(),
+			});
+		}
+		for name in &self.validators {
+			let f = validators::by_name(name)
+				.ok_or_else(|| CheckError::UnknownValidator { name: name.clone() })?;
+			if !f(value) {
+				return Err(CheckError::Rejected {
+					value: value.to_owned(),
+					validator: name.clone(),
+				});
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Why a value failed [`KeySpec::check`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum CheckError {
+	/// The value does not parse as the declared type.
+	#[error("'{value}' is not a valid {type_name}")]
+	InvalidType {
+		/// The rejected value.
+		value: String,
+		/// The declared type name.
+		type_name: String,
+	},
+	/// The value fails a validator.
+	#[error("'{value}' does not pass {validator}")]
+	Rejected {
+		/// The rejected value.
+		value: String,
+		/// The name of the validator.
+		validator: String,
+	},
+	/// A declared validator does not exist.
+	#[error("unknown validator '{name}'")]
+	UnknownValidator {
+		/// The unknown name.
+		name: String,
+	},
+}
+
+impl CheckError {
+	/// Replaces the quoted value with `shown`.
+	fn with_value(mut self, shown: &str) -> Self {
+		if let Self::InvalidType { value, .. } | Self::Rejected { value, .. } = &mut self {
+			shown.clone_into(value);
+		}
+		self
+	}
+}
+
+/// Returns `false` if `value` is known not to parse as `type_name`.
+fn parses_as(type_name: &str, value: &str) -> bool {
+	let v = value.trim();
+	match type_name {
+		"bool" => validators::is_bool(v),
+		"char" => v.parse::<char>().is_ok(),
+		"f32" | "f64" => v.parse::<f64>().is_ok(),
+		"u8" => v.parse::<u8>().is_ok(),
+		"u16" => v.parse::<u16>().is_ok(),
+		"u32" => v.parse::<u32>().is_ok(),
+		"u64" => v.parse::<u64>().is_ok(),
+		"u128" => v.parse::<u128>().is_ok(),
+		"usize" => v.parse::<usize>().is_ok(),
+		"i8" => v.parse::<i8>().is_ok(),
+		"i16" => v.parse::<i16>().is_ok(),
+		"i32" => v.parse::<i32>().is_ok(),
+		"i64" => v.parse::<i64>().is_ok(),
+		"i128" => v.parse::<i128>().is_ok(),
+		"isize" => v.parse::<isize>().is_ok(),
+		_ => true,
+	}
+}
+
+/// An ordered collection of [`KeySpec`]s.
+///
+/// Build one by hand, or get one from `AppConfig::schema()` when using
+/// `#[derive(EnvConfig)]`.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::schema::{KeySpec, Schema};
+///
+/// let schema = Schema::new()
+///     .key(KeySpec::new("PORT").type_name("u16").default("8080"))
+///     .key(KeySpec::new("DATABASE_URL").required());
+/// assert_eq!(schema.keys().len(), 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+	keys: Vec<KeySpec>,
+}
+
+impl Schema {
+	/// Creates an empty schema.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds a key.
+	#[must_use]
+	pub fn key(mut self, spec: KeySpec) -> Self {
+		self.keys.push(spec);
+		self
+	}
+
+	/// Adds every key of `other`, with `prefix` prepended to its name.
+	#[must_use]
+	pub fn nest(mut self, prefix: &str, other: Self) -> Self {
+		self.keys.extend(other.keys.into_iter().map(|mut spec| {
+			spec.name.insert_str(0, prefix);
+			spec
+		}));
+		self
+	}
+
+	/// Returns the declared keys in declaration order.
+	#[must_use]
+	pub fn keys(&self) -> &[KeySpec] {
+		&self.keys
+	}
+
+	/// Returns the declaration for `name`, if any.
+	#[must_use]
+	pub fn get(&self, name: &str) -> Option<&KeySpec> {
+		self.keys.iter().find(|k| k.name == name)
+	}
+
+	/// Returns the declared name closest to `name`, if one is within two
+	/// edits, to suggest a fix for a likely typo.
+	pub(crate) fn closest(&self, name: &str) -> Option<&str> {
+		self
+			.keys
+			.iter()
+			.map(|k| (edit_distance(&k.name, name), k.name.as_str()))
+			.filter(|(d, _)| *d <= 2)
+			.min_by_key(|(d, _)| *d)
+			.map(|(_, k)| k)
+	}
+
+	/// Checks every declared key, looking values up with `get`, and returns
+	/// one line per problem: a required key with no default that is not set,
+	/// or a value that fails its type or validators.
+	///
+	/// Values quoted in problems are [redacted](crate::redact), so reports
+	/// are safe to print in CI logs.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use envflag::schema::{KeySpec, Schema};
+	///
+	/// let schema = Schema::new()
+	///     .key(KeySpec::new("PORT").type_name("u16"))
+	///     .key(KeySpec::new("DATABASE_URL").required());
+	/// let problems = schema.validate(|name| (name == "PORT").then(|| "http".to_owned()));
+	/// assert_eq!(
+	///     problems,
+	///     ["PORT: 'http' is not a valid u16", "DATABASE_URL: required but not set"]
+	/// );
+	/// ```
+	#[must_use]
+	pub fn validate<F: Fn(&str) -> Option<String>>(&self, get: F) -> Vec<String> {
+		let mut problems = Vec::new();
+		for spec in &self.keys {
+			match get(&spec.name) {
+				Some(value) => {
+					if let Err(e) = spec.check(&value) {
+						let e = e.with_value(&redact::redact(&spec.name, &value));
+						problems.push(format!("{}: {e}", spec.name));
+					}
+				}
+				None if spec.required && spec.default.is_none() => {
+					problems.push(format!("{}: required but not set", spec.name));
+				}
+				None => {}
+			}
+		}
+		problems
+	}
+
+	/// Renders a commented `.env.example` file declaring every key.
+	///
+	/// Each key gets its description and a line summarizing its type,
+	/// requiredness, default, and validators. Keys with a default are set to
+	/// it; required keys without one get a placeholder that passes their
+	/// checks where possible; optional keys without one are commented out.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use envflag::schema::{KeySpec, Schema};
+	///
+	/// let schema = Schema::new().key(
+	///     KeySpec::new("PORT")
+	///         .type_name("u16")
+	///         .default("8080")
+	///         .description("Port the HTTP server listens on"),
+	/// );
+	/// assert_eq!(
+	///     schema.to_example(),
+	///     "# Port the HTTP server listens on\n# u16, default 8080\nPORT=8080\n"
+	/// );
+	/// ```
+	#[must_use]
+	pub fn to_example(&self) -> String {
+		let mut out = String::new();
+		for (i, spec) in self.keys.iter().enumerate() {
+			if i > 0 {
+				out.push('\n');
+			}
+			if let Some(description) = &spec.description {
+				for line in description.lines() {
+					let _ = writeln!(out, "# {line}");
+				}
+			}
+			let mut summary = vec![spec.type_name.clone()];
+			if spec.required {
+				summary.push("required".to_owned());
+			}
+			if let Some(default) = &spec.default {
+				summary.push(format!("default {default}"));
+			}
+			if !spec.validators.is_empty() {
+				summary.push(format!("must pass {}", spec.validators.join(", ")));
+			}
+			let _ = writeln!(out, "# {}", summary.join(", "));
+			match (&spec.default, spec.required) {
+				(Some(default), _) => {
+					let _ = writeln!(out, "{}={}", spec.name, quote_value(default));
+				}
+				(None, true) => {
+					let _ = writeln!(out, "{}={}", spec.name, placeholder(spec));
+				}
+				(None, false) => {
+					let _ = writeln!(out, "# {}=", spec.name);
+				}
+			}
+		}
+		out
+	}
+
+	/// Writes [`to_example`](Self::to_example) to `path`, e.g. from a test
+	/// or build script so the sample file never drifts from the code.
+	///
+	/// # Errors
+	///
+	/// Returns `EnvflagError::Io` if the file cannot be written.
+	pub fn write_example<P: AsRef<Path>>(&self, path: P) -> Result<(), EnvflagError> {
+		fs::write(path, self.to_example())?;
+		Ok(())
+	}
+
+	/// Renders a Markdown table of every key, for a README or operator docs.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use envflag::schema::{KeySpec, Schema};
+	///
+	/// let schema = Schema::new()
+	///     .key(KeySpec::new("PORT").type_name("u16").default("8080"))
+	///     .key(KeySpec::new("DATABASE_URL").required().description("Primary database"));
+	/// assert_eq!(
+	///     schema.to_markdown(),
+	///     "| Variable | Type | Default | Required | Description |\n\
+	///      |----------|------|---------|----------|-------------|\n\
+	///      | `PORT` | `u16` | `8080` | no |  |\n\
+	///      | `DATABASE_URL` | `String` |  | yes | Primary database |\n"
+	/// );
+	/// ```
+	#[must_use]
+	pub fn to_markdown(&self) -> String {
+		let mut out = String::from(
+			"| Variable | Type | Default | Required | Description |\n\
+			 |----------|------|---------|----------|-------------|\n",
+		);
+		for spec in &self.keys {
+			let default = spec.default.as_deref().map(code).unwrap_or_default();
+			let required = if spec.required { "yes" } else { "no" };
+			let description = spec
+				.description
+				.as_deref()
+				.unwrap_or_default()
+				.lines()
+				.map(|l| l.trim().replace('|', "\\|"))
+				.collect::<Vec<_>>()
+				.join(" ");
+			let _ = writeln!(
+				out,
+				"| {} | {} | {default} | {required} | {description} |",
+				code(&spec.name),
+				code(&spec.type_name)
+			);
+		}
+		out
+	}
+
+	/// Parses a schema file, declaring keys in file order.
+	///
+	/// Each key is a `[[key]]` table; only `name` is mandatory:
+	///
+	/// ```toml
+	/// [[key]]
+	/// name = "PORT"
+	/// type = "u16"
+	/// default = "8080"
+	/// required = false
+	/// description = "Port the HTTP server listens on"
+	/// validate = ["is_port"]
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Returns `EnvflagError::InvalidSchema` for malformed TOML, unknown
+	/// fields or validators, wrongly typed fields, and duplicate keys.
+	#[cfg(feature = "toml")]
+	pub fn from_toml(text: &str) -> Result<Self, EnvflagError> {
+		let invalid = |message: String| EnvflagError::InvalidSchema { message };
+		let doc: toml::Table = text
+			.parse()
+			.map_err(|e: toml::de::Error| invalid(e.to_string()))?;
+
+		let mut schema = Self::new();
+		for (field, value) in &doc {
+			if field != "key" {
+				return Err(invalid(format!("unknown top-level field '{field}'")));
+			}
+			let Some(tables) = value.as_array() else {
+				return Err(invalid(
+					"`key` must be an array of tables ([[key]])".to_owned(),
+				));
+			};
+			for (i, table) in tables.iter().enumerate() {
+				let Some(table) = table.as_table() else {
+					return Err(invalid(format!("key #{} is not a table", i + 1)));
+				};
+				let spec = key_from_toml(table).map_err(|m| invalid(format!("key #{}: {m}", i + 1)))?;
+				if schema.get(&spec.name).is_some() {
+					return Err(invalid(format!("duplicate key '{}'", spec.name)));
+				}
+				schema = schema.key(spec);
+			}
+		}
+		Ok(schema)
+	}
+}
+
+/// Counts the insertions, deletions, substitutions, and adjacent swaps
+/// needed to turn `a` into `b`.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+	let a: Vec<char> = a.chars().collect();
+	let b: Vec<char> = b.chars().collect();
+	// d[i][j] is the distance between a[..i] and b[..j].
+	let mut d: Vec<Vec<usize>> = (0..=a.len())
+		.map(|i| {
+			(0..=b.len())
+				.map(|j| {
+					if i == 0 {
+						j
+					} else if j == 0 {
+						i
+					} else {
+						0
+					}
+				})
+				.collect()
+		})
+		.collect();
+	for i in 1..=a.len() {
+		for j in 1..=b.len() {
+			let cost = usize::from(a[i - 1] != b[j - 1]);
+			d[i][j] = (d[i - 1][j] + 1)
+				.min(d[i][j - 1] + 1)
+				.min(d[i - 1][j - 1] + cost);
+			if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+				d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+			}
+		}
+	}
+	d[a.len()][b.len()]
+}
+
+/// Wraps `text` in a Markdown code span that is safe inside a table cell.
+fn code(text: &str) -> String {
+	if text.is_empty() {
+		return String::new();
+	}
+	let text = text.replace('|', "\\|").replace('\n', " ");
+	if text.contains('`') {
+		format!("`` {text} ``")
+	} else {
+		format!("`{text}`")
+	}
+}
+
+/// Returns a sample value for a required key with no default.
+fn placeholder(spec: &KeySpec) -> &'static str {
+	let by_validator = spec.validators.iter().find_map(|v| {
+		Some(match v.as_str() {
+			"is_port" => "8080",
+			"is_url" => "https://example.com",
+			"is_bool" => "false",
+			"is_integer" | "is_positive_integer" | "is_positive_number" => "1",
+			"is_git_url" | "is_ssh_url" => "git@example.com:org/repo.git",
+			"is_docker_image_ref" => "example/image:latest",
+			_ => return None,
+		})
+	});
+	by_validator.unwrap_or(match spec.type_name.as_str() {
+		"bool" => "false",
+		"char" => "x",
+		"f32" | "f64" | "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32"
+		| "i64" | "i128" | "isize" => "1",
+		_ => "changeme",
+	})
+}
+
+/// Renders a value so dotenv parsers read it back unchanged.
+///
+/// Plain values are written bare; anything else is double-quoted with `\`,
+/// `"`, `$`, and newlines escaped.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::schema::quote_value;
+///
+/// assert_eq!(quote_value("8080"), "8080");
+/// assert_eq!(quote_value("a b"), "\"a b\"");
+/// ```
+#[must_use]
+pub fn quote_value(value: &str) -> String {
+	let plain = |c: char| c.is_ascii_alphanumeric() || "-_./:@,+=%".contains(c);
+	if !value.is_empty() && value.chars().all(plain) {
+		return value.to_owned();
+	}
+
+	let mut out = String::with_capacity(value.len() + 2);
+	out.push('"');
+	for c in value.chars() {
+		match c {
+			'"' | '\\' | '$' => {
+				out.push('\\');
+				out.push(c);
+			}
+			'\n' => out.push_str("\\n"),
+			_ => out.push(c),
+		}
+	}
+	out.push('"');
+	out
+}
+
+/// Builds one [`KeySpec`] from a `[[key]]` table.
+#[cfg(feature = "toml")]
+fn key_from_toml(table: &toml::Table) -> Result<KeySpec, String> {
+	let string = |field: &str| match table.get(field) {
+		None => Ok(None),
+		Some(toml::Value::String(s)) => Ok(Some(s.as_str())),
+		Some(_) => Err(format!("`{field}` must be a string")),
+	};
+
+	let name = string("name")?.ok_or("missing `name`")?;
+	if name.is_empty() {
+		return Err("`name` must not be empty".to_owned());
+	}
+	let mut spec = KeySpec::new(name);
+	if let Some(t) = string("type")? {
+		spec = spec.type_name(t);
+	}
+	if let Some(d) = string("default")? {
+		spec = spec.default(d);
+	}
+	if let Some(d) = string("description")? {
+		spec = spec.description(d);
+	}
+	match table.get("required") {
+		None | Some(toml::Value::Boolean(false)) => {}
+		Some(toml::Value::Boolean(true)) => spec = spec.required(),
+		Some(_) => return Err("`required` must be a boolean".to_owned()),
+	}
+	if let Some(list) = table.get("validate") {
+		let list = list.as_array().ok_or("`validate` must be an array")?;
+		for v in list {
+			let v = v.as_str().ok_or("`validate` entries must be strings")?;
+			if validators::by_name(v).is_none() {
+				return Err(format!("unknown validator '{v}'"));
+			}
+			spec = spec.validator(v);
+		}
+	}
+	if let Some(field) = table.keys().find(|k| {
+		!matches!(
+			k.as_str(),
+			"name" | "type" | "default" | "description" | "required" | "validate"
+		)
+	}) {
+		return Err(format!("unknown field '{field}'"));
+	}
+	if let Some(d) = &spec.default {
+		spec.check(d).map_err(|m| format!("invalid default: {m}"))?;
+	}
+	Ok(spec)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn example_parses_and_passes_checks() {
+		let schema = Schema::new()
+			.key(
+				KeySpec::new("PORT")
+					.type_name("u16")
+					.default("8080")
+					.validator("is_port"),
+			)
+			.key(
+				KeySpec::new("DATABASE_URL")
+					.required()
+					.validator("is_url")
+					.description("Primary database\nRead-write"),
+			)
+			.key(KeySpec::new("GREETING").default("hello $USER"))
+			.key(KeySpec::new("WORKERS").type_name("usize").required())
+			.key(KeySpec::new("LOG_FILTER"));
+		let example = schema.to_example();
+		assert_eq!(
+			example,
+			"# u16, default 8080, must pass is_port\nPORT=8080\n\n\
+			 # Primary database\n# Read-write\n# String, required, must pass is_url\n\
+			 DATABASE_URL=https://example.com\n\n\
+			 # String, default hello $USER\nGREETING=\"hello \\$USER\"\n\n\
+			 # usize, required\nWORKERS=1\n\n\
+			 # String\n# LOG_FILTER=\n"
+		);
+
+		let vars: std::collections::HashMap<_, _> = dotenvy::from_read_iter(example.as_bytes())
+			.collect::<Result<_, _>>()
+			.unwrap();
+		assert_eq!(vars["GREETING"], "hello $USER");
+		assert!(schema.validate(|k| vars.get(k).cloned()).is_empty());
+	}
+
+	#[test]
+	fn validate_redacts_only_the_value() {
+		let schema = Schema::new()
+			.key(KeySpec::new("API_TOKEN").validator("is_url"))
+			.key(KeySpec::new("PORT").type_name("u16"));
+		// Secrets that happen to match other words of the message.
+		let problems =
+			schema.validate(|name| Some(if name == "PORT" { "u16" } else { "is_url" }.to_owned()));
+		assert_eq!(
+			problems,
+			[
+				"API_TOKEN: '********' does not pass is_url",
+				"PORT: 'u16' is not a valid u16"
+			]
+		);
+	}
+
+	#[test]
+	fn quoted_values_round_trip() {
+		for value in [
+			"plain",
+			"",
+			"with space",
+			"a#b",
+			"say \"hi\"",
+			"back\\slash",
+			"$HOME",
+			"line1\nline2",
+			"postgres://u:p@h:5432/db?x=1",
+		] {
+			let line = format!("K={}\n", quote_value(value));
+			let parsed: Vec<_> = dotenvy::from_read_iter(line.as_bytes())
+				.collect::<Result<_, _>>()
+				.unwrap();
+			assert_eq!(parsed, [("K".to_owned(), value.to_owned())], "{line}");
+		}
+	}
+
+	#[test]
+	fn closest_suggests_near_names() {
+		let schema = Schema::new()
+			.key(KeySpec::new("PORT"))
+			.key(KeySpec::new("HOST"));
+		assert_eq!(schema.closest("PROT"), Some("PORT"));
+		assert_eq!(schema.closest("HOSTS"), Some("HOST"));
+		assert_eq!(schema.closest("DATABASE_URL"), None);
+	}
+
+	#[test]
+	fn markdown_escapes_cells() {
+		let schema = Schema::new().key(
+			KeySpec::new("SEP")
+				.default("a|b")
+				.description("Split on `|`\nor commas"),
+		);
+		assert!(
+			schema
+				.to_markdown()
+				.ends_with("| `SEP` | `String` | `a\\|b` | no | Split on `\\|` or commas |\n")
+		);
+	}
+
+	#[cfg(feature = "toml")]
+	#[test]
+	fn from_toml_in_file_order() {
+		let schema = Schema::from_toml(
+			r#"
+			[[key]]
+			name = "PORT"
+			type = "u16"
+			default = "8080"
+			validate = ["is_port"]
+
+			[[key]]
+			name = "DATABASE_URL"
+			required = true
+			description = "Primary database"
+			"#,
+		)
+		.unwrap();
+		let names: Vec<_> = schema.keys().iter().map(|k| k.name.as_str()).collect();
+		assert_eq!(names, ["PORT", "DATABASE_URL"]);
+		assert_eq!(schema.keys()[0].validators, ["is_port"]);
+		assert!(schema.keys()[1].required);
+	}
+
+	#[cfg(feature = "toml")]
+	#[test]
+	fn from_toml_rejects_mistakes() {
+		for doc in [
+			"[[key]]\ntype = \"u16\"",
+			"[[key]]\nname = \"A\"\n[[key]]\nname = \"A\"",
+			"[[key]]\nname = \"A\"\nvalidate = [\"is_nope\"]",
+			"[[key]]\nname = \"A\"\ntype = \"u8\"\ndefault = \"300\"",
+			"[[key]]\nname = \"A\"\ncolour = \"red\"",
+			"other = 1",
+		] {
+			assert!(
+				matches!(
+					Schema::from_toml(doc),
+					Err(EnvflagError::InvalidSchema { .. })
+				),
+				"{doc}"
+			);
+		}
+	}
+}
@@ -0,0 +1,88 @@
+/* src/secret.rs */
+
+//! A wrapper that keeps secret values out of logs.
+
+use std::fmt;
+
+/// A secret value whose `Debug` and `Display` print `[REDACTED]`.
+///
+/// The value is only reachable through [`expose`](Self::expose), so it
+/// cannot end up in a log line by accident. With the `zeroize` feature the
+/// value is also overwritten with zeros when the `Secret` is dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::Secret;
+///
+/// let token = Secret::new("hunter2".to_owned());
+/// assert_eq!(format!("{token} {token:?}"), "[REDACTED] [REDACTED]");
+/// assert_eq!(token.expose(), "hunter2");
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret<T: SecretValue = String>(T);
+
+/// Types that can be held in a [`Secret`].
+pub trait SecretValue: sealed::Sealed {
+	/// Overwrites the value in place before it is dropped.
+	#[doc(hidden)]
+	fn wipe(&mut self);
+}
+
+mod sealed {
+	pub trait Sealed {}
+	impl Sealed for String {}
+	impl Sealed for Vec<u8> {}
+}
+
+impl SecretValue for String {
+	fn wipe(&mut self) {
+		#[cfg(feature = "zeroize")]
+		zeroize::Zeroize::zeroize(self);
+	}
+}
+
+impl SecretValue for Vec<u8> {
+	fn wipe(&mut self) {
+		#[cfg(feature = "zeroize")]
+		zeroize::Zeroize::zeroize(self);
+	}
+}
+
+impl<T: SecretValue> Secret<T> {
+	/// Wraps `value`.
+	#[must_use]
+	pub const fn new(value: T) -> Self {
+		Self(value)
+	}
+
+	/// Returns the secret value.
+	#[must_use]
+	pub const fn expose(&self) -> &T {
+		&self.0
+	}
+}
+
+impl<T: SecretValue> From<T> for Secret<T> {
+	fn from(value: T) -> Self {
+		Self(value)
+	}
+}
+
+impl<T: SecretValue> Drop for Secret<T> {
+	fn drop(&mut self) {
+		self.0.wipe();
+	}
+}
+
+impl<T: SecretValue> fmt::Debug for Secret<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("[REDACTED]")
+	}
+}
+
+impl<T: SecretValue> fmt::Display for Secret<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("[REDACTED]")
+	}
+}
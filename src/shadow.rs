@@ -0,0 +1,107 @@
+/* src/shadow.rs */
+
+//! Shadow reads: comparing a secondary configuration against the primary.
+//!
+//! When moving configuration from one source to another (e.g. from `.env`
+//! to a secrets manager), the new setup can be loaded alongside the old one
+//! via [`InitBuilder::shadow`](crate::InitBuilder::shadow). Values are always
+//! served from the primary; every disagreement is recorded here.
+
+use std::collections::BTreeSet;
+
+use crate::store::EnvStore;
+
+/// The keys on which a shadow configuration disagrees with the primary.
+///
+/// Only key names are recorded, never values, so a report is always safe to
+/// log. Each list is sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ShadowReport {
+	/// Keys set only in the primary.
+	pub only_primary: Vec<String>,
+	/// Keys set only in the secondary.
+	pub only_secondary: Vec<String>,
+	/// Keys set in both with different values.
+	pub differing: Vec<String>,
+	/// Why the secondary could not be loaded, if it failed.
+	pub error: Option<String>,
+}
+
+impl ShadowReport {
+	/// Compares two stores.
+	#[must_use]
+	pub fn between(primary: &EnvStore, secondary: &EnvStore) -> Self {
+		let a = primary.entry_map();
+		let b = secondary.entry_map();
+		let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+
+		let mut report = Self::default();
+		for k in keys {
+			match (a.get(k), b.get(k)) {
+				(Some(_), None) => report.only_primary.push(k.clone()),
+				(None, Some(_)) => report.only_secondary.push(k.clone()),
+				(Some(x), Some(y)) if x != y => report.differing.push(k.clone()),
+				_ => {}
+			}
+		}
+		report
+	}
+
+	/// A report for a secondary that failed to load.
+	pub(crate) fn failed(error: String) -> Self {
+		Self {
+			error: Some(error),
+			..Self::default()
+		}
+	}
+
+	/// Returns `true` if the secondary loaded and agrees on every key.
+	#[must_use]
+	pub fn is_clean(&self) -> bool {
+		self.error.is_none()
+			&& self.only_primary.is_empty()
+			&& self.only_secondary.is_empty()
+			&& self.differing.is_empty()
+	}
+}
+
+/// Logs a report as a warning unless it is clean.
+pub(crate) fn log(_report: &ShadowReport) {
+	#[cfg(feature = "tracing")]
+	if !_report.is_clean() {
+		tracing::warn!(
+			only_primary = ?_report.only_primary,
+			only_secondary = ?_report.only_secondary,
+			differing = ?_report.differing,
+			error = ?_report.error,
+			"envflag shadow configuration disagrees with primary"
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn make_store(pairs: &[(&str, &str)]) -> EnvStore {
+		EnvStore::from_map(
+			pairs
+				.iter()
+				.map(|(k, v)| ((*k).into(), (*v).into()))
+				.collect(),
+		)
+	}
+
+	#[test]
+	fn report_between_stores() {
+		let primary = make_store(&[("A", "1"), ("B", "2"), ("C", "3")]);
+		let secondary = make_store(&[("A", "1"), ("B", "20"), ("D", "4")]);
+		let report = ShadowReport::between(&primary, &secondary);
+		assert_eq!(report.only_primary, ["C"]);
+		assert_eq!(report.only_secondary, ["D"]);
+		assert_eq!(report.differing, ["B"]);
+		assert!(!report.is_clean());
+		assert!(ShadowReport::between(&primary, &primary).is_clean());
+	}
+}
@@ -0,0 +1,331 @@
+/* src/source.rs */
+
+//! Source layers beyond the `.env` file and the process environment.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use crate::error::EnvflagError;
+
+/// A custom origin of variables, such as a database, an HTTP endpoint, or a
+/// file format envflag does not parse itself.
+///
+/// Register one with [`InitBuilder::source`](crate::InitBuilder::source).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use envflag::{EnvflagError, Source};
+///
+/// struct Fixed;
+///
+/// impl Source for Fixed {
+///     fn load(&self) -> Result<HashMap<String, String>, EnvflagError> {
+///         Ok(HashMap::from([("PORT".into(), "8080".into())]))
+///     }
+/// }
+/// ```
+pub trait Source: Send + Sync {
+	/// Loads every variable this source provides.
+	///
+	/// Called by `init()` and again on every reload.
+	///
+	/// # Errors
+	///
+	/// Any error fails `init()` (or the reload) and is returned as is.
+	fn load(&self) -> Result<HashMap<String, String>, EnvflagError>;
+
+	/// Describes the source for [`layers()`](crate::layers), e.g. a URL.
+	fn origin(&self) -> Option<String> {
+		None
+	}
+}
+
+impl fmt::Debug for dyn Source {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Source")
+			.field("origin", &self.origin())
+			.finish_non_exhaustive()
+	}
+}
+
+/// The format of a configuration document read from stdin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Format {
+	/// `.env` syntax, parsed exactly like a `.env` file.
+	Dotenv,
+	/// A flat JSON object of strings, numbers, and booleans. `null` values
+	/// are skipped.
+	#[cfg(feature = "json")]
+	Json,
+}
+
+impl Format {
+	pub(crate) fn parser(self) -> ReaderParser {
+		match self {
+			Self::Dotenv => parse_dotenv,
+			#[cfg(feature = "json")]
+			Self::Json => parse_json,
+		}
+	}
+}
+
+/// A directory whose regular files each hold one variable: the file name is
+/// the key and the trimmed contents are the value.
+///
+/// Hidden files are skipped, which also skips the `..data` links Kubernetes
+/// places in mounted volumes.
+#[derive(Debug)]
+pub(crate) struct DirSource {
+	path: PathBuf,
+	required: bool,
+}
+
+impl DirSource {
+	/// A directory that must exist.
+	pub(crate) fn required(path: PathBuf) -> Self {
+		Self {
+			path,
+			required: true,
+		}
+	}
+
+	/// A directory that is skipped if it does not exist.
+	pub(crate) fn optional(path: PathBuf) -> Self {
+		Self {
+			path,
+			required: false,
+		}
+	}
+
+	pub(crate) fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Reads every file, or returns `None` for a missing optional directory.
+	pub(crate) fn load(&self) -> Result<Option<HashMap<String, String>>, EnvflagError> {
+		if !self.required && !self.path.is_dir() {
+			return Ok(None);
+		}
+		let mut vars = HashMap::new();
+		for entry in fs::read_dir(&self.path)? {
+			let path = entry?.path();
+			let Some(key) = path.file_name().and_then(|n| n.to_str()) else {
+				continue;
+			};
+			if key.starts_with('.') || !path.is_file() {
+				continue;
+			}
+			let bytes = fs::read(&path)?;
+			let value = String::from_utf8(bytes).map_err(|_| EnvflagError::Source {
+				origin: path.display().to_string(),
+				message: "file is not valid UTF-8".to_owned(),
+			})?;
+			vars.insert(key.to_owned(), value.trim().to_owned());
+		}
+		Ok(Some(vars))
+	}
+}
+
+/// Parses the full contents of a reader into key-value pairs.
+pub(crate) type ReaderParser = fn(&[u8]) -> Result<HashMap<String, String>, String>;
+
+/// A layer read once from an arbitrary reader, such as stdin.
+///
+/// The reader is consumed on first load and the parsed content cached, so
+/// reloads see the same values instead of an exhausted stream.
+pub(crate) struct ReaderSource {
+	origin: String,
+	parse: ReaderParser,
+	reader: Mutex<Option<Box<dyn Read + Send>>>,
+	is_stdin: bool,
+	cache: OnceLock<HashMap<String, String>>,
+}
+
+impl fmt::Debug for ReaderSource {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ReaderSource")
+			.field("origin", &self.origin)
+			.finish_non_exhaustive()
+	}
+}
+
+impl ReaderSource {
+	pub(crate) fn new(origin: &str, parse: ReaderParser, reader: Box<dyn Read + Send>) -> Self {
+		Self {
+			origin: origin.to_owned(),
+			parse,
+			reader: Mutex::new(Some(reader)),
+			is_stdin: false,
+			cache: OnceLock::new(),
+		}
+	}
+
+	/// Reads from the process's stdin, refusing to block on a terminal.
+	pub(crate) fn stdin(parse: ReaderParser) -> Self {
+		Self {
+			is_stdin: true,
+			..Self::new("stdin", parse, Box::new(std::io::stdin()))
+		}
+	}
+
+	/// Describes where the layer comes from.
+	pub(crate) fn origin(&self) -> &str {
+		&self.origin
+	}
+
+	/// Reads and parses the reader on first call; returns the cached result
+	/// afterwards.
+	pub(crate) fn load(&self) -> Result<HashMap<String, String>, EnvflagError> {
+		if let Some(vars) = self.cache.get() {
+			return Ok(vars.clone());
+		}
+
+		let mut reader = self.reader.lock().unwrap_or_else(PoisonError::into_inner);
+		// Another thread may have loaded it while we waited for the lock.
+		if let Some(vars) = self.cache.get() {
+			return Ok(vars.clone());
+		}
+		if self.is_stdin && std::io::stdin().is_terminal() {
+			return Err(
+				self.error("stdin is a terminal; pipe the configuration in, e.g. `my-app < config.env`"),
+			);
+		}
+		let Some(mut r) = reader.take() else {
+			return Err(self.error("reader was already consumed by a failed load"));
+		};
+
+		let mut buf = Vec::new();
+		r.read_to_end(&mut buf)?;
+		let vars = (self.parse)(&buf).map_err(|message| self.error(&message))?;
+		Ok(self.cache.get_or_init(|| vars).clone())
+	}
+
+	fn error(&self, message: &str) -> EnvflagError {
+		EnvflagError::Source {
+			origin: self.origin.clone(),
+			message: message.to_owned(),
+		}
+	}
+}
+
+/// Parses `KEY=VALUE\0` records, as produced by `env -0`.
+///
+/// Values may contain newlines and `=`; an empty trailing record is ignored.
+pub(crate) fn parse_null_separated(buf: &[u8]) -> Result<HashMap<String, String>, String> {
+	let text = std::str::from_utf8(buf).map_err(|e| format!("input is not valid UTF-8: {e}"))?;
+	text
+		.split('\0')
+		.filter(|record| !record.is_empty())
+		.map(|record| match record.split_once('=') {
+			Some((k, v)) if !k.is_empty() => Ok((k.to_owned(), v.to_owned())),
+			_ => Err(format!("malformed record '{record}': expected KEY=VALUE")),
+		})
+		.collect()
+}
+
+/// Parses a document in `.env` syntax.
+fn parse_dotenv(buf: &[u8]) -> Result<HashMap<String, String>, String> {
+	dotenvy::from_read_iter(buf)
+		.collect::<Result<_, _>>()
+		.map_err(|e| e.to_string())
+}
+
+/// Parses a flat JSON object.
+#[cfg(feature = "json")]
+fn parse_json(buf: &[u8]) -> Result<HashMap<String, String>, String> {
+	use serde_json::Value;
+
+	let Value::Object(object) = serde_json::from_slice(buf).map_err(|e| e.to_string())? else {
+		return Err("expected a JSON object at the top level".to_owned());
+	};
+	let mut vars = HashMap::with_capacity(object.len());
+	for (k, v) in object {
+		let value = match v {
+			Value::Null => continue,
+			Value::String(s) => s,
+			Value::Bool(b) => b.to_string(),
+			Value::Number(n) => n.to_string(),
+			Value::Array(_) | Value::Object(_) => {
+				return Err(format!(
+					"value of '{k}' must be a string, number, or boolean"
+				));
+			}
+		};
+		vars.insert(k, value);
+	}
+	Ok(vars)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dir_source_reads_one_variable_per_file() {
+		let dir = std::env::temp_dir().join(format!("envflag-dir-{}", std::process::id()));
+		fs::create_dir_all(dir.join("nested")).unwrap();
+		fs::write(dir.join("db_password"), "hunter2\n").unwrap();
+		fs::write(dir.join("API_KEY"), "  key  ").unwrap();
+		fs::write(dir.join(".hidden"), "x").unwrap();
+
+		let vars = DirSource::optional(dir.clone()).load().unwrap().unwrap();
+		fs::remove_dir_all(&dir).unwrap();
+		assert_eq!(vars.len(), 2);
+		assert_eq!(vars["db_password"], "hunter2");
+		assert_eq!(vars["API_KEY"], "key");
+
+		assert!(DirSource::optional(dir).load().unwrap().is_none());
+	}
+
+	#[test]
+	fn null_separated_records() {
+		let vars = parse_null_separated(b"A=1\0MULTI=line1\nline2\0EQ=a=b\0").unwrap();
+		assert_eq!(vars.len(), 3);
+		assert_eq!(vars["MULTI"], "line1\nline2");
+		assert_eq!(vars["EQ"], "a=b");
+	}
+
+	#[test]
+	fn null_separated_malformed() {
+		assert!(parse_null_separated(b"A=1\0NOPE\0").is_err());
+		assert!(parse_null_separated(b"=1\0").is_err());
+		assert!(parse_null_separated(&[0xff, b'=', b'1']).is_err());
+	}
+
+	#[test]
+	fn dotenv_document() {
+		let vars = parse_dotenv(b"# comment\nA=1\nB=\"quoted value\"\n").unwrap();
+		assert_eq!(vars.len(), 2);
+		assert_eq!(vars["B"], "quoted value");
+		assert!(parse_dotenv(b"A='unterminated\n").is_err());
+	}
+
+	#[cfg(feature = "json")]
+	#[test]
+	fn json_document() {
+		let vars = parse_json(br#"{"A":"x","PORT":8080,"DEBUG":true,"SKIP":null}"#).unwrap();
+		assert_eq!(vars.len(), 3);
+		assert_eq!(vars["PORT"], "8080");
+		assert_eq!(vars["DEBUG"], "true");
+		assert!(parse_json(br#"{"A":[1]}"#).is_err());
+		assert!(parse_json(b"[]").is_err());
+	}
+
+	#[test]
+	fn reader_source_caches_content() {
+		let source = ReaderSource::new(
+			"test",
+			parse_null_separated,
+			Box::new(std::io::Cursor::new(b"A=1\0".to_vec())),
+		);
+		assert_eq!(source.load().unwrap()["A"], "1");
+		assert_eq!(source.load().unwrap()["A"], "1");
+	}
+}
@@ -2,37 +2,180 @@
 
 //! Internal environment storage and initialization.
 
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
+use std::fs;
+use std::io::Read;
+use std::ops::Deref;
 use std::path::{Path, PathBuf};
-use std::sync::OnceLock;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
 
 use crate::error::EnvflagError;
+use crate::layer::{LayerInfo, LayerKind};
+use crate::migration::{self, Migration, MigrationStep};
+use crate::redact;
+use crate::reload::RefreshKey;
+use crate::schema::{self, Schema};
+use crate::shadow::{self, ShadowReport};
+use crate::source::{self, DirSource, Format, ReaderSource, Source};
+#[cfg(feature = "usage")]
+use crate::usage;
+use crate::usage::DefaultReason;
+
+/// Variables naming the active profile, in priority order; see
+/// [`InitBuilder::profile`].
+const PROFILE_VARS: [&str; 2] = ["APP_ENV", "RUST_ENV"];
 
 /// Global instance of the environment store.
-pub(crate) static INSTANCE: OnceLock<EnvStore> = OnceLock::new();
+pub(crate) static INSTANCE: RwLock<Option<Instance>> = RwLock::new(None);
+
+/// Variables `init` exported from `.env` files to the process environment,
+/// with the values it set. Rebuilding the store reads them from their files
+/// again rather than as process variables, which would outrank the files.
+static EXPORTED: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+thread_local! {
+	/// Stores installed by [`with_store`](crate::with_store) on this thread,
+	/// innermost last.
+	static OVERRIDES: RefCell<Vec<Arc<EnvStore>>> = const { RefCell::new(Vec::new()) };
+}
+
+#[cfg(feature = "test-default")]
+thread_local! {
+	/// Empty store queried before initialization when the `test-default`
+	/// feature is enabled.
+	static TEST_DEFAULT: Arc<EnvStore> = Arc::new(EnvStore::from_map(HashMap::new()));
+}
+
+/// The live global store together with the builder it was loaded from, which
+/// is kept so the store can be rebuilt on reload.
+#[derive(Debug)]
+pub(crate) struct Instance {
+	pub(crate) store: Arc<EnvStore>,
+	pub(crate) builder: Arc<InitBuilder>,
+}
+
+/// A store handle: either borrowed from the caller or a snapshot of the
+/// global instance.
+#[derive(Debug)]
+pub(crate) enum StoreRef<'a> {
+	Borrowed(&'a EnvStore),
+	Shared(Arc<EnvStore>),
+}
+
+impl Deref for StoreRef<'_> {
+	type Target = EnvStore;
+
+	fn deref(&self) -> &EnvStore {
+		match self {
+			Self::Borrowed(s) => s,
+			Self::Shared(s) => s,
+		}
+	}
+}
 
 /// Internal storage for environment variables and configuration.
 ///
 /// This type holds the loaded environment variables and any configured
-/// prefixes.  It is normally created via [`InitBuilder`] and stored in a
-/// global [`OnceLock`], but can also be constructed directly with
+/// prefixes.  It is normally created via [`InitBuilder`] and installed as the
+/// global instance, but can also be constructed directly with
 /// [`EnvStore::from_map`] for unit-testing purposes.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct EnvStore {
 	map: HashMap<String, String>,
 	prefixes: Vec<String>,
+	chained: bool,
+	layers: Vec<LayerInfo>,
+	/// Index into `layers` of the layer each key's value came from.
+	sources: HashMap<String, usize>,
+	/// Every layer's value for each key backed by
+	/// [`InitBuilder::refresh_key`], as `(layer index, value)` in layer
+	/// order, so a refresh can re-resolve the key.
+	refreshable: HashMap<String, Vec<(usize, String)>>,
+	profile: Option<String>,
+	shadow: Option<ShadowReport>,
+	on_demand: Option<Arc<OnDemand>>,
+	/// Keep values out of parse and validation errors.
+	redact_errors: bool,
+	/// Reads counted by [`lookup`](Self::lookup) and defaulted keys, shared
+	/// with copies of this store made by refreshes and reloads.
+	#[cfg(feature = "usage")]
+	usage: Arc<usage::Usage>,
+}
+
+/// Process variables read lazily by a store built with
+/// [`InitBuilder::on_demand`], cached per store.
+#[derive(Debug, Default)]
+struct OnDemand {
+	cache: RwLock<HashMap<String, Option<String>>>,
+}
+
+impl OnDemand {
+	fn get(&self, key: &str) -> Option<String> {
+		if let Some(v) = self
+			.cache
+			.read()
+			.unwrap_or_else(PoisonError::into_inner)
+			.get(key)
+		{
+			return v.clone();
+		}
+		let v = process_var(key);
+		self
+			.cache
+			.write()
+			.unwrap_or_else(PoisonError::into_inner)
+			.insert(key.to_owned(), v.clone());
+		v
+	}
 }
 
 impl EnvStore {
-	pub(crate) fn get_instance() -> Result<&'static Self, EnvflagError> {
-		INSTANCE.get().ok_or(EnvflagError::NotInitialized)
+	pub(crate) fn get_instance() -> Result<Arc<Self>, EnvflagError> {
+		if let Some(store) = OVERRIDES.with_borrow(|o| o.last().cloned()) {
+			return Ok(store);
+		}
+
+		let instance = INSTANCE
+			.read()
+			.unwrap_or_else(PoisonError::into_inner)
+			.as_ref()
+			.map(|i| Arc::clone(&i.store));
+
+		#[cfg(feature = "test-default")]
+		let instance = instance.or_else(|| Some(TEST_DEFAULT.with(Arc::clone)));
+
+		instance.ok_or(EnvflagError::NotInitialized)
+	}
+
+	/// Runs `f` with `store` in place of the global instance on this thread.
+	pub(crate) fn scoped<R>(store: Arc<Self>, f: impl FnOnce() -> R) -> R {
+		struct Pop;
+		impl Drop for Pop {
+			fn drop(&mut self) {
+				OVERRIDES.with_borrow_mut(Vec::pop);
+			}
+		}
+
+		OVERRIDES.with_borrow_mut(|o| o.push(store));
+		let _pop = Pop;
+		f()
+	}
+
+	/// Returns `store` if given, otherwise a snapshot of the global instance.
+	pub(crate) fn resolve(store: Option<&Self>) -> Result<StoreRef<'_>, EnvflagError> {
+		match store {
+			Some(s) => Ok(StoreRef::Borrowed(s)),
+			None => Self::get_instance().map(StoreRef::Shared),
+		}
 	}
 
 	/// Creates an `EnvStore` directly from a map of key-value pairs.
 	///
 	/// This is intended for **testing**: it lets you construct a store without
-	/// touching the global [`OnceLock`], so every test can have its own
+	/// touching the global instance, so every test can have its own
 	/// isolated instance.
 	///
 	/// # Examples
@@ -51,6 +194,16 @@ impl EnvStore {
 		Self {
 			map,
 			prefixes: Vec::new(),
+			chained: false,
+			layers: Vec::new(),
+			sources: HashMap::new(),
+			refreshable: HashMap::new(),
+			profile: None,
+			shadow: None,
+			on_demand: None,
+			redact_errors: false,
+			#[cfg(feature = "usage")]
+			usage: Arc::default(),
 		}
 	}
 
@@ -60,35 +213,103 @@ impl EnvStore {
 	/// configuration, useful for testing prefix-related logic.
 	#[must_use]
 	pub fn from_map_with_prefixes(map: HashMap<String, String>, prefixes: Vec<String>) -> Self {
-		Self { map, prefixes }
+		Self {
+			map,
+			prefixes,
+			chained: false,
+			layers: Vec::new(),
+			sources: HashMap::new(),
+			refreshable: HashMap::new(),
+			profile: None,
+			shadow: None,
+			on_demand: None,
+			redact_errors: false,
+			#[cfg(feature = "usage")]
+			usage: Arc::default(),
+		}
+	}
+
+	/// Creates an `EnvStore` from a map with a prefix priority chain; see
+	/// [`InitBuilder::prefix_chain`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::collections::HashMap;
+	/// use envflag::store::EnvStore;
+	///
+	/// let store = EnvStore::from_map_with_prefix_chain(
+	///     HashMap::from([
+	///         ("APP_PORT".into(), "80".into()),
+	///         ("MYAPP_PORT".into(), "8080".into()),
+	///         ("HOST".into(), "localhost".into()),
+	///     ]),
+	///     vec!["MYAPP_".into(), "APP_".into(), String::new()],
+	/// );
+	/// assert_eq!(store.lookup("PORT", None), Some("8080".to_owned()));
+	/// assert_eq!(store.lookup("HOST", None), Some("localhost".to_owned()));
+	/// ```
+	#[must_use]
+	pub fn from_map_with_prefix_chain(map: HashMap<String, String>, prefixes: Vec<String>) -> Self {
+		Self {
+			chained: true,
+			..Self::from_map_with_prefixes(map, prefixes)
+		}
 	}
 
 	/// Looks up a key in the store.
 	///
 	/// When prefixes are configured:
 	/// - Single prefix: automatically prepends it to reconstruct the original key.
+	/// - Prefix chain: tries each prefix in priority order.
 	/// - Multiple prefixes: `preferred_prefix` **must** be specified; otherwise returns `None`.
 	///
 	/// When no prefixes are configured, looks up the key directly.
+	///
+	/// With the `usage` feature, each hit is counted in
+	/// [`usage_report`](Self::usage_report).
 	#[must_use]
 	pub fn lookup(&self, key: &str, preferred_prefix: Option<&str>) -> Option<String> {
+		let (_full, value) = self.find(key, preferred_prefix)?;
+		#[cfg(feature = "usage")]
+		self.usage.record(&_full);
+		Some(value)
+	}
+
+	/// Like [`lookup`](Self::lookup), but not counted as a read by the
+	/// application; used for the crate's own startup checks.
+	pub(crate) fn peek(&self, key: &str, preferred_prefix: Option<&str>) -> Option<String> {
+		self.find(key, preferred_prefix).map(|(_, value)| value)
+	}
+
+	/// Resolves a short name to the full variable name that is set and its
+	/// value; see [`lookup`](Self::lookup).
+	fn find(&self, key: &str, preferred_prefix: Option<&str>) -> Option<(String, String)> {
+		self
+			.candidates(key, preferred_prefix)
+			.into_iter()
+			.find_map(|full| self.raw(&full).map(|value| (full, value)))
+	}
+
+	/// Returns the full variable names a short name is looked up under, in
+	/// priority order.
+	fn candidates(&self, key: &str, preferred_prefix: Option<&str>) -> Vec<String> {
 		if self.prefixes.is_empty() {
 			// No prefix mode — direct lookup.
-			return self.map.get(key).cloned();
+			return vec![key.to_owned()];
 		}
 
 		// Prefix mode — reconstruct the original key.
 		if let Some(p) = preferred_prefix {
-			return self.map.get(&format!("{p}{key}")).cloned();
+			return vec![format!("{p}{key}")];
 		}
 
-		if self.prefixes.len() == 1 {
-			let p = &self.prefixes[0];
-			return self.map.get(&format!("{p}{key}")).cloned();
+		if self.prefixes.len() == 1 || self.chained {
+			return self.prefixes.iter().map(|p| format!("{p}{key}")).collect();
 		}
 
 		// Multiple prefixes without explicit choice — cannot resolve.
-		None
+		Vec::new()
 	}
 
 	/// Returns the configured prefixes.
@@ -97,6 +318,249 @@ impl EnvStore {
 		&self.prefixes
 	}
 
+	/// Returns `true` if short names cannot be resolved without choosing a
+	/// prefix: several prefixes are configured and they do not form a chain.
+	pub(crate) fn needs_prefix(&self) -> bool {
+		self.prefixes.len() > 1 && !self.chained
+	}
+
+	/// Returns `true` if errors must not include values; see
+	/// [`InitBuilder::redact_values_in_errors`].
+	pub(crate) const fn redacts_values_in_errors(&self) -> bool {
+		self.redact_errors
+	}
+
+	/// Sets whether errors must not include values, for stores derived from
+	/// this one.
+	#[cfg(feature = "serde")]
+	pub(crate) const fn with_redacted_errors(mut self, enabled: bool) -> Self {
+		self.redact_errors = enabled;
+		self
+	}
+
+	/// Returns the source layers this store was assembled from.
+	///
+	/// Layers are listed in the order they were applied, so later entries
+	/// take precedence over earlier ones. Stores built with
+	/// [`from_map`](Self::from_map) have no layers.
+	#[must_use]
+	pub fn layers(&self) -> &[LayerInfo] {
+		&self.layers
+	}
+
+	/// Returns the profile active when the store was loaded, if any; see
+	/// [`InitBuilder::profile`].
+	#[must_use]
+	pub fn profile(&self) -> Option<&str> {
+		self.profile.as_deref()
+	}
+
+	/// Returns the layer that supplied the value of `key`, resolving prefixes
+	/// like [`lookup`](Self::lookup).
+	///
+	/// Returns `None` if the key is not set, was set by a migration, or was
+	/// read lazily in [on-demand](InitBuilder::on_demand) mode.
+	#[must_use]
+	pub fn layer_of(&self, key: &str) -> Option<&LayerInfo> {
+		let full = match self.prefixes.as_slice() {
+			[] => key.to_owned(),
+			[p] => format!("{p}{key}"),
+			_ if self.chained => self
+				.prefixes
+				.iter()
+				.map(|p| format!("{p}{key}"))
+				.find(|k| self.raw(k).is_some())?,
+			_ => return None,
+		};
+		self.source_layer(&full)
+	}
+
+	/// Returns the layer that supplied the value stored under the full
+	/// variable name `key`.
+	pub(crate) fn source_layer(&self, key: &str) -> Option<&LayerInfo> {
+		self.layers.get(*self.sources.get(key)?)
+	}
+
+	/// Returns how the shadow configuration disagreed with this store when
+	/// it was loaded, if one was configured with
+	/// [`InitBuilder::shadow`].
+	#[must_use]
+	pub fn shadow_report(&self) -> Option<&ShadowReport> {
+		self.shadow.as_ref()
+	}
+
+	/// Checks every key declared in `schema` against this store, reporting
+	/// all problems at once instead of stopping at the first.
+	///
+	/// # Errors
+	///
+	/// Returns `EnvflagError::SchemaViolation` listing every missing or
+	/// invalid key, with values redacted.
+	pub fn check(&self, schema: &Schema) -> Result<(), EnvflagError> {
+		let problems = schema.validate(|name| self.peek(name, None));
+		if problems.is_empty() {
+			Ok(())
+		} else {
+			Err(EnvflagError::SchemaViolation { problems })
+		}
+	}
+
+	/// Returns a new store containing only the given keys.
+	///
+	/// Names are short names, as passed to [`key()`](crate::key); with
+	/// prefixes configured, every prefixed variant present is kept. The new
+	/// store has the same prefixes, so lookups resolve exactly as they do
+	/// here, but it reports no source layers.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use std::collections::HashMap;
+	/// use envflag::store::EnvStore;
+	///
+	/// let store = EnvStore::from_map(HashMap::from([
+	///     ("PORT".into(), "8080".into()),
+	///     ("SECRET_KEY".into(), "hunter2".into()),
+	/// ]));
+	/// let child = store.subset(["PORT", "HOST"]);
+	/// assert_eq!(child.entries(), vec![("PORT".to_owned(), "8080".to_owned())]);
+	/// ```
+	#[must_use]
+	pub fn subset<I, S>(&self, names: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>,
+	{
+		let mut map = HashMap::new();
+		for name in names {
+			let name = name.as_ref();
+			let candidates: Vec<String> = if self.prefixes.is_empty() {
+				vec![name.to_owned()]
+			} else {
+				self.prefixes.iter().map(|p| format!("{p}{name}")).collect()
+			};
+			for k in candidates {
+				if let Some(v) = self.raw(&k) {
+					map.insert(k, v);
+				}
+			}
+		}
+
+		Self {
+			map,
+			prefixes: self.prefixes.clone(),
+			chained: self.chained,
+			layers: Vec::new(),
+			sources: HashMap::new(),
+			refreshable: HashMap::new(),
+			profile: None,
+			shadow: None,
+			on_demand: None,
+			redact_errors: self.redact_errors,
+			#[cfg(feature = "usage")]
+			usage: Arc::default(),
+		}
+	}
+
+	/// Returns the value stored under the full variable name `key`, reading
+	/// the process environment on a miss in on-demand mode.
+	fn raw(&self, key: &str) -> Option<String> {
+		self
+			.map
+			.get(key)
+			.cloned()
+			.or_else(|| self.on_demand.as_ref()?.get(key))
+	}
+
+	/// Returns the underlying key-value map.
+	pub(crate) fn entry_map(&self) -> &HashMap<String, String> {
+		&self.map
+	}
+
+	/// Returns the stored variable name for a short name: a single
+	/// configured prefix is added unless `name` already carries a prefix.
+	///
+	/// With a prefix chain, the highest-priority prefix under which `name`
+	/// is set is used, or the first one if it is not set at all.
+	pub(crate) fn storage_key(&self, name: &str) -> String {
+		if self.chained {
+			let mut keys = self.prefixes.iter().map(|p| format!("{p}{name}"));
+			let first = keys.next().unwrap_or_else(|| name.to_owned());
+			if self.raw(&first).is_some() {
+				return first;
+			}
+			return keys.find(|k| self.raw(k).is_some()).unwrap_or(first);
+		}
+		match self.prefixes.as_slice() {
+			[p] if !name.starts_with(p.as_str()) => format!("{p}{name}"),
+			_ => name.to_owned(),
+		}
+	}
+
+	/// Builds the `NotSet` error for a query of the short names `names`
+	/// (the key, then its fallbacks), listing every full name tried and
+	/// suggesting the loaded variable closest to the first.
+	pub(crate) fn not_set(&self, names: &[&str], preferred_prefix: Option<&str>) -> EnvflagError {
+		let tried: Vec<String> = names
+			.iter()
+			.flat_map(|name| self.candidates(name, preferred_prefix))
+			.collect();
+		let key = names.first().copied().unwrap_or_default();
+		let full = tried
+			.first()
+			.map_or(key, String::as_str)
+			.to_ascii_uppercase();
+		let suggestion = self
+			.map
+			.keys()
+			.map(|k| (schema::edit_distance(&k.to_ascii_uppercase(), &full), k))
+			// Within two edits, and not a complete rewrite of a short name.
+			.filter(|(d, _)| *d <= 2 && *d < full.len())
+			.min()
+			.map(|(_, k)| k.clone());
+		EnvflagError::NotSet {
+			key: key.to_owned(),
+			tried,
+			suggestion,
+		}
+	}
+
+	/// Returns the underlying key-value map for in-place rewrites.
+	pub(crate) fn map_mut(&mut self) -> &mut HashMap<String, String> {
+		&mut self.map
+	}
+
+	/// Returns a copy of this store with the refresh layer's value for `key`
+	/// replaced by `value`, or dropped if `value` is `None`.
+	///
+	/// The key is then resolved through the layers again: a command-line
+	/// override still wins, and without a fetched value a lower layer such
+	/// as the `.env` file supplies it.
+	pub(crate) fn with_refreshed(&self, key: &str, value: Option<String>) -> Self {
+		let mut store = self.clone();
+		let Some(remote) = store
+			.layers
+			.iter()
+			.position(|l| l.kind == LayerKind::Remote)
+		else {
+			return store;
+		};
+		let stack = store.refreshable.entry(key.to_owned()).or_default();
+		stack.retain(|(i, _)| *i != remote);
+		if let Some(v) = value {
+			let at = stack.partition_point(|(i, _)| *i < remote);
+			stack.insert(at, (remote, v));
+		}
+		if let Some((i, v)) = stack.last() {
+			store.map.insert(key.to_owned(), v.clone());
+			store.sources.insert(key.to_owned(), *i);
+		} else {
+			store.map.remove(key);
+			store.sources.remove(key);
+		}
+		store
+	}
+
 	/// Returns all environment variables in the store.
 	#[must_use]
 	pub fn entries(&self) -> Vec<(String, String)> {
@@ -106,20 +570,156 @@ impl EnvStore {
 			.map(|(k, v)| (k.clone(), v.clone()))
 			.collect()
 	}
+
+	/// Returns the loaded variables the application has never read, by full
+	/// name and sorted, to spot stale configuration and typos in deployment
+	/// manifests.
+	///
+	/// Reads are counted per store by [`lookup`](Self::lookup). Without a
+	/// prefix the store holds the whole process environment, so the list is
+	/// only useful with one. Requires the `usage` feature.
+	#[cfg(feature = "usage")]
+	#[must_use]
+	pub fn unused_keys(&self) -> Vec<String> {
+		self.usage.unread(self.map.keys())
+	}
+
+	/// Returns every variable read from this store, with the number of
+	/// reads, sorted by name.
+	///
+	/// Names are full variable names, including any prefix. Only reads that
+	/// found a value are counted; the crate's own startup checks (`require`,
+	/// `schema`) are not. Counts carry over to the stores that replace this
+	/// one on reload. Requires the `usage` feature.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// let store = envflag::store::EnvStore::from_map(
+	///     [("USAGE_PORT".to_owned(), "80".to_owned())].into(),
+	/// );
+	/// let _: u16 = store.key("USAGE_PORT").required().unwrap();
+	/// let _: u16 = store.key("USAGE_PORT").required().unwrap();
+	/// assert_eq!(store.usage_report(), [("USAGE_PORT".to_owned(), 2)]);
+	/// ```
+	#[cfg(feature = "usage")]
+	#[must_use]
+	pub fn usage_report(&self) -> Vec<(String, u64)> {
+		self.usage.report()
+	}
+
+	/// Returns every key whose query on this store fell back to its
+	/// default, with the reason, sorted by key.
+	///
+	/// Keys are reported as queried, without prefixes. A misspelled variable
+	/// in a deployment manifest shows up here as [`DefaultReason::Missing`].
+	/// Like read counts, defaults carry over to the stores that replace this
+	/// one on reload. Requires the `usage` feature.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use envflag::DefaultReason;
+	///
+	/// let store = envflag::store::EnvStore::from_map(
+	///     [("WORKERS".to_owned(), "many".to_owned())].into(),
+	/// );
+	/// assert_eq!(store.get("WORKERS", 4u8), 4);
+	/// assert_eq!(store.key("PORT").default(8080u16).get().unwrap(), 8080);
+	/// assert_eq!(
+	///     store.defaulted_keys(),
+	///     [
+	///         ("PORT".to_owned(), DefaultReason::Missing),
+	///         ("WORKERS".to_owned(), DefaultReason::ParseFailed),
+	///     ]
+	/// );
+	/// ```
+	#[cfg(feature = "usage")]
+	#[must_use]
+	pub fn defaulted_keys(&self) -> Vec<(String, DefaultReason)> {
+		self.usage.defaulted()
+	}
+
+	/// Records that the query for `key` returned its default; a no-op
+	/// without the `usage` feature.
+	#[cfg_attr(not(feature = "usage"), allow(clippy::unused_self))]
+	pub(crate) fn record_default(&self, key: &str, reason: DefaultReason) {
+		#[cfg(feature = "usage")]
+		self.usage.record_default(key, reason);
+		#[cfg(not(feature = "usage"))]
+		let _ = (key, reason);
+	}
+
+	/// Returns this store tracking usage where `previous`, the store it
+	/// replaces, left off.
+	#[cfg(feature = "usage")]
+	pub(crate) fn inheriting_usage(mut self, previous: &Self) -> Self {
+		self.usage = Arc::clone(&previous.usage);
+		self
+	}
+
+	/// Returns all environment variables with secret values masked, safe to
+	/// expose on a debugging endpoint.
+	///
+	/// Values are passed through [`redact`](crate::redact::redact), and
+	/// values of keys matching any of `patterns` (see
+	/// [`matches_pattern`](crate::redact::matches_pattern)) are masked
+	/// entirely.
+	#[must_use]
+	pub fn entries_redacted(&self, patterns: &[&str]) -> Vec<(String, String)> {
+		self
+			.map
+			.iter()
+			.map(|(k, v)| {
+				let value = if patterns.iter().any(|p| redact::matches_pattern(p, k)) {
+					redact::MASK.to_owned()
+				} else {
+					redact::redact(k, v).into_owned()
+				};
+				(k.clone(), value)
+			})
+			.collect()
+	}
 }
 
 /// Builder for initializing the envflag crate.
 ///
+/// The `.env` file is read into the store. [`init`](Self::init) also exports
+/// it to the process environment, as `dotenvy::dotenv` does, so
+/// `std::env::var` sees its values; variables that are already set are kept.
+/// Process variables take precedence over the file.
+///
 /// # Initialization order
 ///
 /// It is recommended to call `init()` early in `main()` **before** spawning
-/// any threads. The global store is backed by [`OnceLock`] so concurrent
-/// `init()` calls are memory-safe, but deterministic single-threaded
-/// initialization avoids surprises.
+/// any threads. The global store is guarded by a lock so concurrent `init()`
+/// calls are memory-safe, but deterministic single-threaded initialization
+/// avoids surprises.
 #[derive(Debug)]
 pub struct InitBuilder {
 	path: Option<PathBuf>,
+	#[cfg(feature = "vault")]
+	vault: Option<PathBuf>,
+	defaults: &'static [(&'static str, &'static str)],
 	prefixes: Vec<String>,
+	chained: bool,
+	args: Vec<String>,
+	dirs: Vec<DirSource>,
+	readers: Vec<ReaderSource>,
+	sources: Vec<Box<dyn Source>>,
+	refresh: Vec<RefreshKey>,
+	migrations: Vec<MigrationStep>,
+	shadow: Option<Box<Self>>,
+	on_demand: bool,
+	#[cfg(feature = "watch")]
+	watch: bool,
+	layered: bool,
+	profile: Option<String>,
+	file_indirection: bool,
+	schema: Option<Schema>,
+	strict: bool,
+	required: Vec<String>,
+	redact_errors: bool,
 }
 
 impl Default for InitBuilder {
@@ -134,7 +734,28 @@ impl InitBuilder {
 	pub fn new() -> Self {
 		Self {
 			path: None,
+			#[cfg(feature = "vault")]
+			vault: None,
+			defaults: &[],
 			prefixes: Vec::new(),
+			chained: false,
+			args: Vec::new(),
+			dirs: Vec::new(),
+			readers: Vec::new(),
+			sources: Vec::new(),
+			refresh: Vec::new(),
+			migrations: Vec::new(),
+			shadow: None,
+			on_demand: false,
+			#[cfg(feature = "watch")]
+			watch: false,
+			layered: false,
+			profile: None,
+			file_indirection: false,
+			schema: None,
+			strict: false,
+			required: Vec::new(),
+			redact_errors: false,
 		}
 	}
 
@@ -145,6 +766,103 @@ impl InitBuilder {
 		self
 	}
 
+	/// Loads the `.env` layer from an encrypted `.env.vault` file when
+	/// `DOTENV_KEY` is set.
+	///
+	/// `DOTENV_KEY` selects the environment and holds its decryption key, as
+	/// issued by dotenv-vault tooling; several comma-separated keys are tried
+	/// in order. Without `DOTENV_KEY` the plain `.env` file is used as usual,
+	/// so local development is unaffected. Fails at `init()` if the vault
+	/// cannot be read or decrypted.
+	#[cfg(feature = "vault")]
+	#[must_use]
+	pub fn vault<P: AsRef<Path>>(mut self, path: P) -> Self {
+		self.vault = Some(path.as_ref().to_path_buf());
+		self
+	}
+
+	/// Reloads the global store whenever a `.env` file it was loaded from
+	/// changes on disk.
+	///
+	/// Changes are debounced and applied as by [`reload()`](crate::reload()):
+	/// the new store is swapped in atomically and [`on_reload()`](crate::on_reload)
+	/// subscribers receive the diff. A file that fails to parse mid-edit keeps
+	/// the current store. Only files present at `init()` are watched.
+	#[cfg(feature = "watch")]
+	#[must_use]
+	pub fn watch(mut self) -> Self {
+		self.watch = true;
+		self
+	}
+
+	/// Loads `.env`, then `.env.local`, then `.env.{APP_ENV}`, with later
+	/// files overriding earlier ones, as dotenv-flow and most frameworks do.
+	///
+	/// The sibling files sit next to the `.env` file (the one set with
+	/// [`path`](Self::path), or the one found from the current directory)
+	/// and are optional. The profile is set with [`profile`](Self::profile),
+	/// or read from `APP_ENV` or `RUST_ENV` in the process environment,
+	/// falling back to `.env.local` and `.env`. Each file is its own
+	/// [`LayerKind::DotenvFile`] layer, so
+	/// [`EnvStore::layer_of`] tells which one a key came from.
+	#[must_use]
+	pub fn layered(mut self) -> Self {
+		self.layered = true;
+		self
+	}
+
+	/// Selects the active profile, e.g. `"production"`, and loads
+	/// `.env.{profile}` over the `.env` file.
+	///
+	/// Without this, the profile is taken from `APP_ENV` or `RUST_ENV` in
+	/// the process environment (and, in [layered](Self::layered) mode, from
+	/// the `.env` files), but only layered mode loads a file for it. The
+	/// active profile is reported by [`profile()`](crate::profile) either way.
+	#[must_use]
+	pub fn profile(mut self, name: &str) -> Self {
+		self.profile = Some(name.to_owned());
+		self
+	}
+
+	/// Reads process variables on first lookup instead of copying the whole
+	/// environment at `init()`.
+	///
+	/// Trims startup time and memory for tools that read a handful of keys
+	/// in environments with thousands of variables (CI runners, Kubernetes).
+	/// Each variable is read once per store and cached; precedence is the
+	/// same as in the default mode.
+	///
+	/// Process variables never looked up are not part of the store, so they
+	/// are missing from [`entries()`](crate::entries) and reload diffs.
+	/// Options that inspect every variable while loading,
+	/// [`migration`](Self::migration), [`strict`](Self::strict),
+	/// [`shadow`](Self::shadow) and
+	/// [`file_indirection`](Self::file_indirection), cannot be combined with
+	/// it: `init()` fails with `EnvflagError::IncompatibleOptions`.
+	#[must_use]
+	pub fn on_demand(mut self) -> Self {
+		self.on_demand = true;
+		self
+	}
+
+	/// Sets defaults that every other source overrides.
+	///
+	/// Typically a map generated at build time from a `.env.defaults` file
+	/// with [`build::embed_defaults`](crate::build::embed_defaults) (feature
+	/// `build`), so the binary carries sensible defaults without needing any
+	/// file at runtime:
+	///
+	/// ```rust,ignore
+	/// envflag::builder()
+	///     .defaults(envflag::embedded_defaults!())
+	///     .init()?;
+	/// ```
+	#[must_use]
+	pub fn defaults(mut self, defaults: &'static [(&'static str, &'static str)]) -> Self {
+		self.defaults = defaults;
+		self
+	}
+
 	/// Adds a prefix to filter environment variables.
 	///
 	/// Only keys matching at least one configured prefix will be kept in the
@@ -157,45 +875,1349 @@ impl InitBuilder {
 		self
 	}
 
+	/// Makes several prefixes feed one namespace, in priority order.
+	///
+	/// Each short name is resolved at lookup time by trying the prefixes in
+	/// the given order; the first one set wins. An empty string stands for
+	/// unprefixed variables. Useful after a rebrand, when old variables must
+	/// keep working:
+	///
+	/// ```rust,no_run
+	/// // MYAPP_PORT overrides APP_PORT, which overrides PORT.
+	/// envflag::builder()
+	///     .prefix_chain(["MYAPP_", "APP_", ""])
+	///     .init()
+	///     .unwrap();
+	/// let port = envflag::get("PORT", 8080u16);
+	/// ```
+	///
+	/// Replaces any prefixes added with [`prefix`](Self::prefix).
+	#[must_use]
+	pub fn prefix_chain<I, S>(mut self, prefixes: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.prefixes = prefixes.into_iter().map(Into::into).collect();
+		self.chained = true;
+		self
+	}
+
+	/// Installs `--env KEY=VALUE` / `-e KEY=VALUE` command-line overrides.
+	///
+	/// Typically called with [`std::env::args()`]. Arguments that are not
+	/// overrides are ignored, so the full argument list can be passed as-is.
+	/// The `--env=KEY=VALUE` form is accepted as well, and scanning stops at a
+	/// literal `--`.
+	///
+	/// Overrides take precedence over both the process environment and the
+	/// `.env` file. Malformed overrides are reported by [`init`](Self::init).
+	#[must_use]
+	pub fn args_overrides<I, S>(mut self, args: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.args.extend(args.into_iter().map(Into::into));
+		self
+	}
+
+	/// Imports `KEY=VALUE\0` records from `reader`, the format produced by
+	/// `env -0`.
+	///
+	/// Unlike line-based formats this is lossless: values may contain
+	/// newlines. Typical use is a wrapper tool piping its environment in,
+	/// e.g. `env -0 | my-app`. The reader is consumed once during `init()`;
+	/// reloads reuse what was read.
+	///
+	/// Imported records take precedence over the `.env` file and the process
+	/// environment. Malformed records fail `init()` with
+	/// `EnvflagError::Source`.
+	#[must_use]
+	pub fn null_separated(mut self, reader: impl Read + Send + 'static) -> Self {
+		self.readers.push(ReaderSource::new(
+			"env -0 input",
+			source::parse_null_separated,
+			Box::new(reader),
+		));
+		self
+	}
+
+	/// Imports `env -0` records from stdin; see
+	/// [`null_separated`](Self::null_separated).
+	#[must_use]
+	pub fn null_separated_stdin(mut self) -> Self {
+		self
+			.readers
+			.push(ReaderSource::stdin(source::parse_null_separated));
+		self
+	}
+
+	/// Registers a migration from env contract version `from` to `to`.
+	///
+	/// At load time the deployment's `CONFIG_VERSION` (or, if missing, the
+	/// oldest `from` registered) selects the first step; steps are then
+	/// chained until no step starts from the reached version, and
+	/// `CONFIG_VERSION` is set to it. Migrations see the merged result of
+	/// every layer and run again on reload.
+	///
+	/// `init()` fails with `EnvflagError::InvalidMigration` if a step does
+	/// not move forward or two steps start from the same version, and with
+	/// `EnvflagError::ParseFailed` if `CONFIG_VERSION` is not a number.
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// envflag::builder()
+	///     .migration(1, 2, |m| m.rename("DB_HOST", "DATABASE_HOST"))
+	///     .migration(2, 3, |m| {
+	///         if m.remove("LEGACY_MODE").is_some() {
+	///             m.set("COMPAT", "true");
+	///         }
+	///     })
+	///     .init()
+	///     .unwrap();
+	/// ```
+	#[must_use]
+	pub fn migration<F>(mut self, from: u32, to: u32, f: F) -> Self
+	where
+		F: Fn(&mut Migration<'_>) + Send + Sync + 'static,
+	{
+		self.migrations.push(MigrationStep {
+			from,
+			to,
+			f: Arc::new(f),
+		});
+		self
+	}
+
+	/// Loads `secondary` alongside this configuration and records where the
+	/// two disagree, while serving every value from this one.
+	///
+	/// Meant for de-risking a move between sources: configure the new setup
+	/// as the secondary, deploy, and watch the
+	/// [`ShadowReport`](crate::shadow::ShadowReport) (logged as a warning
+	/// with the `tracing` feature, and available from
+	/// [`shadow_report()`](crate::shadow_report)) until it is clean. The
+	/// comparison is redone on every reload. A secondary that fails to load
+	/// never fails `init()`; the error is recorded in the report instead.
+	#[must_use]
+	pub fn shadow(mut self, secondary: Self) -> Self {
+		self.shadow = Some(Box::new(secondary));
+		self
+	}
+
+	/// Loads each file under `/run/secrets` as a variable, as mounted by
+	/// Docker Swarm and Compose secrets.
+	///
+	/// The file name is the key and the trimmed contents are the value, so a
+	/// secret named `db_password` is read with `envflag::get("db_password", ..)`.
+	/// Secrets take precedence over `.env` files but not over the process
+	/// environment. A missing `/run/secrets` is skipped.
+	#[must_use]
+	pub fn docker_secrets(mut self) -> Self {
+		self
+			.dirs
+			.push(DirSource::optional(PathBuf::from("/run/secrets")));
+		self
+	}
+
+	/// Loads every regular file in `path` as a variable, matching how
+	/// Kubernetes mounts ConfigMaps and Secrets.
+	///
+	/// The file name is the key and the trimmed contents are the value.
+	/// Hidden files, including the `..data` links Kubernetes creates, are
+	/// skipped, and so are subdirectories. The directory must exist; its
+	/// variables take precedence over `.env` files but not over the process
+	/// environment, and later directories override earlier ones.
+	#[must_use]
+	pub fn from_dir<P: AsRef<Path>>(mut self, path: P) -> Self {
+		self
+			.dirs
+			.push(DirSource::required(path.as_ref().to_path_buf()));
+		self
+	}
+
+	/// Resolves `KEY_FILE=/path/to/secret` into `KEY`, as official Docker
+	/// images do.
+	///
+	/// After all layers are merged, every key ending in `_FILE` is read from
+	/// the file it names, with trailing newlines stripped, and exposed under
+	/// the key without the suffix; the `_FILE` key itself is kept. When both
+	/// `KEY` and `KEY_FILE` are set, the one from the higher layer wins.
+	/// `init()` fails if the file cannot be read, or if both are set in the
+	/// same layer.
+	#[must_use]
+	pub fn file_indirection(mut self) -> Self {
+		self.file_indirection = true;
+		self
+	}
+
+	/// Requires `name` to be set, so `init()` fails at startup instead of the
+	/// first query deep in a request path.
+	///
+	/// `init()` (and every reload) returns `EnvflagError::MissingKeys`
+	/// listing every required key that is not set. Names are short names,
+	/// resolved like [`key()`](crate::key).
+	#[must_use]
+	pub fn require(mut self, name: &str) -> Self {
+		self.required.push(name.to_owned());
+		self
+	}
+
+	/// Keeps values out of every `ParseFailed` and `ValidationFailed` error
+	/// and its log line, for deployments where error messages end up in
+	/// centralized logging.
+	///
+	/// Errors then report [`MASK`](crate::redact::MASK) in place of the
+	/// value, as if every key were [`sensitive`](crate::KeyBuilder::sensitive).
+	#[must_use]
+	pub fn redact_values_in_errors(mut self, enabled: bool) -> Self {
+		self.redact_errors = enabled;
+		self
+	}
+
+	/// Validates the loaded configuration against `schema` during `init()`.
+	///
+	/// `init()` (and every reload) fails with
+	/// `EnvflagError::SchemaViolation` listing every required key that is
+	/// missing and every value that fails its type or validators, so a
+	/// misconfigured service stops at startup rather than at first access.
+	/// Checks run after migrations.
+	#[must_use]
+	pub fn schema(mut self, schema: Schema) -> Self {
+		self.schema = Some(schema);
+		self
+	}
+
+	/// Rejects prefixed variables the [`schema`](Self::schema) does not
+	/// declare.
+	///
+	/// Catches typos like `APP_PROT=8080` that would otherwise be ignored
+	/// while `PORT` silently falls back to its default. Undeclared keys are
+	/// reported alongside other schema violations, with a suggestion when a
+	/// declared key is spelled similarly. `CONFIG_VERSION` and, with
+	/// [`file_indirection`](Self::file_indirection), `KEY_FILE` for a
+	/// declared `KEY` are allowed. Has no effect without a schema and a
+	/// non-empty prefix.
+	#[must_use]
+	pub fn strict(mut self) -> Self {
+		self.strict = true;
+		self
+	}
+
+	/// Adds a custom source of variables.
+	///
+	/// Sources are loaded on `init()` and every reload, in registration
+	/// order, each overriding the ones before it. Together they take
+	/// precedence over the `.env` file, the process environment, and reader
+	/// imports, but not over refreshable keys or command-line overrides.
+	#[must_use]
+	pub fn source(mut self, source: Box<dyn Source>) -> Self {
+		self.sources.push(source);
+		self
+	}
+
+	/// Reads a whole configuration document from stdin during `init()`.
+	///
+	/// Lets a containerized job receive its configuration piped in at
+	/// start, e.g. `my-app < prod.env`, without writing a temp file. The
+	/// document takes precedence over the `.env` file and the process
+	/// environment, and is read only once; reloads reuse it.
+	///
+	/// `init()` fails with `EnvflagError::Source` if stdin is a terminal or
+	/// the document cannot be parsed.
+	#[must_use]
+	pub fn stdin(mut self, format: Format) -> Self {
+		self.readers.push(ReaderSource::stdin(format.parser()));
+		self
+	}
+
+	/// Backs a key with a fetch callback that is re-run whenever `ttl` expires.
+	///
+	/// Intended for values that rotate at their origin, such as short-lived
+	/// database credentials issued by a secrets manager. `fetch` is called
+	/// with the full key name during `init()` and then again by a background
+	/// thread on every expiry; each refresh swaps in just that key and notifies
+	/// [`on_reload`](crate::on_reload) subscribers if the value changed.
+	///
+	/// Returning `Ok(None)` leaves the key to the layers below, such as the
+	/// `.env` file, or unset if none supplies it. An error during `init()` fails
+	/// initialization; an error during a background refresh keeps the current
+	/// value and retries after another `ttl`.
+	///
+	/// Fetched values take precedence over the `.env` file and the process
+	/// environment, but not over command-line overrides.
+	///
+	/// # Panics
+	///
+	/// Panics if `ttl` is zero.
+	#[must_use]
+	pub fn refresh_key<F>(mut self, key: &str, ttl: Duration, fetch: F) -> Self
+	where
+		F: Fn(&str) -> Result<Option<String>, EnvflagError> + Send + Sync + 'static,
+	{
+		assert!(
+			!ttl.is_zero(),
+			"refresh_key({key}): ttl must be greater than zero"
+		);
+		self.refresh.push(RefreshKey {
+			key: key.to_owned(),
+			ttl,
+			fetch: Arc::new(fetch),
+		});
+		self
+	}
+
 	/// Initializes the global environment store.
 	///
 	/// # Errors
 	///
-	/// Returns an error if the crate is already initialized, or if the `.env`
-	/// file cannot be loaded.
+	/// Returns an error if the crate is already initialized, if the `.env`
+	/// file cannot be loaded, or if a command-line override is malformed.
 	pub fn init(self) -> Result<(), EnvflagError> {
-		// 1. Load dotenv into std::env
-		if let Some(p) = self.path {
-			dotenvy::from_path(p)?;
+		let store = self.build()?;
+		#[cfg(feature = "watch")]
+		let watcher = if self.watch {
+			let files: Vec<PathBuf> = store
+				.layers()
+				.iter()
+				.filter(|l| l.kind == LayerKind::DotenvFile)
+				.filter_map(|l| l.origin.as_ref().map(PathBuf::from))
+				.collect();
+			Some(crate::watch::FileWatcher::new(&files)?)
 		} else {
-			match dotenvy::dotenv() {
-				Ok(_) => {}
-				Err(e) if e.not_found() => {}
-				Err(e) => return Err(EnvflagError::Dotenv(e)),
+			None
+		};
+
+		// Check and install under one write lock — no TOCTOU possible.
+		let mut instance = INSTANCE.write().unwrap_or_else(PoisonError::into_inner);
+		if instance.is_some() {
+			return Err(EnvflagError::AlreadyInitialized);
+		}
+		let builder = Arc::new(self);
+		let store = Arc::new(store);
+		*instance = Some(Instance {
+			store: Arc::clone(&store),
+			builder: Arc::clone(&builder),
+		});
+		drop(instance);
+
+		let started = export_dotenv(store.layers()).and_then(|()| {
+			Self::start_threads(
+				&builder,
+				#[cfg(feature = "watch")]
+				watcher,
+			)
+		});
+		if let Err(e) = started {
+			// Without its `.env` export or background threads the
+			// configuration would be incomplete or go stale unnoticed, so
+			// undo the installation and let a retry succeed.
+			let mut instance = INSTANCE.write().unwrap_or_else(PoisonError::into_inner);
+			if instance
+				.as_ref()
+				.is_some_and(|i| Arc::ptr_eq(&i.builder, &builder))
+			{
+				*instance = None;
+			}
+			return Err(e);
+		}
+		Ok(())
+	}
+
+	/// Starts the file watcher and the refresher for the installed `builder`.
+	fn start_threads(
+		builder: &Arc<Self>,
+		#[cfg(feature = "watch")] watcher: Option<crate::watch::FileWatcher>,
+	) -> Result<(), EnvflagError> {
+		#[cfg(feature = "watch")]
+		if let Some(watcher) = watcher {
+			watcher.spawn(Arc::clone(builder))?;
+		}
+		if !builder.refresh.is_empty() {
+			crate::reload::spawn_refresher(Arc::clone(builder))?;
+		}
+		Ok(())
+	}
+
+	/// Loads every source layer and assembles them into a store.
+	///
+	/// Layers are applied in order — compiled-in defaults, `.env` file,
+	/// process environment, reader imports, custom sources, refreshable
+	/// keys, command-line overrides — with later layers overriding earlier
+	/// ones.
+	pub(crate) fn build(&self) -> Result<EnvStore, EnvflagError> {
+		self.check_on_demand()?;
+		let mut layers = Vec::new();
+
+		// 1. Defaults compiled into the binary.
+		if !self.defaults.is_empty() {
+			let vars = self
+				.defaults
+				.iter()
+				.map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+				.collect();
+			layers.push((LayerKind::Defaults, None, vars, Duration::ZERO));
+		}
+
+		// 2. The .env file. An explicit path must exist; the default one is
+		//    optional. A vault unlocked by DOTENV_KEY takes its place.
+		let start = Instant::now();
+		#[cfg(feature = "vault")]
+		let vault = match (&self.vault, env::var(crate::vault::KEY_VAR)) {
+			(Some(p), Ok(key)) => {
+				let vars = crate::vault::load(p, &key)?;
+				layers.push((
+					LayerKind::DotenvFile,
+					Some(p.display().to_string()),
+					vars,
+					start.elapsed(),
+				));
+				true
+			}
+			_ => false,
+		};
+		#[cfg(not(feature = "vault"))]
+		let vault = false;
+		let dotenv_path = match &self.path {
+			_ if vault => None,
+			Some(p) => Some(p.clone()),
+			None => find_dotenv(),
+		};
+		if let Some(p) = &dotenv_path {
+			let vars = dotenvy::from_path_iter(p)?.collect::<Result<HashMap<_, _>, _>>()?;
+			layers.push((
+				LayerKind::DotenvFile,
+				Some(p.display().to_string()),
+				vars,
+				start.elapsed(),
+			));
+		}
+
+		// 2b. The active profile: explicit, else `APP_ENV` / `RUST_ENV` from
+		//     the process, else (in layered mode) from the `.env` files.
+		//     Layered mode loads `.env.local`, and layered mode or an explicit
+		//     profile loads `.env.{profile}`, next to the `.env` file. Either
+		//     may be missing.
+		let mut profile = self
+			.profile
+			.clone()
+			.or_else(|| PROFILE_VARS.iter().find_map(|k| env::var(k).ok()));
+		if (self.layered || self.profile.is_some()) && !vault {
+			let base = dotenv_path.or_else(|| Some(env::current_dir().ok()?.join(".env")));
+			if let Some(base) = base {
+				if self.layered {
+					load_sibling(&mut layers, &base, "local")?;
+					profile = profile.or_else(|| {
+						layers
+							.iter()
+							.rev()
+							.find_map(|(_, _, vars, _)| PROFILE_VARS.iter().find_map(|k| vars.get(*k).cloned()))
+					});
+				}
+				if let Some(p) = profile
+					.as_deref()
+					.filter(|p| !p.is_empty() && *p != "local")
+				{
+					load_sibling(&mut layers, &base, p)?;
+				}
+			}
+		}
+		let profile = profile.filter(|p| !p.is_empty());
+
+		// 2c. Directories holding one variable per file.
+		for d in &self.dirs {
+			let start = Instant::now();
+			if let Some(vars) = d.load()? {
+				layers.push((
+					LayerKind::Directory,
+					Some(d.path().display().to_string()),
+					vars,
+					start.elapsed(),
+				));
 			}
 		}
 
-		// 2. Collect env vars into private map
-		let all_vars: HashMap<String, String> = env::vars().collect();
-		let map = if self.prefixes.is_empty() {
-			all_vars
+		// 3. The process environment. On demand, only the keys the layers
+		//    below it define are read now, so it still overrides them.
+		let start = Instant::now();
+		if self.on_demand {
+			let vars = layers
+				.iter()
+				.flat_map(|(_, _, vars, _): &(_, _, HashMap<String, String>, _)| vars.keys())
+				.filter_map(|k| Some((k.clone(), process_var(k)?)))
+				.collect();
+			layers.push((
+				LayerKind::ProcessEnv,
+				Some("on demand".to_owned()),
+				vars,
+				start.elapsed(),
+			));
 		} else {
+			let exported = EXPORTED.lock().unwrap_or_else(PoisonError::into_inner);
+			let vars = env::vars()
+				.filter(|(k, v)| exported.get(k) != Some(v))
+				.collect();
+			layers.push((LayerKind::ProcessEnv, None, vars, start.elapsed()));
+		}
+
+		// 4. Reader imports, e.g. `env -0` piped into stdin.
+		for r in &self.readers {
+			let start = Instant::now();
+			let vars = r.load()?;
+			layers.push((
+				LayerKind::Reader,
+				Some(r.origin().to_owned()),
+				vars,
+				start.elapsed(),
+			));
+		}
+
+		// 5. Custom sources, in registration order.
+		for s in &self.sources {
+			let start = Instant::now();
+			let vars = s.load()?;
+			layers.push((LayerKind::Custom, s.origin(), vars, start.elapsed()));
+		}
+
+		// 6. Keys backed by a refresh callback.
+		if !self.refresh.is_empty() {
+			let start = Instant::now();
+			let mut vars = HashMap::new();
+			for r in &self.refresh {
+				if let Some(v) = (r.fetch)(&r.key)? {
+					vars.insert(r.key.clone(), v);
+				}
+			}
+			layers.push((LayerKind::Remote, None, vars, start.elapsed()));
+		}
+
+		// 7. Command-line overrides.
+		if !self.args.is_empty() {
+			let start = Instant::now();
+			let vars = parse_overrides(&self.args)?.into_iter().collect();
+			layers.push((LayerKind::CommandLine, None, vars, start.elapsed()));
+		}
+
+		let refreshable = self
+			.refresh
+			.iter()
+			.filter(|r| self.accepts(&r.key))
+			.map(|r| {
+				let stack = layers
+					.iter()
+					.enumerate()
+					.filter_map(|(i, (_, _, vars, _))| Some((i, vars.get(&r.key)?.clone())))
+					.collect();
+				(r.key.clone(), stack)
+			})
+			.collect();
+		let mut map = HashMap::new();
+		let mut sources = HashMap::new();
+		let mut infos = Vec::with_capacity(layers.len());
+		for (i, (kind, origin, vars, load_time)) in layers.into_iter().enumerate() {
 			// Strict filter: only keep keys that match a configured prefix.
-			all_vars
-				.into_iter()
-				.filter(|(k, _)| self.prefixes.iter().any(|p| k.starts_with(p)))
-				.collect()
-		};
+			let vars: HashMap<String, String> =
+				vars.into_iter().filter(|(k, _)| self.accepts(k)).collect();
+			infos.push(LayerInfo {
+				kind,
+				origin,
+				keys: vars.len(),
+				load_time,
+			});
+			sources.extend(vars.keys().map(|k| (k.clone(), i)));
+			map.extend(vars);
+		}
+
+		if self.file_indirection {
+			resolve_file_refs(&mut map, &mut sources)?;
+		}
 
-		let store = EnvStore {
+		let mut store = EnvStore {
 			map,
-			prefixes: self.prefixes,
+			prefixes: self.prefixes.clone(),
+			chained: self.chained,
+			layers: infos,
+			sources,
+			refreshable,
+			profile,
+			shadow: None,
+			on_demand: self.on_demand.then(Arc::default),
+			redact_errors: self.redact_errors,
+			#[cfg(feature = "usage")]
+			usage: Arc::default(),
 		};
+		migration::check_steps(&self.migrations)?;
+		migration::apply(&mut store, &self.migrations)?;
 
-		// OnceLock::set is atomic — no TOCTOU possible.
-		INSTANCE
-			.set(store)
-			.map_err(|_| EnvflagError::AlreadyInitialized)?;
-		Ok(())
+		let missing: Vec<String> = self
+			.required
+			.iter()
+			.filter(|name| store.peek(name, None).is_none())
+			.cloned()
+			.collect();
+		if !missing.is_empty() {
+			return Err(EnvflagError::MissingKeys { keys: missing });
+		}
+
+		if let Some(schema) = &self.schema {
+			let mut problems = schema.validate(|name| store.peek(name, None));
+			if self.strict {
+				problems.extend(self.undeclared(&store, schema));
+			}
+			if !problems.is_empty() {
+				return Err(EnvflagError::SchemaViolation { problems });
+			}
+		}
+
+		if let Some(secondary) = &self.shadow {
+			let report = match secondary.build() {
+				Ok(other) => ShadowReport::between(&store, &other),
+				Err(e) => ShadowReport::failed(e.to_string()),
+			};
+			shadow::log(&report);
+			store.shadow = Some(report);
+		}
+		Ok(store)
+	}
+}
+
+impl InitBuilder {
+	/// Rejects options that need every variable up front in on-demand mode,
+	/// where process variables are only known once looked up.
+	fn check_on_demand(&self) -> Result<(), EnvflagError> {
+		if !self.on_demand {
+			return Ok(());
+		}
+		let conflicts: Vec<&str> = [
+			("migration()", !self.migrations.is_empty()),
+			("strict()", self.strict),
+			("shadow()", self.shadow.is_some()),
+			("file_indirection()", self.file_indirection),
+		]
+		.into_iter()
+		.filter_map(|(name, set)| set.then_some(name))
+		.collect();
+		if conflicts.is_empty() {
+			Ok(())
+		} else {
+			Err(EnvflagError::IncompatibleOptions {
+				message: format!(
+					"on_demand() cannot be combined with {}",
+					conflicts.join(", ")
+				),
+			})
+		}
+	}
+
+	/// Returns the keys whose values are backed by a refresh callback.
+	pub(crate) fn refresh_keys(&self) -> &[RefreshKey] {
+		&self.refresh
+	}
+
+	/// Lists the prefixed variables in `store` that `schema` does not declare.
+	fn undeclared(&self, store: &EnvStore, schema: &Schema) -> Vec<String> {
+		let allowed = |short: &str| {
+			schema.get(short).is_some()
+				|| short == migration::VERSION_KEY
+				|| (self.file_indirection
+					&& short
+						.strip_suffix("_FILE")
+						.is_some_and(|base| schema.get(base).is_some()))
+		};
+		let mut problems: Vec<String> = store
+			.entry_map()
+			.keys()
+			.filter_map(|key| {
+				let short = self
+					.prefixes
+					.iter()
+					.filter(|p| !p.is_empty())
+					.find_map(|p| key.strip_prefix(p.as_str()))?;
+				(!allowed(short)).then(|| match schema.closest(short) {
+					Some(similar) => format!("{key}: not declared in the schema (did you mean {similar}?)"),
+					None => format!("{key}: not declared in the schema"),
+				})
+			})
+			.collect();
+		problems.sort();
+		problems
+	}
+
+	/// Returns `true` if `key` passes the prefix filter.
+	pub(crate) fn accepts(&self, key: &str) -> bool {
+		self.prefixes.is_empty() || self.prefixes.iter().any(|p| key.starts_with(p))
+	}
+}
+
+/// Replaces each `KEY_FILE` entry's referenced file contents into `KEY`.
+fn resolve_file_refs(
+	map: &mut HashMap<String, String>,
+	sources: &mut HashMap<String, usize>,
+) -> Result<(), EnvflagError> {
+	let refs: Vec<(String, String)> = map
+		.iter()
+		.filter_map(|(k, v)| Some((k.strip_suffix("_FILE")?.to_owned(), v.clone())))
+		.filter(|(base, _)| !base.is_empty())
+		.collect();
+	for (base, path) in refs {
+		let file_layer = sources.get(&format!("{base}_FILE")).copied();
+		if map.contains_key(&base) {
+			// The higher layer wins; within one layer the two are ambiguous.
+			let layer = sources.get(&base).copied();
+			if layer > file_layer {
+				continue;
+			}
+			if layer == file_layer {
+				return Err(EnvflagError::Source {
+					origin: format!("{base}_FILE"),
+					message: format!("both {base} and {base}_FILE are set, but they are exclusive"),
+				});
+			}
+		}
+		let contents = fs::read_to_string(&path).map_err(|e| EnvflagError::Source {
+			origin: format!("{base}_FILE"),
+			message: format!("cannot read {path}: {e}"),
+		})?;
+		if let Some(layer) = file_layer {
+			sources.insert(base.clone(), layer);
+		}
+		map.insert(base, contents.trim_end_matches(['\r', '\n']).to_owned());
+	}
+	Ok(())
+}
+
+/// Exports the `.env` files among `layers` to the process environment without
+/// overwriting variables that are already set. Later files are exported
+/// first, so they win as they do in the store.
+fn export_dotenv(layers: &[LayerInfo]) -> Result<(), EnvflagError> {
+	let files = layers
+		.iter()
+		.rev()
+		.filter(|l| l.kind == LayerKind::DotenvFile)
+		.filter_map(|l| l.origin.as_deref())
+		// Encrypted vault files hold ciphertext, not variables.
+		.filter(|path| Path::new(path).extension().is_none_or(|ext| ext != "vault"));
+	let mut exported = EXPORTED.lock().unwrap_or_else(PoisonError::into_inner);
+	for path in files {
+		for item in dotenvy::from_path_iter(path)? {
+			let (key, value) = item?;
+			if env::var_os(&key).is_none() {
+				exported.insert(key, value);
+			}
+		}
+		dotenvy::from_path(path)?;
+	}
+	Ok(())
+}
+
+/// Reads a process variable, unless `init` exported it from a `.env` file.
+fn process_var(key: &str) -> Option<String> {
+	let value = env::var(key).ok()?;
+	let exported = EXPORTED.lock().unwrap_or_else(PoisonError::into_inner);
+	(exported.get(key) != Some(&value)).then_some(value)
+}
+
+/// Loads `<base>.<suffix>` as a `.env` layer if the file exists.
+fn load_sibling(
+	layers: &mut Vec<(LayerKind, Option<String>, HashMap<String, String>, Duration)>,
+	base: &Path,
+	suffix: &str,
+) -> Result<(), EnvflagError> {
+	let start = Instant::now();
+	let mut name = base.file_name().unwrap_or_default().to_owned();
+	name.push(format!(".{suffix}"));
+	let path = base.with_file_name(name);
+	if !path.is_file() {
+		return Ok(());
+	}
+	let vars = dotenvy::from_path_iter(&path)?.collect::<Result<HashMap<_, _>, _>>()?;
+	layers.push((
+		LayerKind::DotenvFile,
+		Some(path.display().to_string()),
+		vars,
+		start.elapsed(),
+	));
+	Ok(())
+}
+
+/// Searches the current directory and its ancestors for a `.env` file.
+fn find_dotenv() -> Option<PathBuf> {
+	let cwd = env::current_dir().ok()?;
+	cwd
+		.ancestors()
+		.map(|dir| dir.join(".env"))
+		.find(|p| p.is_file())
+}
+
+/// Extracts `KEY=VALUE` pairs from `--env` / `-e` command-line arguments.
+fn parse_overrides(args: &[String]) -> Result<Vec<(String, String)>, EnvflagError> {
+	let mut overrides = Vec::new();
+	let mut iter = args.iter();
+
+	while let Some(arg) = iter.next() {
+		let pair = match arg.as_str() {
+			"--" => break,
+			"--env" | "-e" => iter
+				.next()
+				.ok_or_else(|| EnvflagError::InvalidOverride { arg: arg.clone() })?,
+			_ => match arg.strip_prefix("--env=") {
+				Some(rest) => rest,
+				None => continue,
+			},
+		};
+
+		match pair.split_once('=') {
+			Some((k, v)) if !k.is_empty() => overrides.push((k.to_owned(), v.to_owned())),
+			_ => {
+				return Err(EnvflagError::InvalidOverride {
+					arg: pair.to_owned(),
+				});
+			}
+		}
+	}
+
+	Ok(overrides)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::schema::KeySpec;
+
+	fn args(list: &[&str]) -> Vec<String> {
+		list.iter().map(|s| (*s).to_owned()).collect()
+	}
+
+	#[test]
+	fn overrides_all_forms() {
+		let parsed = parse_overrides(&args(&[
+			"app",
+			"--env",
+			"PORT=3000",
+			"-e",
+			"HOST=0.0.0.0",
+			"--verbose",
+			"--env=URL=http://a?b=c",
+		]))
+		.unwrap();
+		assert_eq!(
+			parsed,
+			vec![
+				("PORT".to_owned(), "3000".to_owned()),
+				("HOST".to_owned(), "0.0.0.0".to_owned()),
+				("URL".to_owned(), "http://a?b=c".to_owned()),
+			]
+		);
+	}
+
+	#[test]
+	fn overrides_stop_at_double_dash() {
+		let parsed = parse_overrides(&args(&["app", "--", "-e", "PORT=1"])).unwrap();
+		assert!(parsed.is_empty());
+	}
+
+	#[test]
+	fn build_records_layers() {
+		let path = env::temp_dir().join(format!("envflag-layers-{}.env", std::process::id()));
+		std::fs::write(&path, "LAYER_A=file\nLAYER_B=file\n").unwrap();
+
+		let store = InitBuilder::new()
+			.path(&path)
+			.prefix("LAYER_")
+			.null_separated(std::io::Cursor::new(b"LAYER_C=piped\nvalue\0".to_vec()))
+			.args_overrides(["-e", "LAYER_B=cli"])
+			.build()
+			.unwrap();
+		std::fs::remove_file(&path).unwrap();
+
+		let kinds: Vec<_> = store.layers().iter().map(|l| (l.kind, l.keys)).collect();
+		assert_eq!(
+			kinds,
+			vec![
+				(LayerKind::DotenvFile, 2),
+				(LayerKind::ProcessEnv, 0),
+				(LayerKind::Reader, 1),
+				(LayerKind::CommandLine, 1),
+			]
+		);
+		assert_eq!(store.lookup("C", None), Some("piped\nvalue".to_owned()));
+		assert_eq!(store.lookup("A", None), Some("file".to_owned()));
+		assert_eq!(store.lookup("B", None), Some("cli".to_owned()));
+	}
+
+	#[test]
+	fn build_layers_custom_sources_in_order() {
+		struct Fixed(&'static str, &'static [(&'static str, &'static str)]);
+
+		impl Source for Fixed {
+			fn load(&self) -> Result<HashMap<String, String>, EnvflagError> {
+				Ok(
+					self
+						.1
+						.iter()
+						.map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+						.collect(),
+				)
+			}
+
+			fn origin(&self) -> Option<String> {
+				Some(self.0.to_owned())
+			}
+		}
+
+		let store = InitBuilder::new()
+			.prefix("CUSTOM_")
+			.source(Box::new(Fixed(
+				"db",
+				&[("CUSTOM_A", "db"), ("CUSTOM_B", "db")],
+			)))
+			.source(Box::new(Fixed("http", &[("CUSTOM_B", "http")])))
+			.args_overrides(["-e", "CUSTOM_A=cli"])
+			.build()
+			.unwrap();
+
+		assert_eq!(store.lookup("A", None), Some("cli".to_owned()));
+		assert_eq!(store.lookup("B", None), Some("http".to_owned()));
+		let custom: Vec<_> = store
+			.layers()
+			.iter()
+			.filter(|l| l.kind == LayerKind::Custom)
+			.map(|l| (l.origin.as_deref(), l.keys))
+			.collect();
+		assert_eq!(custom, [(Some("db"), 2), (Some("http"), 1)]);
+	}
+
+	#[test]
+	fn build_layers_directories_below_env() {
+		let dir = env::temp_dir().join(format!("envflag-from-dir-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		std::fs::write(dir.join("FROMDIR_A"), "mounted\n").unwrap();
+		std::fs::write(dir.join("FROMDIR_B"), "mounted").unwrap();
+
+		let store = InitBuilder::new()
+			.prefix("FROMDIR_")
+			.from_dir(&dir)
+			.args_overrides(["-e", "FROMDIR_B=cli"])
+			.build()
+			.unwrap();
+		let missing = InitBuilder::new().from_dir(dir.join("missing")).build();
+		std::fs::remove_dir_all(&dir).unwrap();
+
+		assert_eq!(store.lookup("A", None), Some("mounted".to_owned()));
+		assert_eq!(store.lookup("B", None), Some("cli".to_owned()));
+		assert_eq!(
+			store.layer_of("A").map(|l| l.kind),
+			Some(LayerKind::Directory)
+		);
+		assert!(matches!(missing, Err(EnvflagError::Io(_))));
+	}
+
+	#[test]
+	fn build_resolves_file_references() {
+		let secret = env::temp_dir().join(format!("envflag-file-ref-{}", std::process::id()));
+		std::fs::write(&secret, "s3cret\n").unwrap();
+		let arg = format!("FILEREF_PASSWORD_FILE={}", secret.display());
+
+		let store = InitBuilder::new()
+			.prefix("FILEREF_")
+			.file_indirection()
+			.args_overrides(["-e", &arg])
+			.build()
+			.unwrap();
+		let conflict = InitBuilder::new()
+			.prefix("FILEREF_")
+			.file_indirection()
+			.args_overrides(["-e", &arg, "-e", "FILEREF_PASSWORD=inline"])
+			.build();
+		let missing = InitBuilder::new()
+			.prefix("FILEREF_")
+			.file_indirection()
+			.args_overrides(["-e", "FILEREF_TOKEN_FILE=/nonexistent/envflag"])
+			.build();
+		// Across layers the higher one wins, and a losing file is not read.
+		static DEFAULTS: &[(&str, &str)] = &[
+			("FILEREF_PASSWORD", "baked"),
+			("FILEREF_TOKEN_FILE", "/nonexistent/envflag"),
+		];
+		let layered = InitBuilder::new()
+			.prefix("FILEREF_")
+			.file_indirection()
+			.defaults(DEFAULTS)
+			.args_overrides(["-e", &arg, "-e", "FILEREF_TOKEN=inline"])
+			.build()
+			.unwrap();
+		std::fs::remove_file(&secret).unwrap();
+
+		assert_eq!(store.lookup("PASSWORD", None), Some("s3cret".to_owned()));
+		assert_eq!(
+			store.layer_of("PASSWORD").map(|l| l.kind),
+			Some(LayerKind::CommandLine)
+		);
+		assert!(matches!(conflict, Err(EnvflagError::Source { .. })));
+		assert!(matches!(missing, Err(EnvflagError::Source { .. })));
+		assert_eq!(layered.lookup("PASSWORD", None), Some("s3cret".to_owned()));
+		assert_eq!(layered.lookup("TOKEN", None), Some("inline".to_owned()));
+	}
+
+	#[test]
+	fn build_validates_schema() {
+		let schema = Schema::new()
+			.key(KeySpec::new("PORT").type_name("u16").default("8080"))
+			.key(KeySpec::new("DATABASE_URL").required())
+			.key(KeySpec::new("API_TOKEN").required().validator("is_url"));
+
+		let err = InitBuilder::new()
+			.prefix("SCHEMA_")
+			.schema(schema.clone())
+			.args_overrides(["-e", "SCHEMA_PORT=http", "-e", "SCHEMA_API_TOKEN=hunter2"])
+			.build()
+			.unwrap_err();
+		let EnvflagError::SchemaViolation { problems } = err else {
+			panic!("unexpected error: {err}");
+		};
+		assert_eq!(
+			problems,
+			[
+				"PORT: 'http' is not a valid u16",
+				"DATABASE_URL: required but not set",
+				"API_TOKEN: '********' does not pass is_url",
+			]
+		);
+
+		let store = InitBuilder::new()
+			.prefix("SCHEMA_")
+			.schema(schema)
+			.args_overrides([
+				"-e",
+				"SCHEMA_DATABASE_URL=postgres://db",
+				"-e",
+				"SCHEMA_API_TOKEN=https://token",
+			])
+			.build()
+			.unwrap();
+		assert_eq!(store.lookup("PORT", None), None);
+	}
+
+	#[test]
+	fn build_strict_rejects_undeclared_keys() {
+		let schema = Schema::new()
+			.key(KeySpec::new("PORT").type_name("u16").default("8080"))
+			.key(KeySpec::new("TOKEN"));
+		let builder = || {
+			InitBuilder::new()
+				.prefix("STRICT_")
+				.schema(schema.clone())
+				.file_indirection()
+				.args_overrides([
+					"-e",
+					"STRICT_PROT=9000",
+					"-e",
+					"STRICT_CONFIG_VERSION=1",
+					"-e",
+					"STRICT_EXTRA=x",
+					"-e",
+					"STRICT_TOKEN_FILE=/dev/null",
+				])
+		};
+
+		assert!(builder().build().is_ok());
+		let err = builder().strict().build().unwrap_err();
+		let EnvflagError::SchemaViolation { problems } = err else {
+			panic!("unexpected error: {err}");
+		};
+		assert_eq!(
+			problems,
+			[
+				"STRICT_EXTRA: not declared in the schema",
+				"STRICT_PROT: not declared in the schema (did you mean PORT?)",
+			]
+		);
+	}
+
+	#[test]
+	fn check_collects_every_problem() {
+		let schema = Schema::new()
+			.key(KeySpec::new("PORT").type_name("u16"))
+			.key(KeySpec::new("WORKERS").type_name("u8"))
+			.key(KeySpec::new("DATABASE_URL").required());
+		let store = EnvStore::from_map_with_prefixes(
+			HashMap::from([
+				("APP_PORT".to_owned(), "http".to_owned()),
+				("APP_WORKERS".to_owned(), "4".to_owned()),
+			]),
+			vec!["APP_".to_owned()],
+		);
+		let err = store.check(&schema).unwrap_err();
+		assert!(
+			matches!(&err, EnvflagError::SchemaViolation { problems } if problems == &[
+				"PORT: 'http' is not a valid u16",
+				"DATABASE_URL: required but not set",
+			]),
+			"{err}"
+		);
+
+		let store = EnvStore::from_map(HashMap::from([
+			("PORT".to_owned(), "80".to_owned()),
+			("DATABASE_URL".to_owned(), "db".to_owned()),
+		]));
+		assert!(store.check(&schema).is_ok());
+	}
+
+	#[test]
+	fn build_reports_every_missing_required_key() {
+		let builder = || {
+			InitBuilder::new()
+				.prefix("REQUIRE_")
+				.require("DATABASE_URL")
+				.require("PORT")
+				.require("SECRET_KEY")
+		};
+		let err = builder()
+			.args_overrides(["-e", "REQUIRE_PORT=80"])
+			.build()
+			.unwrap_err();
+		assert!(
+			matches!(&err, EnvflagError::MissingKeys { keys } if keys == &["DATABASE_URL", "SECRET_KEY"]),
+			"{err}"
+		);
+		assert_eq!(
+			err.to_string(),
+			"missing required environment variables: DATABASE_URL, SECRET_KEY"
+		);
+
+		let store = builder()
+			.args_overrides([
+				"-e",
+				"REQUIRE_PORT=80",
+				"-e",
+				"REQUIRE_DATABASE_URL=db",
+				"-e",
+				"REQUIRE_SECRET_KEY=k",
+			])
+			.build();
+		assert!(store.is_ok());
+	}
+
+	#[test]
+	fn build_can_redact_values_in_errors() {
+		let store = InitBuilder::new()
+			.prefix("REDACT_")
+			.redact_values_in_errors(true)
+			.args_overrides(["-e", "REDACT_PORT=hunter2"])
+			.build()
+			.unwrap();
+		let err = store.key("PORT").required::<u16>().unwrap_err();
+		assert!(!err.to_string().contains("hunter2"), "{err}");
+		let err = store
+			.key("PORT")
+			.validate(|v| v.is_empty())
+			.required::<String>()
+			.unwrap_err();
+		assert!(!err.to_string().contains("hunter2"), "{err}");
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn nested_keeps_redaction() {
+		let store = InitBuilder::new()
+			.prefix("REDACT_NESTED_")
+			.redact_values_in_errors(true)
+			.args_overrides(["-e", "REDACT_NESTED_DB_PORT=hunter2"])
+			.build()
+			.unwrap();
+		let err = store.nested::<HashMap<String, u16>>("DB_").unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { .. }), "{err}");
+		assert!(!err.to_string().contains("hunter2"), "{err}");
+	}
+
+	#[test]
+	fn refresh_resolves_through_layers() {
+		static DEFAULTS: &[(&str, &str)] = &[("ROTATE_A", "baked"), ("ROTATE_B", "baked")];
+		let fetch = |_: &str| Ok(Some("fetched".to_owned()));
+		let store = InitBuilder::new()
+			.prefix("ROTATE_")
+			.defaults(DEFAULTS)
+			.args_overrides(["-e", "ROTATE_B=cli"])
+			.refresh_key("ROTATE_A", Duration::from_secs(60), fetch)
+			.refresh_key("ROTATE_B", Duration::from_secs(60), fetch)
+			.build()
+			.unwrap();
+		let kind = |s: &EnvStore, k: &str| s.layer_of(k).map(|l| l.kind);
+		assert_eq!(store.lookup_string("A").as_deref(), Some("fetched"));
+		assert_eq!(store.lookup_string("B").as_deref(), Some("cli"));
+
+		let store = store.with_refreshed("ROTATE_B", Some("rotated".to_owned()));
+		assert_eq!(store.lookup_string("B").as_deref(), Some("cli"));
+		assert_eq!(kind(&store, "B"), Some(LayerKind::CommandLine));
+
+		let store = store.with_refreshed("ROTATE_A", None);
+		assert_eq!(store.lookup_string("A").as_deref(), Some("baked"));
+		assert_eq!(kind(&store, "A"), Some(LayerKind::Defaults));
+
+		let store = store.with_refreshed("ROTATE_A", Some("rotated".to_owned()));
+		assert_eq!(store.lookup_string("A").as_deref(), Some("rotated"));
+		assert_eq!(kind(&store, "A"), Some(LayerKind::Remote));
+	}
+
+	#[test]
+	#[should_panic(expected = "ttl must be greater than zero")]
+	fn refresh_rejects_zero_ttl() {
+		let _ = InitBuilder::new().refresh_key("TOKEN", Duration::ZERO, |_| Ok(None));
+	}
+
+	#[test]
+	fn build_layers_defaults_beneath_everything() {
+		static DEFAULTS: &[(&str, &str)] = &[("DEFAULTS_A", "baked"), ("DEFAULTS_B", "baked")];
+		let store = InitBuilder::new()
+			.prefix("DEFAULTS_")
+			.defaults(DEFAULTS)
+			.args_overrides(["-e", "DEFAULTS_B=cli"])
+			.build()
+			.unwrap();
+		assert_eq!(store.layers()[0].kind, LayerKind::Defaults);
+		assert_eq!(store.lookup("A", None), Some("baked".to_owned()));
+		assert_eq!(store.lookup("B", None), Some("cli".to_owned()));
+	}
+
+	#[test]
+	fn build_layers_local_and_profile_files() {
+		let dir = env::temp_dir().join(format!("envflag-layered-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		std::fs::write(
+			dir.join(".env"),
+			"APP_ENV=staging\nLAYERED_A=env\nLAYERED_B=env\n",
+		)
+		.unwrap();
+		std::fs::write(dir.join(".env.local"), "LAYERED_B=local\nLAYERED_C=local\n").unwrap();
+		std::fs::write(dir.join(".env.staging"), "LAYERED_C=staging\n").unwrap();
+		std::fs::write(dir.join(".env.production"), "LAYERED_C=production\n").unwrap();
+
+		let store = InitBuilder::new()
+			.path(dir.join(".env"))
+			.prefix("LAYERED_")
+			.layered()
+			.build()
+			.unwrap();
+		std::fs::remove_dir_all(&dir).unwrap();
+
+		assert_eq!(store.lookup("A", None), Some("env".to_owned()));
+		assert_eq!(store.lookup("B", None), Some("local".to_owned()));
+		assert_eq!(store.lookup("C", None), Some("staging".to_owned()));
+		let origin = |key| store.layer_of(key).and_then(|l| l.origin.clone()).unwrap();
+		assert!(origin("A").ends_with(".env"));
+		assert!(origin("B").ends_with(".env.local"));
+		assert!(origin("C").ends_with(".env.staging"));
+		assert_eq!(store.layer_of("MISSING"), None);
+		assert_eq!(store.profile(), Some("staging"));
+	}
+
+	#[test]
+	fn build_loads_explicit_profile_file() {
+		let dir = env::temp_dir().join(format!("envflag-profile-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+		std::fs::write(dir.join(".env"), "PROFILE_A=env\nPROFILE_B=env\n").unwrap();
+		std::fs::write(dir.join(".env.local"), "PROFILE_A=local\n").unwrap();
+		std::fs::write(dir.join(".env.production"), "PROFILE_B=production\n").unwrap();
+
+		let store = InitBuilder::new()
+			.path(dir.join(".env"))
+			.prefix("PROFILE_")
+			.profile("production")
+			.build()
+			.unwrap();
+		std::fs::remove_dir_all(&dir).unwrap();
+
+		assert_eq!(store.profile(), Some("production"));
+		assert_eq!(store.lookup("A", None), Some("env".to_owned()));
+		assert_eq!(store.lookup("B", None), Some("production".to_owned()));
+	}
+
+	#[test]
+	fn build_on_demand_reads_env_lazily() {
+		// Cargo sets these for every test binary it runs.
+		static DEFAULTS: &[(&str, &str)] = &[("CARGO_PKG_NAME", "baked")];
+		let store = InitBuilder::new()
+			.on_demand()
+			.defaults(DEFAULTS)
+			.build()
+			.unwrap();
+		assert_eq!(store.layers()[1].origin.as_deref(), Some("on demand"));
+		assert_eq!(
+			store.lookup("CARGO_PKG_NAME", None),
+			Some("envflag".to_owned())
+		);
+		assert!(!store.entry_map().contains_key("CARGO_MANIFEST_DIR"));
+		assert_eq!(
+			store.lookup("CARGO_MANIFEST_DIR", None),
+			Some(env!("CARGO_MANIFEST_DIR").to_owned())
+		);
+		assert_eq!(store.lookup("ENVFLAG_ON_DEMAND_UNSET", None), None);
+	}
+
+	#[test]
+	fn build_on_demand_rejects_full_scans() {
+		let err = InitBuilder::new()
+			.on_demand()
+			.strict()
+			.file_indirection()
+			.build()
+			.unwrap_err();
+		assert!(matches!(
+			err,
+			EnvflagError::IncompatibleOptions { ref message }
+				if message == "on_demand() cannot be combined with strict(), file_indirection()"
+		));
+	}
+
+	#[test]
+	fn on_demand_layer_of_follows_prefix_chain() {
+		temp_env::with_var("DEMAND_HI_PORT", Some("1"), || {
+			let store = InitBuilder::new()
+				.prefix_chain(["DEMAND_HI_", "DEMAND_LO_"])
+				.on_demand()
+				.args_overrides(["-e", "DEMAND_LO_PORT=2"])
+				.build()
+				.unwrap();
+			assert_eq!(store.lookup("PORT", None), Some("1".to_owned()));
+			assert_eq!(store.storage_key("PORT"), "DEMAND_HI_PORT");
+			assert!(store.layer_of("PORT").is_none());
+		});
+	}
+
+	#[test]
+	fn build_compares_shadow() {
+		let secondary = InitBuilder::new().prefix("SHADOW_").args_overrides([
+			"-e",
+			"SHADOW_A=1",
+			"-e",
+			"SHADOW_B=new",
+		]);
+		let store = InitBuilder::new()
+			.prefix("SHADOW_")
+			.args_overrides(["-e", "SHADOW_A=1", "-e", "SHADOW_B=old", "-e", "SHADOW_C=x"])
+			.shadow(secondary)
+			.build()
+			.unwrap();
+
+		assert_eq!(store.lookup("B", None), Some("old".to_owned()));
+		let report = store.shadow_report().unwrap();
+		assert_eq!(report.differing, ["SHADOW_B"]);
+		assert_eq!(report.only_primary, ["SHADOW_C"]);
+
+		let failing = InitBuilder::new()
+			.shadow(InitBuilder::new().path("/nonexistent/envflag.env"))
+			.build()
+			.unwrap();
+		assert!(failing.shadow_report().unwrap().error.is_some());
+	}
+
+	#[test]
+	fn overrides_malformed() {
+		for bad in [&["-e"][..], &["-e", "PORT"], &["--env==1"]] {
+			let err = parse_overrides(&args(bad)).unwrap_err();
+			assert!(matches!(err, EnvflagError::InvalidOverride { .. }));
+		}
 	}
 }
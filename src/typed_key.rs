@@ -0,0 +1,185 @@
+/* src/typed_key.rs */
+
+//! Typed `static` key declarations with cached parsing.
+
+use std::fmt;
+use std::mem;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+
+use crate::error::EnvflagError;
+use crate::store::EnvStore;
+use crate::usage::DefaultReason;
+
+/// A typed variable declared once, usually as a `static`, and parsed at most
+/// once per loaded configuration.
+///
+/// Declaring keys as statics keeps the application's inventory of variables
+/// in one place. The parsed value is cached against the store it was read
+/// from, so hot paths do not re-parse on every access; a [`reload()`](crate::reload())
+/// installs a new store and the next access parses again.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::Key;
+///
+/// static PORT: Key<u16> = Key::new("PORT").default(8080);
+/// static DATABASE_URL: Key<String> = Key::new("DATABASE_URL");
+///
+/// envflag::builder().args_overrides(["-e", "PORT=9000"]).init().unwrap();
+/// assert_eq!(PORT.get().unwrap(), 9000);
+/// assert!(DATABASE_URL.get().is_err());
+/// ```
+pub struct Key<T> {
+	name: &'static str,
+	default: Option<T>,
+	cache: Mutex<Option<Cached<T>>>,
+}
+
+/// A parsed value and the store it was parsed from.
+struct Cached<T> {
+	store: Weak<EnvStore>,
+	value: T,
+}
+
+impl<T> Key<T> {
+	/// Declares a key with no default; [`get`](Self::get) fails if it is not
+	/// set.
+	#[must_use]
+	pub const fn new(name: &'static str) -> Self {
+		Self {
+			name,
+			default: None,
+			cache: Mutex::new(None),
+		}
+	}
+
+	/// Sets the value returned when the variable is not set.
+	#[must_use]
+	#[allow(clippy::mem_forget)]
+	pub const fn default(mut self, value: T) -> Self {
+		// Dropping the old default cannot run in a `static` initializer, so
+		// forget it; it is `None` unless `default` is called twice.
+		mem::forget(self.default.replace(value));
+		self
+	}
+
+	/// Returns the variable name.
+	#[must_use]
+	pub const fn name(&self) -> &'static str {
+		self.name
+	}
+}
+
+impl<T> Key<T>
+where
+	T: FromStr + Clone + 'static,
+{
+	/// Returns the parsed value from the global store.
+	///
+	/// # Errors
+	///
+	/// - `EnvflagError::NotInitialized` if the crate has not been initialized.
+	/// - `EnvflagError::NotSet` if the variable is missing and has no default.
+	/// - `EnvflagError::ParseFailed` if parsing fails.
+	/// - `EnvflagError::AmbiguousPrefix` if multiple prefixes are configured.
+	pub fn get(&self) -> Result<T, EnvflagError> {
+		self.get_from(&EnvStore::get_instance()?)
+	}
+
+	/// Returns the parsed value from `store`, using the cached value if it
+	/// was parsed from the same store.
+	fn get_from(&self, store: &Arc<EnvStore>) -> Result<T, EnvflagError> {
+		let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+		if let Some(cached) = cache.as_ref()
+			&& cached.store.as_ptr() == Arc::as_ptr(store)
+		{
+			return Ok(cached.value.clone());
+		}
+
+		let value = self.parse(store)?;
+		*cache = Some(Cached {
+			store: Arc::downgrade(store),
+			value: value.clone(),
+		});
+		Ok(value)
+	}
+
+	fn parse(&self, store: &EnvStore) -> Result<T, EnvflagError> {
+		if let Some(value) = store.key(self.name).optional()? {
+			return Ok(value);
+		}
+		let value = self
+			.default
+			.clone()
+			.ok_or_else(|| store.not_set(&[self.name], None))?;
+		store.record_default(self.name, DefaultReason::Missing);
+		Ok(value)
+	}
+}
+
+impl<T: fmt::Debug> fmt::Debug for Key<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Key")
+			.field("name", &self.name)
+			.field("default", &self.default)
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashMap;
+
+	fn store(pairs: &[(&str, &str)]) -> Arc<EnvStore> {
+		Arc::new(EnvStore::from_map(
+			pairs
+				.iter()
+				.map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+				.collect::<HashMap<_, _>>(),
+		))
+	}
+
+	#[test]
+	fn value_is_cached_per_store() {
+		static PORT: Key<u16> = Key::new("PORT").default(8080);
+
+		let first = store(&[("PORT", "9000")]);
+		assert_eq!(PORT.get_from(&first).unwrap(), 9000);
+		assert_eq!(PORT.get_from(&first).unwrap(), 9000);
+
+		let second = store(&[]);
+		assert_eq!(PORT.get_from(&second).unwrap(), 8080);
+		assert_eq!(PORT.get_from(&first).unwrap(), 9000);
+	}
+
+	#[test]
+	fn errors_are_not_cached() {
+		static WORKERS: Key<u8> = Key::new("WORKERS");
+
+		let err = WORKERS.get_from(&store(&[])).unwrap_err();
+		assert!(matches!(err, EnvflagError::NotSet { .. }), "{err}");
+		let err = WORKERS
+			.get_from(&store(&[("WORKERS", "many")]))
+			.unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { .. }), "{err}");
+		assert_eq!(WORKERS.get_from(&store(&[("WORKERS", "4")])).unwrap(), 4);
+	}
+
+	#[test]
+	fn parse_errors_honor_redaction() {
+		static TOKEN: Key<u16> = Key::new("TOKEN");
+
+		let store = crate::InitBuilder::new()
+			.prefix("KEY_REDACT_")
+			.redact_values_in_errors(true)
+			.args_overrides(["-e", "KEY_REDACT_TOKEN=hunter2"])
+			.build()
+			.unwrap();
+		let err = TOKEN.get_from(&Arc::new(store)).unwrap_err();
+		assert!(matches!(err, EnvflagError::ParseFailed { .. }), "{err}");
+		assert!(!err.to_string().contains("hunter2"), "{err}");
+	}
+}
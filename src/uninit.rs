@@ -0,0 +1,94 @@
+/* src/uninit.rs */
+
+//! Diagnostics for queries made before initialization.
+//!
+//! The convenience query functions panic when called before
+//! [`init()`](crate::init), naming the key that was queried. Code that must
+//! not panic, such as a library reading optional settings, can run those
+//! queries inside [`catch_uninitialized`] to get an error instead.
+
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+use crate::error::EnvflagError;
+use crate::store::EnvStore;
+
+thread_local! {
+	/// How many `catch_uninitialized` calls are active on this thread.
+	static CATCHING: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Unwind payload used inside `catch_uninitialized`.
+struct Uninitialized;
+
+/// Returns the global store, or diverges with a diagnostic naming `call`.
+///
+/// `key` is the variable being queried, if any.
+pub(crate) fn global(call: &str, key: Option<&str>) -> Arc<EnvStore> {
+	EnvStore::get_instance().unwrap_or_else(|_| uninitialized(call, key))
+}
+
+fn uninitialized(call: &str, key: Option<&str>) -> ! {
+	if CATCHING.get() > 0 {
+		// Unwind straight to `catch_uninitialized` without running the panic
+		// hook, so nothing is printed for an error the caller handles.
+		panic::resume_unwind(Box::new(Uninitialized));
+	}
+
+	let alternative = key.map_or_else(String::new, |k| {
+		format!(
+			", or use `envflag::key(\"{k}\")`, which returns `EnvflagError::NotInitialized` instead of panicking"
+		)
+	});
+	let subject = key.map_or_else(
+		|| format!("`envflag::{call}()`"),
+		|k| format!("`envflag::{call}(\"{k}\")`"),
+	);
+	panic!(
+		"envflag is not initialized: {subject} was called before initialization. \
+		 Call `envflag::init()` (or `envflag::builder()...init()`) early in `main()`{alternative}; \
+		 library code can wrap the call in `envflag::catch_uninitialized`."
+	);
+}
+
+/// Runs `f`, turning an uninitialized-access panic inside it into an error.
+///
+/// Lets library code call the panicking convenience functions
+/// ([`get`](crate::get), [`is_set`](crate::is_set), …) without imposing an
+/// initialization requirement on the application: if envflag was never
+/// initialized, the call returns `EnvflagError::NotInitialized` and nothing
+/// is printed. Any other panic propagates unchanged.
+///
+/// Requires unwinding; with `panic = "abort"` the process still aborts.
+///
+/// # Errors
+///
+/// Returns `EnvflagError::NotInitialized` if `f` queried the global store
+/// before initialization.
+///
+/// # Examples
+///
+/// ```rust
+/// let verbose = envflag::catch_uninitialized(|| envflag::get("VERBOSE", false)).unwrap_or(false);
+/// assert!(!verbose);
+/// ```
+pub fn catch_uninitialized<T>(f: impl FnOnce() -> T) -> Result<T, EnvflagError> {
+	struct Depth;
+	impl Drop for Depth {
+		fn drop(&mut self) {
+			CATCHING.set(CATCHING.get() - 1);
+		}
+	}
+
+	CATCHING.set(CATCHING.get() + 1);
+	let depth = Depth;
+	let result = panic::catch_unwind(AssertUnwindSafe(f));
+	drop(depth);
+
+	match result {
+		Ok(v) => Ok(v),
+		Err(payload) if payload.is::<Uninitialized>() => Err(EnvflagError::NotInitialized),
+		Err(payload) => panic::resume_unwind(payload),
+	}
+}
@@ -0,0 +1,105 @@
+/* src/usage.rs */
+
+//! Tracking which variables the application actually read.
+
+#[cfg(feature = "usage")]
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "usage")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "usage")]
+use std::sync::{PoisonError, RwLock};
+
+/// Why a query returned its default instead of the configured value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DefaultReason {
+	/// The variable is not set.
+	Missing,
+	/// The variable is set but its value could not be parsed.
+	ParseFailed,
+}
+
+/// Read counts per full variable name and defaulted keys, kept by each
+/// store.
+#[cfg(feature = "usage")]
+#[derive(Debug, Default)]
+pub(crate) struct Usage {
+	counts: RwLock<HashMap<String, AtomicU64>>,
+	/// Keys that fell back to their default, with the latest reason.
+	defaulted: RwLock<BTreeMap<String, DefaultReason>>,
+}
+
+#[cfg(feature = "usage")]
+impl Usage {
+	/// Counts a read of the full variable name `key`. Only the first read of
+	/// a key takes the write lock.
+	pub(crate) fn record(&self, key: &str) {
+		if let Some(count) = self
+			.counts
+			.read()
+			.unwrap_or_else(PoisonError::into_inner)
+			.get(key)
+		{
+			count.fetch_add(1, Ordering::Relaxed);
+			return;
+		}
+		self
+			.counts
+			.write()
+			.unwrap_or_else(PoisonError::into_inner)
+			.entry(key.to_owned())
+			.or_default()
+			.fetch_add(1, Ordering::Relaxed);
+	}
+
+	/// Returns every key read so far with its count, sorted by name.
+	pub(crate) fn report(&self) -> Vec<(String, u64)> {
+		let mut report: Vec<(String, u64)> = self
+			.counts
+			.read()
+			.unwrap_or_else(PoisonError::into_inner)
+			.iter()
+			.map(|(key, count)| (key.clone(), count.load(Ordering::Relaxed)))
+			.collect();
+		report.sort();
+		report
+	}
+
+	/// Returns the names in `keys` that have never been read, sorted.
+	pub(crate) fn unread<'a>(&self, keys: impl Iterator<Item = &'a String>) -> Vec<String> {
+		let counts = self.counts.read().unwrap_or_else(PoisonError::into_inner);
+		let mut unread: Vec<String> = keys.filter(|k| !counts.contains_key(*k)).cloned().collect();
+		unread.sort();
+		unread
+	}
+
+	/// Records that the query for `key` returned its default. Only a new
+	/// key or a changed reason takes the write lock.
+	pub(crate) fn record_default(&self, key: &str, reason: DefaultReason) {
+		if self
+			.defaulted
+			.read()
+			.unwrap_or_else(PoisonError::into_inner)
+			.get(key)
+			== Some(&reason)
+		{
+			return;
+		}
+		self
+			.defaulted
+			.write()
+			.unwrap_or_else(PoisonError::into_inner)
+			.insert(key.to_owned(), reason);
+	}
+
+	/// Returns every defaulted key with its reason, sorted by key.
+	pub(crate) fn defaulted(&self) -> Vec<(String, DefaultReason)> {
+		self
+			.defaulted
+			.read()
+			.unwrap_or_else(PoisonError::into_inner)
+			.iter()
+			.map(|(key, reason)| (key.clone(), *reason))
+			.collect()
+	}
+}
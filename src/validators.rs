@@ -8,6 +8,42 @@ pub fn is_non_empty(s: &str) -> bool {
 	!s.trim().is_empty()
 }
 
+/// Checks if a string consists only of printable ASCII characters (space
+/// through `~`).
+///
+/// Unlike most validators, the value is not trimmed, so a stray trailing
+/// newline from a copy-pasted secret is rejected.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::is_printable_ascii;
+///
+/// assert!(is_printable_ascii("s3cr3t-T0ken!"));
+/// assert!(!is_printable_ascii("s3cr3t\n"));
+/// assert!(!is_printable_ascii("café"));
+/// ```
+#[must_use]
+pub fn is_printable_ascii(s: &str) -> bool {
+	s.bytes().all(|b| (b' '..=b'~').contains(&b))
+}
+
+/// Checks if a string contains no control characters, such as newlines,
+/// tabs, or terminal escape codes. Other Unicode is allowed.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::has_no_control_chars;
+///
+/// assert!(has_no_control_chars("café"));
+/// assert!(!has_no_control_chars("red\u{1b}[31m"));
+/// ```
+#[must_use]
+pub fn has_no_control_chars(s: &str) -> bool {
+	!s.chars().any(char::is_control)
+}
+
 /// Checks if a string can be parsed as an integer.
 #[must_use]
 pub fn is_integer(s: &str) -> bool {
@@ -40,6 +76,169 @@ pub fn is_bool(s: &str) -> bool {
 	matches!(s.as_str(), "true" | "1" | "yes" | "false" | "0" | "no")
 }
 
+/// Checks if a string is a human-friendly duration such as `30s`, `5m`, or
+/// `1h 30m`.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::is_duration;
+///
+/// assert!(is_duration("2h"));
+/// assert!(is_duration("1h 30m"));
+/// assert!(!is_duration("30"));
+/// ```
+#[cfg(feature = "humantime")]
+#[must_use]
+pub fn is_duration(s: &str) -> bool {
+	humantime::parse_duration(s.trim()).is_ok()
+}
+
+/// Returns a validator that accepts only the listed values, e.g. log levels.
+///
+/// Surrounding whitespace is ignored; the comparison is otherwise exact.
+/// See [`one_of_ignore_case`] for a case-insensitive variant.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::one_of;
+///
+/// let is_level = one_of(["debug", "info", "warn", "error"]);
+/// assert!(is_level("info"));
+/// assert!(!is_level("INFO"));
+/// assert!(!is_level("trace"));
+/// ```
+pub fn one_of<S: Into<String>>(allowed: impl IntoIterator<Item = S>) -> impl Fn(&str) -> bool {
+	let allowed: Vec<String> = allowed.into_iter().map(Into::into).collect();
+	move |s| allowed.iter().any(|a| a == s.trim())
+}
+
+/// Like [`one_of`], but ignores ASCII case.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::one_of_ignore_case;
+///
+/// let is_level = one_of_ignore_case(["debug", "info", "warn", "error"]);
+/// assert!(is_level("INFO"));
+/// assert!(!is_level("trace"));
+/// ```
+pub fn one_of_ignore_case<S: Into<String>>(
+	allowed: impl IntoIterator<Item = S>,
+) -> impl Fn(&str) -> bool {
+	let allowed: Vec<String> = allowed.into_iter().map(Into::into).collect();
+	move |s| allowed.iter().any(|a| a.eq_ignore_ascii_case(s.trim()))
+}
+
+/// Returns a validator that passes if any of `validators` passes.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::{any_of, is_port, is_url};
+///
+/// let check = any_of([is_url, is_port]);
+/// assert!(check("8080"));
+/// assert!(check("https://example.com"));
+/// assert!(!check("neither"));
+/// ```
+pub fn any_of<F: Fn(&str) -> bool>(
+	validators: impl IntoIterator<Item = F>,
+) -> impl Fn(&str) -> bool {
+	let validators: Vec<F> = validators.into_iter().collect();
+	move |s| validators.iter().any(|f| f(s))
+}
+
+/// Returns a validator that passes if every one of `validators` passes.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::{all_of, is_integer, is_non_empty};
+///
+/// let check = all_of([is_non_empty, is_integer]);
+/// assert!(check("42"));
+/// assert!(!check("forty-two"));
+/// ```
+pub fn all_of<F: Fn(&str) -> bool>(
+	validators: impl IntoIterator<Item = F>,
+) -> impl Fn(&str) -> bool {
+	let validators: Vec<F> = validators.into_iter().collect();
+	move |s| validators.iter().all(|f| f(s))
+}
+
+/// Returns a validator that passes if `validator` fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::{is_integer, not};
+///
+/// let check = not(is_integer);
+/// assert!(check("abc"));
+/// assert!(!check("123"));
+/// ```
+pub fn not(validator: impl Fn(&str) -> bool) -> impl Fn(&str) -> bool {
+	move |s| !validator(s)
+}
+
+/// Looks up a built-in validator by its function name, e.g. `"is_port"`.
+///
+/// Only validators that take no arguments are available by name. Used to
+/// resolve the validators listed in a [`KeySpec`](crate::KeySpec).
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::by_name;
+///
+/// let check = by_name("is_port").unwrap();
+/// assert!(check("8080"));
+/// assert!(by_name("is_unknown").is_none());
+/// ```
+#[must_use]
+pub fn by_name(name: &str) -> Option<fn(&str) -> bool> {
+	let f: fn(&str) -> bool = match name {
+		"is_non_empty" => is_non_empty,
+		"is_integer" => is_integer,
+		"is_positive_integer" => is_positive_integer,
+		"is_positive_number" => is_positive_number,
+		"is_bool" => is_bool,
+		"is_port" => is_port,
+		"is_ip" => is_ip,
+		"is_log_level" => is_log_level,
+		"is_uuid" => is_uuid,
+		"is_email" => is_email,
+		"is_hostname" => is_hostname,
+		"is_semver" => is_semver,
+		"file_exists" => file_exists,
+		"dir_exists" => dir_exists,
+		"path_is_absolute" => path_is_absolute,
+		"is_readable" => is_readable,
+		"is_hex" => is_hex,
+		"is_base64" => is_base64,
+		"is_printable_ascii" => is_printable_ascii,
+		"has_no_control_chars" => has_no_control_chars,
+		"is_socket_addr" => is_socket_addr,
+		"is_url" => is_url,
+		"is_pem" => is_pem,
+		"is_aws_arn" => is_aws_arn,
+		"is_docker_image_ref" => is_docker_image_ref,
+		"is_ssh_url" => is_ssh_url,
+		"is_git_url" => is_git_url,
+		#[cfg(feature = "humantime")]
+		"is_duration" => is_duration,
+		#[cfg(feature = "chrono")]
+		"is_rfc3339" => is_rfc3339,
+		#[cfg(feature = "chrono")]
+		"is_time_of_day" => is_time_of_day,
+		_ => return None,
+	};
+	Some(f)
+}
+
 /// Internal helper to normalize boolean strings.
 pub(crate) fn normalize_bool(s: &str) -> std::borrow::Cow<'_, str> {
 	let trimmed = s.trim();
@@ -78,12 +277,283 @@ pub fn is_integer_in_range(min: i64, max: i64) -> impl Fn(&str) -> bool {
 	move |s| s.trim().parse::<i64>().is_ok_and(|v| v >= min && v <= max)
 }
 
+/// Returns a validator that checks if a string can be parsed as a finite
+/// number within the given inclusive range, e.g. for ratios, sampling
+/// rates, and percentages.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::is_float_in_range;
+///
+/// let is_ratio = is_float_in_range(0.0, 1.0);
+/// assert!(is_ratio("0.25"));
+/// assert!(is_ratio("1"));
+/// assert!(!is_ratio("1.5"));
+/// assert!(!is_ratio("NaN"));
+/// ```
+pub fn is_float_in_range(min: f64, max: f64) -> impl Fn(&str) -> bool {
+	move |s| {
+		s.trim()
+			.parse::<f64>()
+			.is_ok_and(|v| v.is_finite() && v >= min && v <= max)
+	}
+}
+
+/// Returns a validator that checks the length of a string, in characters,
+/// is within the given inclusive range.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::len_between;
+///
+/// let is_api_key = len_between(32, 32);
+/// assert!(is_api_key("0123456789abcdef0123456789abcdef"));
+/// assert!(!is_api_key("too-short"));
+/// ```
+pub fn len_between(min: usize, max: usize) -> impl Fn(&str) -> bool {
+	move |s| (min..=max).contains(&s.chars().count())
+}
+
+/// Returns a validator that checks a string is at most `max` characters.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::max_len;
+///
+/// let is_short = max_len(8);
+/// assert!(is_short("service"));
+/// assert!(!is_short("much-too-long"));
+/// ```
+pub fn max_len(max: usize) -> impl Fn(&str) -> bool {
+	move |s| s.chars().count() <= max
+}
+
 /// Checks if a string is a valid port number (1-65535).
 #[must_use]
 pub fn is_port(s: &str) -> bool {
 	s.trim().parse::<u16>().is_ok_and(|v| v > 0)
 }
 
+/// Checks if a string is an RFC 3339 timestamp such as
+/// `2024-05-01T02:00:00Z`.
+#[cfg(feature = "chrono")]
+#[must_use]
+pub fn is_rfc3339(s: &str) -> bool {
+	crate::parse::datetime(s.trim()).is_some()
+}
+
+/// Checks if a string is a 24-hour time of day, `HH:MM` or `HH:MM:SS`.
+#[cfg(feature = "chrono")]
+#[must_use]
+pub fn is_time_of_day(s: &str) -> bool {
+	crate::parse::time_of_day(s.trim()).is_some()
+}
+
+/// Checks if a string is a UUID in the hyphenated form, e.g.
+/// `67e55044-10b1-426f-9247-bb680e5fe0c8`, in either case.
+///
+/// Any version is accepted, as is the nil UUID.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::is_uuid;
+///
+/// assert!(is_uuid("67e55044-10b1-426f-9247-bb680e5fe0c8"));
+/// assert!(!is_uuid("67e5504410b1426f9247bb680e5fe0c8"));
+/// ```
+#[must_use]
+pub fn is_uuid(s: &str) -> bool {
+	let groups: Vec<&str> = s.trim().split('-').collect();
+	groups.len() == 5
+		&& groups
+			.iter()
+			.zip([8, 4, 4, 4, 12])
+			.all(|(g, len)| g.len() == len && g.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Checks if a string is a semantic version such as `1.4.0`,
+/// `2.0.0-rc.1`, or `1.0.0+build.5`, e.g. for `MIN_CLIENT_VERSION`.
+///
+/// When the `semver` feature is enabled, this uses the
+/// [`semver`](https://docs.rs/semver) crate; otherwise the SemVer 2.0.0
+/// grammar is checked directly. Either way a leading `v` is rejected.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::is_semver;
+///
+/// assert!(is_semver("1.4.0"));
+/// assert!(is_semver("2.0.0-rc.1+build.5"));
+/// assert!(!is_semver("1.4"));
+/// assert!(!is_semver("v1.4.0"));
+/// assert!(!is_semver("01.4.0"));
+/// ```
+#[must_use]
+pub fn is_semver(s: &str) -> bool {
+	#[cfg(feature = "semver")]
+	{
+		semver::Version::parse(s.trim()).is_ok()
+	}
+	#[cfg(not(feature = "semver"))]
+	{
+		is_semver_grammar(s.trim())
+	}
+}
+
+/// Checks `MAJOR.MINOR.PATCH[-PRE][+BUILD]` against the SemVer 2.0.0 grammar.
+#[cfg(not(feature = "semver"))]
+fn is_semver_grammar(s: &str) -> bool {
+	let numeric = |id: &str| {
+		!id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()) && (id == "0" || !id.starts_with('0'))
+	};
+	let identifier =
+		|id: &str| !id.is_empty() && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-');
+
+	let (rest, build) = match s.split_once('+') {
+		Some((rest, build)) => (rest, Some(build)),
+		None => (s, None),
+	};
+	let (core, pre) = match rest.split_once('-') {
+		Some((core, pre)) => (core, Some(pre)),
+		None => (rest, None),
+	};
+	let parts: Vec<&str> = core.split('.').collect();
+	parts.len() == 3
+		&& parts.iter().all(|p| numeric(p))
+		&& pre.is_none_or(|pre| {
+			pre
+				.split('.')
+				.all(|id| identifier(id) && (!id.bytes().all(|b| b.is_ascii_digit()) || numeric(id)))
+		}) && build.is_none_or(|build| build.split('.').all(identifier))
+}
+
+/// Checks if a string is a host name under RFC 1123: dot-separated labels
+/// of 1-63 letters, digits, and hyphens, not starting or ending with a
+/// hyphen, at most 253 characters in total. A trailing dot is allowed.
+///
+/// Catches schemes, ports, or paths pasted into a `HOST` variable.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::is_hostname;
+///
+/// assert!(is_hostname("db-1.internal.example.com"));
+/// assert!(is_hostname("localhost"));
+/// assert!(!is_hostname("https://example.com"));
+/// assert!(!is_hostname("example.com:5432"));
+/// assert!(!is_hostname("-bad.example.com"));
+/// ```
+#[must_use]
+pub fn is_hostname(s: &str) -> bool {
+	crate::parse::is_dns_name(s.trim())
+}
+
+/// Checks if a string looks like an email address: `local@domain`.
+///
+/// This is a deliberately light check, not full RFC 5322: the local part
+/// must be 1-64 characters without whitespace or `@`, and the domain must be
+/// a valid host name containing at least one dot.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::is_email;
+///
+/// assert!(is_email("alerts@example.com"));
+/// assert!(!is_email("alerts@localhost"));
+/// assert!(!is_email("no reply@example.com"));
+/// ```
+#[must_use]
+pub fn is_email(s: &str) -> bool {
+	let Some((local, domain)) = s.trim().split_once('@') else {
+		return false;
+	};
+	!local.is_empty()
+		&& local.len() <= 64
+		&& !local.contains(char::is_whitespace)
+		&& domain.contains('.')
+		&& crate::parse::is_dns_name(domain)
+}
+
+/// Checks if a string is hexadecimal, two digits per byte, in either case.
+#[must_use]
+pub fn is_hex(s: &str) -> bool {
+	crate::parse::decode_hex(s.trim()).is_some()
+}
+
+/// Checks if a string is base64 in the standard or URL-safe alphabet, with
+/// or without `=` padding.
+#[must_use]
+pub fn is_base64(s: &str) -> bool {
+	crate::parse::decode_base64(s.trim()).is_some()
+}
+
+/// Checks if a string is the path of an existing regular file (following
+/// symlinks), e.g. for `TLS_CERT_PATH`.
+///
+/// Checks the file system at validation time, so a missing file fails
+/// startup instead of surfacing as an I/O error later.
+#[must_use]
+pub fn file_exists(s: &str) -> bool {
+	std::path::Path::new(s.trim()).is_file()
+}
+
+/// Checks if a string is the path of an existing directory (following
+/// symlinks).
+#[must_use]
+pub fn dir_exists(s: &str) -> bool {
+	std::path::Path::new(s.trim()).is_dir()
+}
+
+/// Checks if a string is an absolute path. Does not touch the file system.
+#[must_use]
+pub fn path_is_absolute(s: &str) -> bool {
+	std::path::Path::new(s.trim()).is_absolute()
+}
+
+/// Checks if a string is the path of a file this process can open for
+/// reading.
+#[must_use]
+pub fn is_readable(s: &str) -> bool {
+	let path = std::path::Path::new(s.trim());
+	path.is_file() && std::fs::File::open(path).is_ok()
+}
+
+/// Checks if a string is a log level: `trace`, `debug`, `info`, `warn`,
+/// `error`, or `off`, in any case, ignoring surrounding whitespace.
+///
+/// Numeric levels, which `tracing` also parses, are rejected. Read a value
+/// with [`log_level`](crate::KeyBuilder::log_level) or its siblings, which
+/// trim it before parsing.
+#[must_use]
+pub fn is_log_level(s: &str) -> bool {
+	["trace", "debug", "info", "warn", "error", "off"]
+		.iter()
+		.any(|level| level.eq_ignore_ascii_case(s.trim()))
+}
+
+/// Checks if a string is an IPv4 or IPv6 address, e.g. `10.0.0.1` or `::1`.
+#[must_use]
+pub fn is_ip(s: &str) -> bool {
+	s.trim().parse::<std::net::IpAddr>().is_ok()
+}
+
+/// Checks if a string is a socket address, e.g. `0.0.0.0:8080` or
+/// `[::1]:443`.
+///
+/// Unlike [`host_port`](crate::KeyBuilder::host_port), the host must be an
+/// IP address, not a DNS name.
+#[must_use]
+pub fn is_socket_addr(s: &str) -> bool {
+	s.trim().parse::<std::net::SocketAddr>().is_ok()
+}
+
 /// Checks if a string is a valid URL.
 ///
 /// When the `url` feature is enabled, this uses the [`url`](https://docs.rs/url)
@@ -103,6 +573,383 @@ pub fn is_url(s: &str) -> bool {
 	}
 }
 
+/// Checks if a string contains at least one well-formed PEM block.
+///
+/// Literal `\n` escapes are treated as newlines, so single-line values as
+/// commonly stored in environment variables are accepted. Each
+/// `-----BEGIN <LABEL>-----` must be closed by a matching
+/// `-----END <LABEL>-----`, with base64 (or `Key: value` header) lines in
+/// between.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::is_pem;
+///
+/// assert!(is_pem("-----BEGIN CERTIFICATE-----\\nMIIB\\n-----END CERTIFICATE-----"));
+/// assert!(!is_pem("-----BEGIN CERTIFICATE-----\\nMIIB\\n-----END PRIVATE KEY-----"));
+/// assert!(!is_pem("/etc/tls/cert.pem"));
+/// ```
+#[must_use]
+pub fn is_pem(s: &str) -> bool {
+	let text = crate::parse::unescape_newlines(s);
+	let mut label: Option<&str> = None;
+	let mut blocks = 0;
+
+	for line in text.lines().map(str::trim) {
+		match label {
+			None => {
+				if let Some(l) = pem_boundary(line, "BEGIN") {
+					label = Some(l);
+				}
+			}
+			Some(open) => {
+				if let Some(l) = pem_boundary(line, "END") {
+					if l != open {
+						return false;
+					}
+					label = None;
+					blocks += 1;
+				} else if !line.contains(':')
+					&& !line
+						.bytes()
+						.all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'='))
+				{
+					return false;
+				}
+			}
+		}
+	}
+
+	label.is_none() && blocks > 0
+}
+
+/// Extracts the label from a `-----BEGIN <LABEL>-----` / `-----END ...` line.
+fn pem_boundary<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+	line
+		.strip_prefix("-----")?
+		.strip_prefix(kind)?
+		.strip_prefix(' ')?
+		.strip_suffix("-----")
+}
+
+/// Checks if a string is an AWS ARN
+/// (`arn:partition:service:region:account-id:resource`).
+///
+/// The partition must be an AWS partition (`aws`, `aws-cn`, `aws-us-gov`,
+/// ...), the account ID empty, 12 digits, or `aws` (AWS-managed resources),
+/// and the resource non-empty. Region may be empty for global services.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::is_aws_arn;
+///
+/// assert!(is_aws_arn("arn:aws:sqs:us-east-1:123456789012:orders"));
+/// assert!(is_aws_arn("arn:aws:s3:::my-bucket/path/*"));
+/// assert!(!is_aws_arn("arn:aws:sqs:us-east-1:1234:orders"));
+/// ```
+#[must_use]
+pub fn is_aws_arn(s: &str) -> bool {
+	arn_service(s).is_some()
+}
+
+/// Returns a validator that checks if a string is an AWS ARN for the given
+/// service, e.g. `arn_of("sqs")`.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::arn_of;
+///
+/// let is_queue = arn_of("sqs");
+/// assert!(is_queue("arn:aws:sqs:us-east-1:123456789012:orders"));
+/// assert!(!is_queue("arn:aws:sns:us-east-1:123456789012:orders"));
+/// ```
+pub fn arn_of(service: &str) -> impl Fn(&str) -> bool {
+	let service = service.to_owned();
+	move |s| arn_service(s) == Some(service.as_str())
+}
+
+/// Validates an ARN and returns its service component.
+fn arn_service(s: &str) -> Option<&str> {
+	let mut parts = s.trim().splitn(6, ':');
+	let (arn, partition, service, region, account, resource) = (
+		parts.next()?,
+		parts.next()?,
+		parts.next()?,
+		parts.next()?,
+		parts.next()?,
+		parts.next()?,
+	);
+
+	let is_slug = |p: &str| {
+		p.bytes()
+			.all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+	};
+	let valid = arn == "arn"
+		&& (partition == "aws" || partition.starts_with("aws-"))
+		&& is_slug(partition)
+		&& !service.is_empty()
+		&& is_slug(service)
+		&& is_slug(region)
+		&& (account.is_empty()
+			|| account == "aws"
+			|| (account.len() == 12 && account.bytes().all(|b| b.is_ascii_digit())))
+		&& !resource.is_empty();
+
+	valid.then_some(service)
+}
+
+/// Checks if a string is a container image reference
+/// (`[registry[:port]/]repo/name[:tag][@digest]`).
+///
+/// Follows the rules used by Docker and OCI registries: repository path
+/// components are lowercase alphanumerics joined by `.`, `_`, `__`, or
+/// dashes; tags are at most 128 word characters, dots, and dashes; digests
+/// are `algorithm:hex` with at least 32 hex digits.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::is_docker_image_ref;
+///
+/// assert!(is_docker_image_ref("nginx"));
+/// assert!(is_docker_image_ref("ghcr.io/org/app:1.2.3"));
+/// assert!(is_docker_image_ref("localhost:5000/app@sha256:0123456789abcdef0123456789abcdef"));
+/// assert!(is_docker_image_ref("[::1]:5000/team/app_v2:v1.0-rc.1"));
+/// assert!(!is_docker_image_ref("Nginx:latest"));
+/// assert!(!is_docker_image_ref("app:"));
+/// assert!(!is_docker_image_ref("org//app"));
+/// assert!(!is_docker_image_ref("app@sha256:abc"));
+/// ```
+#[must_use]
+pub fn is_docker_image_ref(s: &str) -> bool {
+	let (rest, digest) = match s.split_once('@') {
+		Some((rest, digest)) => (rest, Some(digest)),
+		None => (s, None),
+	};
+	if digest.is_some_and(|d| !is_image_digest(d)) {
+		return false;
+	}
+
+	// A tag colon can only appear after the last slash; earlier colons belong
+	// to a registry port.
+	let last_slash = rest.rfind('/').map_or(0, |i| i + 1);
+	let (name, tag) = match rest[last_slash..].rfind(':') {
+		Some(i) => (&rest[..last_slash + i], Some(&rest[last_slash + i + 1..])),
+		None => (rest, None),
+	};
+	if tag.is_some_and(|t| !is_image_tag(t)) {
+		return false;
+	}
+
+	if name.is_empty() || name.len() > 255 {
+		return false;
+	}
+	let mut components = name.split('/').peekable();
+	let first = components.next().unwrap_or_default();
+	let path: Vec<&str> = if components.peek().is_some()
+		&& (first.contains(['.', ':'])
+			|| first == "localhost"
+			|| first.bytes().any(|b| b.is_ascii_uppercase()))
+	{
+		if !is_registry_host(first) {
+			return false;
+		}
+		components.collect()
+	} else {
+		std::iter::once(first).chain(components).collect()
+	};
+
+	path.iter().all(|c| is_image_path_component(c))
+}
+
+/// Checks a registry `host[:port]`.
+fn is_registry_host(s: &str) -> bool {
+	let (host, port) = if let Some(rest) = s.strip_prefix('[') {
+		let Some((ip, after)) = rest.split_once(']') else {
+			return false;
+		};
+		if ip.parse::<std::net::Ipv6Addr>().is_err() {
+			return false;
+		}
+		match after.strip_prefix(':') {
+			Some(port) => ("", Some(port)),
+			None if after.is_empty() => ("", None),
+			None => return false,
+		}
+	} else {
+		match s.split_once(':') {
+			Some((host, port)) => (host, Some(port)),
+			None => (s, None),
+		}
+	};
+
+	if port.is_some_and(|p| p.is_empty() || !p.bytes().all(|b| b.is_ascii_digit())) {
+		return false;
+	}
+	s.starts_with('[')
+		|| (!host.is_empty()
+			&& host.split('.').all(|label| {
+				!label.is_empty()
+					&& !label.starts_with('-')
+					&& !label.ends_with('-')
+					&& label
+						.bytes()
+						.all(|b| b.is_ascii_alphanumeric() || b == b'-')
+			}))
+}
+
+/// Checks one repository path component, e.g. `my-app` or `app_v2`.
+fn is_image_path_component(s: &str) -> bool {
+	let bytes = s.as_bytes();
+	let is_alnum = |b: u8| b.is_ascii_lowercase() || b.is_ascii_digit();
+	if bytes.is_empty() || !is_alnum(bytes[0]) || !is_alnum(bytes[bytes.len() - 1]) {
+		return false;
+	}
+
+	let mut i = 0;
+	while i < bytes.len() {
+		if is_alnum(bytes[i]) {
+			i += 1;
+			continue;
+		}
+		// Separator: `.`, `_`, `__`, or one or more `-`.
+		let sep_end = match bytes[i] {
+			b'_' if bytes.get(i + 1) == Some(&b'_') => i + 2,
+			b'.' | b'_' => i + 1,
+			b'-' => i + bytes[i..].iter().take_while(|b| **b == b'-').count(),
+			_ => return false,
+		};
+		if !bytes.get(sep_end).copied().is_some_and(is_alnum) {
+			return false;
+		}
+		i = sep_end;
+	}
+	true
+}
+
+/// Checks an image tag: `[A-Za-z0-9_][A-Za-z0-9_.-]{0,127}`.
+fn is_image_tag(s: &str) -> bool {
+	let is_word = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+	s.len() <= 128
+		&& s.bytes().next().is_some_and(is_word)
+		&& s.bytes().all(|b| is_word(b) || b == b'.' || b == b'-')
+}
+
+/// Checks an image digest: `algorithm:hex` with at least 32 hex digits.
+fn is_image_digest(s: &str) -> bool {
+	let Some((algorithm, encoded)) = s.split_once(':') else {
+		return false;
+	};
+	let valid_algorithm = algorithm.split(['-', '_', '+', '.']).all(|part| {
+		part.bytes().next().is_some_and(|b| b.is_ascii_alphabetic())
+			&& part.bytes().all(|b| b.is_ascii_alphanumeric())
+	});
+	valid_algorithm && encoded.len() >= 32 && encoded.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Checks if a string is an SSH repository URL: either scp-like
+/// (`git@host:org/repo.git`) or `ssh://[user@]host[:port]/path`.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::is_ssh_url;
+///
+/// assert!(is_ssh_url("git@github.com:org/repo.git"));
+/// assert!(is_ssh_url("ssh://git@git.internal:2222/org/repo.git"));
+/// assert!(!is_ssh_url("https://github.com/org/repo.git"));
+/// ```
+#[must_use]
+pub fn is_ssh_url(s: &str) -> bool {
+	let s = s.trim();
+	if let Some(rest) = s.strip_prefix("ssh://") {
+		return is_url_with_path(rest, true);
+	}
+
+	// scp-like syntax: user@host:path, where the path must not look like a
+	// URL authority (`//`).
+	let Some((user_host, path)) = s.split_once(':') else {
+		return false;
+	};
+	let Some((user, host)) = user_host.split_once('@') else {
+		return false;
+	};
+	is_url_user(user)
+		&& crate::parse::is_dns_name(host)
+		&& is_repo_path(path)
+		&& !path.starts_with('/')
+}
+
+/// Checks if a string is a Git repository URL: any [`is_ssh_url`] form,
+/// `git://host/path`, or `http(s)://host/path.git`.
+///
+/// # Examples
+///
+/// ```rust
+/// use envflag::validators::is_git_url;
+///
+/// assert!(is_git_url("https://github.com/org/repo.git"));
+/// assert!(is_git_url("git@github.com:org/repo.git"));
+/// assert!(is_git_url("git://git.kernel.org/pub/scm/git/git.git"));
+/// assert!(!is_git_url("https://github.com/org/repo"));
+/// ```
+#[must_use]
+pub fn is_git_url(s: &str) -> bool {
+	let s = s.trim();
+	if is_ssh_url(s) {
+		return true;
+	}
+	if let Some(rest) = s.strip_prefix("git://") {
+		return is_url_with_path(rest, false);
+	}
+	let Some(rest) = s
+		.strip_prefix("https://")
+		.or_else(|| s.strip_prefix("http://"))
+	else {
+		return false;
+	};
+	is_url_with_path(rest, true) && rest.trim_end_matches('/').ends_with(".git")
+}
+
+/// Checks `[user@]host[:port]/path` (the part of a URL after the scheme).
+fn is_url_with_path(s: &str, allow_user: bool) -> bool {
+	let Some((authority, path)) = s.split_once('/') else {
+		return false;
+	};
+	let host_port = match authority.rsplit_once('@') {
+		Some((user, host_port)) => {
+			if !allow_user || !is_url_user(user) {
+				return false;
+			}
+			host_port
+		}
+		None => authority,
+	};
+	let host = match host_port.rsplit_once(':') {
+		Some((host, port)) => {
+			if port.parse::<u16>().map_or(true, |p| p == 0) {
+				return false;
+			}
+			host
+		}
+		None => host_port,
+	};
+	crate::parse::is_dns_name(host) && is_repo_path(path)
+}
+
+/// Checks the user part of a URL.
+fn is_url_user(s: &str) -> bool {
+	!s.is_empty() && !s.contains(['/', ':', '@']) && !s.contains(char::is_whitespace)
+}
+
+/// Checks a repository path: non-empty, no whitespace.
+fn is_repo_path(s: &str) -> bool {
+	!s.trim_matches('/').is_empty() && !s.contains(char::is_whitespace)
+}
+
 /// Returns a validator that checks if a string matches a regex pattern.
 ///
 /// # Panics
@@ -116,3 +963,50 @@ pub fn matches_regex(pattern: &str) -> impl Fn(&str) -> bool {
 		.unwrap_or_else(|e| panic!("invalid regex pattern \"{pattern}\": {e}"));
 	move |s| re.is_match(s).unwrap_or(false)
 }
+
+/// Returns a parser that extracts capture group `group` of `pattern`, for
+/// use with [`parse_with`](crate::KeyBuilder::parse_with).
+///
+/// Group 0 is the whole match. A value that does not match, or where the
+/// group did not participate, is an error.
+///
+/// # Panics
+///
+/// Panics immediately if `pattern` is not a valid regex or has no group
+/// `group`, like [`matches_regex`].
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use envflag::store::EnvStore;
+/// use envflag::validators::extract_regex;
+///
+/// let store = EnvStore::from_map(HashMap::from([(
+///     "PLACEMENT".into(),
+///     "region=us-east-1;az=a".into(),
+/// )]));
+/// let region = store
+///     .key("PLACEMENT")
+///     .parse_with(extract_regex(r"region=([^;]+)", 1))
+///     .unwrap();
+/// assert_eq!(region, "us-east-1");
+/// ```
+#[cfg(feature = "regex")]
+pub fn extract_regex(pattern: &str, group: usize) -> impl Fn(&str) -> Result<String, String> {
+	let re = fancy_regex::Regex::new(pattern)
+		.unwrap_or_else(|e| panic!("invalid regex pattern \"{pattern}\": {e}"));
+	assert!(
+		group < re.captures_len(),
+		"regex pattern \"{pattern}\" has no capture group {group}"
+	);
+	let pattern = pattern.to_owned();
+	move |s| {
+		re.captures(s)
+			.ok()
+			.flatten()
+			.and_then(|caps| caps.get(group))
+			.map(|m| m.as_str().to_owned())
+			.ok_or_else(|| format!("does not match {pattern}"))
+	}
+}
@@ -0,0 +1,199 @@
+/* src/vault.rs */
+
+//! Decrypting `.env.vault` files produced by dotenv-vault tooling.
+//!
+//! A vault is a `.env`-syntax file holding one encrypted payload per
+//! environment, e.g. `DOTENV_VAULT_PRODUCTION="..."`. `DOTENV_KEY` selects
+//! and unlocks one of them:
+//!
+//! ```text
+//! dotenv://:key_<64 hex chars>@dotenv.org/vault/.env.vault?environment=production
+//! ```
+//!
+//! Several keys may be given, separated by commas; the first one that
+//! decrypts its payload wins, which allows rotating keys without downtime.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+
+use crate::error::EnvflagError;
+
+/// The variable holding the decryption key(s).
+pub(crate) const KEY_VAR: &str = "DOTENV_KEY";
+
+/// Length of the AES-GCM nonce prepended to each payload.
+const NONCE_LEN: usize = 12;
+
+/// Reads the vault at `path` and decrypts the payload selected by
+/// `dotenv_key`.
+pub(crate) fn load(path: &Path, dotenv_key: &str) -> Result<HashMap<String, String>, EnvflagError> {
+	let error = |message: String| EnvflagError::Source {
+		origin: path.display().to_string(),
+		message,
+	};
+	let payloads = dotenvy::from_path_iter(path)?.collect::<Result<HashMap<_, _>, _>>()?;
+
+	let mut last = None;
+	for uri in dotenv_key
+		.split(',')
+		.map(str::trim)
+		.filter(|k| !k.is_empty())
+	{
+		match decrypt_with(&payloads, uri) {
+			Ok(vars) => return Ok(vars),
+			Err(e) => last = Some(e),
+		}
+	}
+	Err(error(last.unwrap_or_else(|| format!("{KEY_VAR} is empty"))))
+}
+
+/// Decrypts the payload that a single `DOTENV_KEY` URI points at.
+fn decrypt_with(
+	payloads: &HashMap<String, String>,
+	uri: &str,
+) -> Result<HashMap<String, String>, String> {
+	let (key, environment) = parse_key(uri)?;
+	let name = format!("DOTENV_VAULT_{}", environment.to_uppercase());
+	let ciphertext = payloads
+		.get(&name)
+		.ok_or_else(|| format!("no {name} entry for environment '{environment}'"))?;
+	let plaintext = decrypt(&key, ciphertext)?;
+	dotenvy::from_read_iter(plaintext.as_slice())
+		.collect::<Result<_, _>>()
+		.map_err(|e| format!("decrypted {name} is not valid .env syntax: {e}"))
+}
+
+/// Splits a `DOTENV_KEY` URI into the raw AES key and the environment name.
+fn parse_key(uri: &str) -> Result<([u8; 32], String), String> {
+	let invalid = || {
+		format!(
+			"invalid {KEY_VAR}: expected dotenv://:key_...@dotenv.org/vault/.env.vault?environment=..."
+		)
+	};
+	let rest = uri.strip_prefix("dotenv://").ok_or_else(invalid)?;
+	let (userinfo, rest) = rest.split_once('@').ok_or_else(invalid)?;
+	let password = userinfo.rsplit_once(':').map_or(userinfo, |(_, p)| p);
+	let environment = rest
+		.split_once('?')
+		.map(|(_, query)| query)
+		.into_iter()
+		.flat_map(|query| query.split('&'))
+		.find_map(|pair| pair.strip_prefix("environment="))
+		.filter(|e| !e.is_empty())
+		.ok_or_else(|| format!("{KEY_VAR} is missing the environment parameter"))?;
+
+	// The key is the trailing 64 hex characters, after the `key_` prefix.
+	let key: [u8; 32] = password
+		.len()
+		.checked_sub(64)
+		.and_then(|start| password.get(start..))
+		.and_then(crate::parse::decode_hex)
+		.and_then(|bytes| bytes.try_into().ok())
+		.ok_or_else(|| format!("{KEY_VAR} key must end in 64 hex characters"))?;
+	Ok((key, environment.to_owned()))
+}
+
+/// Decrypts a base64 payload laid out as nonce, ciphertext, then tag.
+fn decrypt(key: &[u8; 32], payload: &str) -> Result<Vec<u8>, String> {
+	let bytes = STANDARD
+		.decode(payload.trim())
+		.map_err(|e| format!("payload is not valid base64: {e}"))?;
+	if bytes.len() < NONCE_LEN {
+		return Err("payload is too short".to_owned());
+	}
+	let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+	Aes256Gcm::new(key.into())
+		.decrypt(Nonce::from_slice(nonce), ciphertext)
+		.map_err(|_| "decryption failed: wrong key or corrupted payload".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const HEX: &str = "e31910a1c51a4ba1ba31ad4bfa2a71c4b7a6a8e7b0f4c1f1d7e2a3b4c5d6e7f8";
+
+	fn encrypt(plaintext: &str) -> String {
+		let mut key = [0; 32];
+		for (byte, pair) in key.iter_mut().zip(HEX.as_bytes().chunks(2)) {
+			*byte = u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap();
+		}
+		let nonce = [7; NONCE_LEN];
+		let mut bytes = nonce.to_vec();
+		bytes.extend(
+			Aes256Gcm::new(&key.into())
+				.encrypt(Nonce::from_slice(&nonce), plaintext.as_bytes())
+				.unwrap(),
+		);
+		STANDARD.encode(bytes)
+	}
+
+	fn vault(name: &str, contents: &str) -> std::path::PathBuf {
+		let path = std::env::temp_dir().join(format!(
+			"envflag-vault-{}-{name}.env.vault",
+			std::process::id()
+		));
+		std::fs::write(&path, contents).unwrap();
+		path
+	}
+
+	fn key(environment: &str) -> String {
+		format!("dotenv://:key_{HEX}@dotenv.org/vault/.env.vault?environment={environment}")
+	}
+
+	#[test]
+	fn decrypts_selected_environment() {
+		let path = vault(
+			"select",
+			&format!(
+				"DOTENV_VAULT_DEVELOPMENT=\"{}\"\nDOTENV_VAULT_PRODUCTION=\"{}\"\n",
+				encrypt("PORT=3000\n"),
+				encrypt("PORT=80\nSECRET=\"a b\"\n"),
+			),
+		);
+		let vars = load(&path, &key("production")).unwrap();
+		std::fs::remove_file(&path).unwrap();
+		assert_eq!(vars.get("PORT").map(String::as_str), Some("80"));
+		assert_eq!(vars.get("SECRET").map(String::as_str), Some("a b"));
+	}
+
+	#[test]
+	fn falls_through_comma_separated_keys() {
+		let path = vault(
+			"fallthrough",
+			&format!("DOTENV_VAULT_CI=\"{}\"\n", encrypt("A=1\n")),
+		);
+		let keys = format!("{},{}", key("staging"), key("ci"));
+		let vars = load(&path, &keys).unwrap();
+		std::fs::remove_file(&path).unwrap();
+		assert_eq!(vars.get("A").map(String::as_str), Some("1"));
+	}
+
+	#[test]
+	fn rejects_wrong_key() {
+		let path = vault(
+			"wrong",
+			&format!("DOTENV_VAULT_CI=\"{}\"\n", encrypt("A=1\n")),
+		);
+		let wrong = key("ci").replace("key_e3", "key_f3");
+		let err = load(&path, &wrong).unwrap_err().to_string();
+		std::fs::remove_file(&path).unwrap();
+		assert!(err.contains("decryption failed"), "{err}");
+	}
+
+	#[test]
+	fn rejects_malformed_keys() {
+		assert!(parse_key("https://example.com").is_err());
+		assert!(parse_key(&format!("dotenv://:key_{HEX}@dotenv.org/vault/.env.vault")).is_err());
+		assert!(parse_key("dotenv://:key_abc@dotenv.org/vault?environment=ci").is_err());
+		let signed = key("ci").replace(&format!("key_{}", &HEX[..2]), "key_+f");
+		assert!(parse_key(&signed).is_err());
+		let (_, environment) = parse_key(&key("ci")).unwrap();
+		assert_eq!(environment, "ci");
+	}
+}
@@ -0,0 +1,201 @@
+/* src/warn.rs */
+
+//! Deduplication of per-key warnings.
+//!
+//! Parse and validation failures are logged (with the `tracing` feature) at
+//! the point of access, which for a hot key can be every request. Warnings
+//! are therefore limited per key and kind of failure according to the
+//! [`WarnPolicy`] set with [`set_warn_policy`].
+//!
+//! Reads through a [deprecated alias](crate::KeyBuilder::deprecated_alias)
+//! are also recorded here, so they can be reported once at shutdown via
+//! [`deprecated_usage`].
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+/// How often the same warning may be emitted for one key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WarnPolicy {
+	/// Emit every warning.
+	Always,
+	/// Emit a warning once per key until the next reload.
+	Once,
+	/// Emit at most one warning per key within each interval.
+	Interval(Duration),
+}
+
+impl Default for WarnPolicy {
+	fn default() -> Self {
+		DEFAULT_POLICY
+	}
+}
+
+const DEFAULT_POLICY: WarnPolicy = WarnPolicy::Interval(Duration::from_secs(60));
+
+static POLICY: RwLock<WarnPolicy> = RwLock::new(DEFAULT_POLICY);
+
+/// Last emission per (key, kind of failure).
+static EMITTED: Mutex<Option<HashMap<(String, &'static str), Emitted>>> = Mutex::new(None);
+
+/// Deprecated aliases that supplied a value, mapped to their replacement.
+static DEPRECATED: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug)]
+struct Emitted {
+	at: Instant,
+	suppressed: u64,
+}
+
+/// Sets how often repeated warnings for the same key are emitted.
+///
+/// The default is [`WarnPolicy::Interval`] of one minute. Changing the policy
+/// forgets which warnings were already emitted.
+pub fn set_warn_policy(policy: WarnPolicy) {
+	*POLICY.write().unwrap_or_else(PoisonError::into_inner) = policy;
+	reset();
+}
+
+/// Forgets which warnings were emitted, e.g. after a reload brought in new
+/// values.
+pub(crate) fn reset() {
+	*EMITTED.lock().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Decides whether a `kind` warning for `key` should be emitted now.
+///
+/// Returns the number of identical warnings suppressed since the last one
+/// emitted, or `None` to suppress this one.
+#[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+pub(crate) fn admit(key: &str, kind: &'static str) -> Option<u64> {
+	let policy = *POLICY.read().unwrap_or_else(PoisonError::into_inner);
+	if policy == WarnPolicy::Always {
+		return Some(0);
+	}
+
+	let now = Instant::now();
+	let mut emitted = EMITTED.lock().unwrap_or_else(PoisonError::into_inner);
+	let emitted = emitted.get_or_insert_with(HashMap::new);
+	let Some(last) = emitted.get_mut(&(key.to_owned(), kind)) else {
+		emitted.insert(
+			(key.to_owned(), kind),
+			Emitted {
+				at: now,
+				suppressed: 0,
+			},
+		);
+		return Some(0);
+	};
+
+	let due = match policy {
+		WarnPolicy::Interval(interval) => now.duration_since(last.at) >= interval,
+		WarnPolicy::Always | WarnPolicy::Once => false,
+	};
+	if due {
+		let suppressed = last.suppressed;
+		*last = Emitted {
+			at: now,
+			suppressed: 0,
+		};
+		Some(suppressed)
+	} else {
+		last.suppressed += 1;
+		None
+	}
+}
+
+/// Forgets which deprecated aliases were used.
+#[cfg(feature = "test-util")]
+pub(crate) fn clear_deprecated() {
+	DEPRECATED
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.clear();
+}
+
+/// Records that the deprecated `alias` supplied the value for `key`, and
+/// warns operators to rename it.
+pub(crate) fn deprecated(alias: &str, key: &str) {
+	DEPRECATED
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.insert(alias.to_owned(), key.to_owned());
+
+	#[cfg(feature = "tracing")]
+	if let Some(suppressed) = admit(alias, "deprecated") {
+		tracing::warn!(
+			alias = %alias,
+			key = %key,
+			suppressed,
+			"deprecated environment variable is set; rename it"
+		);
+	}
+}
+
+/// Returns every deprecated alias read since startup, with the name that
+/// replaces it, sorted by alias.
+///
+/// # Examples
+///
+/// ```rust
+/// let store = envflag::store::EnvStore::from_map(
+///     [("OLD_PORT".to_owned(), "80".to_owned())].into(),
+/// );
+/// let port: u16 = store.key("PORT").deprecated_alias("OLD_PORT").required().unwrap();
+/// assert_eq!(port, 80);
+/// assert_eq!(
+///     envflag::deprecated_usage(),
+///     [("OLD_PORT".to_owned(), "PORT".to_owned())]
+/// );
+/// ```
+#[must_use]
+pub fn deprecated_usage() -> Vec<(String, String)> {
+	DEPRECATED
+		.lock()
+		.unwrap_or_else(PoisonError::into_inner)
+		.iter()
+		.map(|(alias, key)| (alias.clone(), key.clone()))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// The policy is global, so every case runs in one test.
+	#[test]
+	fn policies() {
+		set_warn_policy(WarnPolicy::Once);
+		assert_eq!(admit("WARN_A", "parse"), Some(0));
+		assert_eq!(admit("WARN_A", "parse"), None);
+		assert_eq!(admit("WARN_A", "validate"), Some(0));
+		assert_eq!(admit("WARN_B", "parse"), Some(0));
+		reset();
+		assert_eq!(admit("WARN_A", "parse"), Some(0));
+
+		set_warn_policy(WarnPolicy::Interval(Duration::ZERO));
+		assert_eq!(admit("WARN_A", "parse"), Some(0));
+		assert_eq!(admit("WARN_A", "parse"), Some(0));
+
+		set_warn_policy(WarnPolicy::Interval(Duration::from_secs(3600)));
+		assert_eq!(admit("WARN_A", "parse"), Some(0));
+		assert_eq!(admit("WARN_A", "parse"), None);
+		assert_eq!(admit("WARN_A", "parse"), None);
+		let mut emitted = EMITTED.lock().unwrap();
+		let entry = emitted
+			.as_mut()
+			.unwrap()
+			.get_mut(&("WARN_A".to_owned(), "parse"))
+			.unwrap();
+		entry.at -= Duration::from_secs(3600);
+		drop(emitted);
+		assert_eq!(admit("WARN_A", "parse"), Some(2));
+
+		set_warn_policy(WarnPolicy::Always);
+		assert_eq!(admit("WARN_A", "parse"), Some(0));
+		assert_eq!(admit("WARN_A", "parse"), Some(0));
+		set_warn_policy(WarnPolicy::default());
+	}
+}
@@ -0,0 +1,112 @@
+/* src/watch.rs */
+
+//! Reloading the global store when a `.env` file changes on disk.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::error::EnvflagError;
+use crate::reload;
+use crate::store::InitBuilder;
+
+/// How long to wait for further events before reloading, so an editor's
+/// write-rename-chmod sequence triggers a single reload.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// How often an idle watcher checks whether it is still needed.
+const POLL: Duration = Duration::from_secs(1);
+
+/// A file watcher created before the store is installed, so that failing to
+/// set it up fails `init()` instead of silently disabling hot reload.
+pub(crate) struct FileWatcher {
+	// Dropping the watcher stops event delivery.
+	_watcher: RecommendedWatcher,
+	events: Receiver<notify::Result<Event>>,
+	files: Vec<PathBuf>,
+}
+
+impl FileWatcher {
+	/// Starts watching `files`.
+	///
+	/// The parent directories are watched rather than the files themselves,
+	/// so editors and deploy tools that replace a file by renaming over it
+	/// are picked up too.
+	pub(crate) fn new(files: &[PathBuf]) -> Result<Self, EnvflagError> {
+		let (tx, events) = mpsc::channel();
+		let error = |path: &Path, e: notify::Error| EnvflagError::Source {
+			origin: path.display().to_string(),
+			message: format!("cannot watch for changes: {e}"),
+		};
+		let mut watcher = notify::recommended_watcher(tx).map_err(|e| error(Path::new("."), e))?;
+
+		let files = files
+			.iter()
+			.map(|f| f.canonicalize())
+			.collect::<Result<Vec<_>, _>>()?;
+		let mut dirs: Vec<&Path> = files.iter().filter_map(|f| f.parent()).collect();
+		dirs.sort();
+		dirs.dedup();
+		for dir in dirs {
+			watcher
+				.watch(dir, RecursiveMode::NonRecursive)
+				.map_err(|e| error(dir, e))?;
+		}
+
+		Ok(Self {
+			_watcher: watcher,
+			events,
+			files,
+		})
+	}
+
+	/// Moves the watcher to a background thread that reloads the global store
+	/// on every change.
+	///
+	/// The thread exits once `builder` is no longer the one backing the
+	/// global instance.
+	pub(crate) fn spawn(self, builder: Arc<InitBuilder>) -> Result<(), EnvflagError> {
+		thread::Builder::new()
+			.name("envflag-watch".to_owned())
+			.spawn(move || self.run(&builder))?;
+		Ok(())
+	}
+
+	fn run(&self, builder: &Arc<InitBuilder>) {
+		loop {
+			match self.events.recv_timeout(POLL) {
+				Ok(event) if self.is_relevant(&event) => {
+					// Let the burst settle, then reload once.
+					while self.events.recv_timeout(DEBOUNCE).is_ok() {}
+					if !reload::rebuild(builder) {
+						return;
+					}
+				}
+				Ok(_) => {}
+				Err(RecvTimeoutError::Timeout) => {
+					if !reload::is_current(builder) {
+						return;
+					}
+				}
+				Err(RecvTimeoutError::Disconnected) => return,
+			}
+		}
+	}
+
+	/// Returns `true` if `event` changed one of the watched files.
+	///
+	/// Access events are ignored: the reload itself reads the files.
+	fn is_relevant(&self, event: &notify::Result<Event>) -> bool {
+		let Ok(event) = event else {
+			return false;
+		};
+		matches!(
+			event.kind,
+			EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) | EventKind::Any
+		) && event.paths.iter().any(|p| self.files.contains(p))
+	}
+}
@@ -0,0 +1,183 @@
+//! `#[derive(EnvConfig)]` against isolated stores.
+
+#![cfg(feature = "derive")]
+
+use std::collections::HashMap;
+
+use envflag::store::EnvStore;
+use envflag::{EnvConfig, EnvflagError};
+
+fn make_store(pairs: &[(&str, &str)]) -> EnvStore {
+	EnvStore::from_map(
+		pairs
+			.iter()
+			.map(|(k, v)| ((*k).into(), (*v).into()))
+			.collect(),
+	)
+}
+
+#[derive(Debug, EnvConfig)]
+struct DbConfig {
+	/// Database host name.
+	host: String,
+	#[env(default = "5432")]
+	port: u16,
+}
+
+#[derive(Debug, EnvConfig)]
+struct AppConfig {
+	port: u16,
+	#[env(name = "LISTEN_HOST", default = "localhost")]
+	host: String,
+	debug: Option<bool>,
+	timeout: Option<u64>,
+	hosts: Vec<String>,
+	upstreams: Vec<String>,
+	#[env(prefix = "DB_")]
+	db: DbConfig,
+}
+
+#[test]
+fn derive_loads_all_shapes() {
+	let store = make_store(&[
+		("PORT", "8080"),
+		("DEBUG", "yes"),
+		("HOSTS", "a, b,c"),
+		("UPSTREAMS_0", "x"),
+		("UPSTREAMS_1", "y"),
+		("UPSTREAMS_3", "skipped"),
+		("DB_HOST", "db.internal"),
+	]);
+
+	let cfg = AppConfig::from_store(&store).unwrap();
+	assert_eq!(cfg.port, 8080);
+	assert_eq!(cfg.host, "localhost");
+	assert_eq!(cfg.debug, Some(true));
+	assert_eq!(cfg.timeout, None);
+	assert_eq!(cfg.hosts, ["a", "b", "c"]);
+	assert_eq!(cfg.upstreams, ["x", "y"]);
+	assert_eq!(cfg.db.host, "db.internal");
+	assert_eq!(cfg.db.port, 5432);
+}
+
+#[test]
+fn derive_missing_required_field() {
+	let store = make_store(&[("DB_HOST", "db")]);
+	let err = AppConfig::from_store(&store).unwrap_err();
+	assert!(matches!(err, EnvflagError::NotSet { key, .. } if key == "PORT"));
+}
+
+#[test]
+fn derive_optional_garbage_is_an_error() {
+	let store = make_store(&[("PORT", "1"), ("TIMEOUT", "soon"), ("DB_HOST", "db")]);
+	let err = AppConfig::from_store(&store).unwrap_err();
+	assert!(matches!(err, EnvflagError::ParseFailed { key, .. } if key == "TIMEOUT"));
+}
+
+#[test]
+fn derive_honors_store_prefix() {
+	let store = EnvStore::from_map_with_prefixes(
+		HashMap::from([
+			("APP_DB_HOST".into(), "db".into()),
+			("APP_DB_PORT".into(), "6543".into()),
+		]),
+		vec!["APP_".into()],
+	);
+	let db = <DbConfig as EnvConfig>::from_store_prefixed(&store, "DB_").unwrap();
+	assert_eq!(db.port, 6543);
+}
+
+#[test]
+fn derive_redacts_values_in_errors() {
+	envflag::builder()
+		.prefix("DERIVE_REDACT_")
+		.redact_values_in_errors(true)
+		.args_overrides([
+			"-e",
+			"DERIVE_REDACT_PORT=1",
+			"-e",
+			"DERIVE_REDACT_DB_HOST=db",
+			"-e",
+			"DERIVE_REDACT_DB_PORT=hunter3",
+		])
+		.init()
+		.unwrap();
+	let err = AppConfig::from_env().unwrap_err();
+	assert!(
+		matches!(err, EnvflagError::ParseFailed { ref key, .. } if key == "DB_PORT"),
+		"{err}"
+	);
+	assert!(!err.to_string().contains("hunter3"), "{err}");
+}
+
+#[derive(Debug, EnvConfig)]
+struct Defaulted {
+	#[env(default = "4")]
+	workers: u8,
+}
+
+#[test]
+fn derive_default_needs_unambiguous_prefix() {
+	let store = EnvStore::from_map_with_prefixes(
+		HashMap::from([("A_WORKERS".into(), "8".into())]),
+		vec!["A_".into(), "B_".into()],
+	);
+	let err = Defaulted::from_store(&store).unwrap_err();
+	assert!(matches!(err, EnvflagError::AmbiguousPrefix { .. }), "{err}");
+	assert_eq!(Defaulted::from_store(&make_store(&[])).unwrap().workers, 4);
+}
+
+#[test]
+fn derive_emits_schema() {
+	let schema = AppConfig::schema();
+	let names: Vec<_> = schema.keys().iter().map(|k| k.name.as_str()).collect();
+	assert_eq!(
+		names,
+		[
+			"PORT",
+			"LISTEN_HOST",
+			"DEBUG",
+			"TIMEOUT",
+			"HOSTS",
+			"UPSTREAMS",
+			"DB_HOST",
+			"DB_PORT"
+		]
+	);
+
+	let port = schema.get("PORT").unwrap();
+	assert_eq!(port.type_name, "u16");
+	assert!(port.required);
+
+	let debug = schema.get("DEBUG").unwrap();
+	assert_eq!(debug.type_name, "bool");
+	assert!(!debug.required);
+
+	assert_eq!(schema.get("HOSTS").unwrap().type_name, "Vec<String>");
+
+	let db_host = schema.get("DB_HOST").unwrap();
+	assert_eq!(db_host.description.as_deref(), Some("Database host name."));
+
+	let db_port = schema.get("DB_PORT").unwrap();
+	assert_eq!(db_port.default.as_deref(), Some("5432"));
+	assert!(!db_port.required);
+}
+
+#[test]
+fn static_schema_matches_runtime_parse() {
+	let schema = envflag::static_schema!("tests/fixtures/schema.toml");
+	let names: Vec<_> = schema.keys().iter().map(|k| k.name.as_str()).collect();
+	assert_eq!(names, ["PORT", "DATABASE_URL"]);
+
+	let port = schema.get("PORT").unwrap();
+	assert_eq!(port.type_name, "u16");
+	assert_eq!(port.default.as_deref(), Some("8080"));
+	assert_eq!(port.validators, ["is_port"]);
+	assert!(schema.get("DATABASE_URL").unwrap().required);
+
+	#[cfg(feature = "toml")]
+	assert_eq!(
+		schema,
+		envflag::Schema::from_toml(include_str!("fixtures/schema.toml")).unwrap()
+	);
+}
@@ -0,0 +1,17 @@
+//! `init` exports the `.env` file to the process environment. Lives in its
+//! own test binary so it can call `init` without affecting other tests.
+
+#[test]
+fn init_exports_dotenv_without_overwriting() {
+	let path = std::env::temp_dir().join(format!("envflag-export-{}.env", std::process::id()));
+	std::fs::write(&path, "EXPORT_NEW=file\nEXPORT_KEPT=file\n").unwrap();
+
+	temp_env::with_var("EXPORT_KEPT", Some("process"), || {
+		envflag::builder().path(&path).init().unwrap();
+		assert_eq!(std::env::var("EXPORT_NEW").as_deref(), Ok("file"));
+		assert_eq!(std::env::var("EXPORT_KEPT").as_deref(), Ok("process"));
+		assert_eq!(envflag::get_string("EXPORT_KEPT", ""), "process");
+	});
+
+	std::fs::remove_file(&path).unwrap();
+}
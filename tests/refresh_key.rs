@@ -0,0 +1,38 @@
+//! Per-key TTL refresh. Lives in its own test binary so it can call `init`
+//! without affecting other tests.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+use envflag::LayerKind;
+
+#[test]
+fn refresh_key_refetches_on_expiry() {
+	let calls = Arc::new(AtomicU32::new(0));
+	let counter = Arc::clone(&calls);
+
+	envflag::builder()
+		.prefix("REFRESH_")
+		.refresh_key("REFRESH_TOKEN", Duration::from_millis(20), move |key| {
+			assert_eq!(key, "REFRESH_TOKEN");
+			let n = counter.fetch_add(1, Ordering::SeqCst);
+			Ok(Some(format!("token-{n}")))
+		})
+		.init()
+		.unwrap();
+
+	assert_eq!(envflag::get_string("TOKEN", ""), "token-0");
+	assert!(
+		envflag::layers()
+			.iter()
+			.any(|l| l.kind == LayerKind::Remote && l.keys == 1)
+	);
+
+	let deadline = Instant::now() + Duration::from_secs(5);
+	while envflag::get_string("TOKEN", "") == "token-0" {
+		assert!(Instant::now() < deadline, "key was never refreshed");
+		std::thread::sleep(Duration::from_millis(5));
+	}
+	assert!(calls.load(Ordering::SeqCst) >= 2);
+}
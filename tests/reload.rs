@@ -0,0 +1,47 @@
+//! Reloading the global store. Lives in its own test binary so it can call
+//! `init` without affecting other tests.
+
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn reload_reports_diff_to_subscribers() {
+	let path = std::env::temp_dir().join(format!("envflag-reload-{}.env", std::process::id()));
+	std::fs::write(&path, "RELOAD_A=1\nRELOAD_B=2\n").unwrap();
+
+	envflag::builder()
+		.path(&path)
+		.prefix("RELOAD_")
+		.init()
+		.unwrap();
+	assert_eq!(envflag::get("A", 0u8), 1);
+
+	let seen = Arc::new(Mutex::new(Vec::new()));
+	let sink = Arc::clone(&seen);
+	envflag::on_reload(move |diff| sink.lock().unwrap().push(diff.clone()));
+
+	std::fs::write(&path, "RELOAD_A=10\nRELOAD_C=3\n").unwrap();
+	let diff = envflag::reload().unwrap();
+
+	assert_eq!(diff.added, vec!["RELOAD_C".to_owned()]);
+	assert_eq!(diff.removed, vec!["RELOAD_B".to_owned()]);
+	assert_eq!(diff.changed, vec!["RELOAD_A".to_owned()]);
+	assert_eq!(*seen.lock().unwrap(), vec![diff]);
+	assert_eq!(envflag::get("A", 0u8), 10);
+
+	// Callbacks may register others; unsubscribed ones stop running.
+	let nested = Arc::new(Mutex::new(0));
+	let count = Arc::clone(&nested);
+	let outer = envflag::on_reload(move |_| {
+		let count = Arc::clone(&count);
+		envflag::on_reload(move |_| *count.lock().unwrap() += 1);
+	});
+	envflag::reload().unwrap();
+	outer.unsubscribe();
+	envflag::reload().unwrap();
+	std::fs::remove_file(&path).unwrap();
+	assert_eq!(*nested.lock().unwrap(), 1);
+	// Read counts carry over to the reloaded store.
+	#[cfg(feature = "usage")]
+	assert_eq!(envflag::usage_report(), [("RELOAD_A".to_owned(), 2)]);
+	assert_eq!(seen.lock().unwrap().len(), 3);
+}
@@ -0,0 +1,71 @@
+//! Resetting the global between tests, so several tests of the global API
+//! can share this binary. The tests hold a lock because the global is still
+//! shared between threads.
+
+#![cfg(feature = "test-util")]
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+static SERIAL: Mutex<()> = Mutex::new(());
+
+/// Serializes the tests and starts each one uninitialized.
+fn fresh() -> MutexGuard<'static, ()> {
+	let guard = SERIAL.lock().unwrap_or_else(PoisonError::into_inner);
+	envflag::reset_for_tests();
+	guard
+}
+
+#[test]
+fn init_after_reset() {
+	let _guard = fresh();
+	envflag::builder()
+		.args_overrides(["-e", "RESET_MODE=first"])
+		.init()
+		.unwrap();
+	assert_eq!(envflag::get_string("RESET_MODE", ""), "first");
+	assert!(matches!(
+		envflag::init(),
+		Err(envflag::EnvflagError::AlreadyInitialized)
+	));
+
+	envflag::reset_for_tests();
+	assert!(!envflag::is_initialized());
+
+	envflag::builder()
+		.args_overrides(["-e", "RESET_MODE=second"])
+		.init()
+		.unwrap();
+	assert_eq!(envflag::get_string("RESET_MODE", ""), "second");
+}
+
+#[cfg(feature = "usage")]
+#[test]
+fn reset_forgets_usage() {
+	let _guard = fresh();
+	envflag::builder()
+		.args_overrides(["-e", "RESET_USED=1", "-e", "RESET_UNUSED=2"])
+		.init()
+		.unwrap();
+	assert_eq!(envflag::get("RESET_USED", 0u8), 1);
+	assert_eq!(envflag::get("RESET_MISSING", 0u8), 0);
+	// Reads and defaults of other stores are theirs alone.
+	let other =
+		envflag::store::EnvStore::from_map([("RESET_UNUSED".to_owned(), "3".to_owned())].into());
+	assert_eq!(other.get("RESET_UNUSED", 0u8), 3);
+	assert_eq!(other.get("RESET_OTHER", 0u8), 0);
+	let unused = envflag::unused_keys();
+	assert!(unused.iter().any(|k| k == "RESET_UNUSED"));
+	assert!(!unused.iter().any(|k| k == "RESET_USED"));
+	assert_eq!(
+		envflag::defaulted_keys(),
+		[("RESET_MISSING".to_owned(), envflag::DefaultReason::Missing)]
+	);
+
+	envflag::reset_for_tests();
+	envflag::builder()
+		.args_overrides(["-e", "RESET_USED=1"])
+		.init()
+		.unwrap();
+	assert!(envflag::usage_report().is_empty());
+	assert!(envflag::defaulted_keys().is_empty());
+}
@@ -0,0 +1,165 @@
+//! `EnvStore::deserialize` against isolated stores.
+
+#![cfg(feature = "serde")]
+
+use std::collections::{BTreeMap, HashMap};
+
+use envflag::EnvflagError;
+use envflag::store::EnvStore;
+use serde::Deserialize;
+
+fn pairs(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+	pairs
+		.iter()
+		.map(|(k, v)| ((*k).into(), (*v).into()))
+		.collect()
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Mode {
+	Dev,
+	Prod,
+}
+
+#[derive(Debug, Deserialize)]
+struct Config {
+	port: u16,
+	debug: bool,
+	mode: Mode,
+	hosts: Vec<String>,
+	timeout: Option<u64>,
+	#[serde(default = "default_workers")]
+	workers: usize,
+}
+
+fn default_workers() -> usize {
+	4
+}
+
+#[test]
+fn deserializes_struct_fields() {
+	let store = EnvStore::from_map(pairs(&[
+		("PORT", "8080"),
+		("DEBUG", "yes"),
+		("MODE", "prod"),
+		("HOSTS", "a, b,c"),
+	]));
+	let cfg: Config = store.deserialize().unwrap();
+	assert_eq!(cfg.port, 8080);
+	assert!(cfg.debug);
+	assert_eq!(cfg.mode, Mode::Prod);
+	assert_eq!(cfg.hosts, ["a", "b", "c"]);
+	assert_eq!(cfg.timeout, None);
+	assert_eq!(cfg.workers, 4);
+}
+
+#[test]
+fn honors_prefix_chain() {
+	let store = EnvStore::from_map_with_prefix_chain(
+		pairs(&[
+			("APP_PORT", "80"),
+			("MYAPP_PORT", "8080"),
+			("APP_DEBUG", "false"),
+			("APP_MODE", "dev"),
+			("APP_HOSTS", "h"),
+			("OTHER", "x"),
+		]),
+		vec!["MYAPP_".into(), "APP_".into()],
+	);
+	let cfg: Config = store.deserialize().unwrap();
+	assert_eq!(cfg.port, 8080);
+	assert_eq!(cfg.mode, Mode::Dev);
+
+	let map: BTreeMap<String, String> = store.deserialize().unwrap();
+	assert_eq!(map.get("port").map(String::as_str), Some("8080"));
+	assert!(!map.contains_key("other"));
+}
+
+#[test]
+fn reports_missing_and_invalid_keys() {
+	let store = EnvStore::from_map(pairs(&[("DEBUG", "1"), ("MODE", "dev"), ("HOSTS", "")]));
+	let err = store.deserialize::<Config>().unwrap_err();
+	assert!(
+		matches!(err, EnvflagError::NotSet { ref key, .. } if key == "PORT"),
+		"{err}"
+	);
+
+	let store = EnvStore::from_map(pairs(&[
+		("PORT", "http"),
+		("DEBUG", "1"),
+		("MODE", "dev"),
+		("HOSTS", ""),
+	]));
+	let err = store.deserialize::<Config>().unwrap_err();
+	assert!(
+		matches!(err, EnvflagError::ParseFailed { ref key, ref value, .. } if key == "PORT" && value == "http"),
+		"{err}"
+	);
+
+	let store = EnvStore::from_map(pairs(&[
+		("PORT", "1"),
+		("DEBUG", "1"),
+		("MODE", "staging"),
+		("HOSTS", ""),
+	]));
+	let err = store.deserialize::<Config>().unwrap_err();
+	assert!(matches!(err, EnvflagError::Deserialize { .. }), "{err}");
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+struct DbConfig {
+	host: String,
+	port: u16,
+}
+
+#[test]
+fn nested_reads_sub_prefix() {
+	let store = EnvStore::from_map_with_prefixes(
+		pairs(&[
+			("APP_DB_HOST", "db.internal"),
+			("APP_DB_PORT", "5432"),
+			("APP_PORT", "8080"),
+		]),
+		vec!["APP_".into()],
+	);
+	let db: DbConfig = store.nested("DB_").unwrap();
+	assert_eq!(
+		db,
+		DbConfig {
+			host: "db.internal".into(),
+			port: 5432,
+		}
+	);
+	let all: BTreeMap<String, String> = store.nested("db_").unwrap();
+	assert_eq!(all.keys().collect::<Vec<_>>(), ["host", "port"]);
+
+	let err = store.nested::<DbConfig>("CACHE_").unwrap_err();
+	assert!(matches!(err, EnvflagError::NotSet { key, .. } if key == "CACHE_HOST"));
+}
+
+#[test]
+fn nested_errors_name_the_full_variable() {
+	let store = EnvStore::from_map_with_prefixes(
+		pairs(&[("APP_DB_HOST", "db.internal"), ("APP_DB_PORT", "http")]),
+		vec!["APP_".into()],
+	);
+	let err = store.nested::<DbConfig>("DB_").unwrap_err();
+	assert!(
+		matches!(err, EnvflagError::ParseFailed { ref key, .. } if key == "APP_DB_PORT"),
+		"{err}"
+	);
+
+	let err = store.nested::<DbConfig>("CACHE_").unwrap_err();
+	assert!(
+		matches!(err, EnvflagError::NotSet { ref key, ref tried, .. } if key == "CACHE_HOST" && tried == &["APP_CACHE_HOST"]),
+		"{err}"
+	);
+	assert!(err.to_string().contains("APP_CACHE_HOST"), "{err}");
+
+	let map_err = store.nested::<BTreeMap<String, u16>>("DB_").unwrap_err();
+	assert!(
+		matches!(map_err, EnvflagError::ParseFailed { ref key, .. } if key == "APP_DB_HOST"),
+		"{map_err}"
+	);
+}
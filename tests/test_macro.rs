@@ -0,0 +1,40 @@
+//! `#[envflag::test]` runs each test against its own store, so these tests
+//! use the global API in parallel without calling `init`.
+
+#![cfg(feature = "derive")]
+
+use envflag::EnvflagError;
+
+#[envflag::test(vars(PORT = "8080", DEBUG = "true"))]
+fn sees_its_variables() {
+	assert_eq!(envflag::get("PORT", 0u16), 8080);
+	assert!(envflag::get("DEBUG", false));
+	assert!(!envflag::is_set("HOST"));
+}
+
+#[envflag::test(vars(PORT = "9090"))]
+fn is_isolated_from_other_tests() {
+	assert_eq!(envflag::get("PORT", 0u16), 9090);
+	assert!(!envflag::is_set("DEBUG"));
+}
+
+#[envflag::test(vars(PORT = "not a port"))]
+fn supports_result_and_question_mark() -> Result<(), EnvflagError> {
+	let err = envflag::key("PORT").required::<u16>().unwrap_err();
+	assert!(matches!(err, EnvflagError::ParseFailed { .. }), "{err}");
+	let port = envflag::key("ADMIN_PORT").default(80u16).get()?;
+	assert_eq!(port, 80);
+	Ok(())
+}
+
+#[envflag::test]
+fn without_vars_sees_an_empty_store() {
+	assert!(!envflag::is_set("PORT"));
+}
+
+#[envflag::test(vars(PORT = "1"))]
+#[should_panic(expected = "boom")]
+fn keeps_other_attributes() {
+	assert_eq!(envflag::get("PORT", 0u8), 1);
+	panic!("boom");
+}
@@ -0,0 +1,38 @@
+//! Hot reload on `.env` changes. Lives in its own test binary so it can call
+//! `init` without affecting other tests.
+
+#![cfg(feature = "watch")]
+
+use std::sync::mpsc;
+use std::time::Duration;
+
+#[test]
+fn watch_reloads_on_file_change() {
+	let dir = std::env::temp_dir().join(format!("envflag-watch-{}", std::process::id()));
+	std::fs::create_dir_all(&dir).unwrap();
+	let path = dir.join(".env");
+	std::fs::write(&path, "WATCH_A=1\n").unwrap();
+
+	envflag::builder()
+		.path(&path)
+		.prefix("WATCH_")
+		.watch()
+		.init()
+		.unwrap();
+	assert_eq!(envflag::get("A", 0u8), 1);
+
+	let (tx, rx) = mpsc::channel();
+	envflag::on_reload(move |diff| tx.send(diff.clone()).unwrap());
+
+	// Replace the file the way editors do: write a sibling, rename over it.
+	let tmp = dir.join(".env.tmp");
+	std::fs::write(&tmp, "WATCH_A=2\nWATCH_B=3\n").unwrap();
+	std::fs::rename(&tmp, &path).unwrap();
+
+	let diff = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+	std::fs::remove_dir_all(&dir).unwrap();
+
+	assert_eq!(diff.added, vec!["WATCH_B".to_owned()]);
+	assert_eq!(diff.changed, vec!["WATCH_A".to_owned()]);
+	assert_eq!(envflag::get("A", 0u8), 2);
+}
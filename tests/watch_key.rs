@@ -0,0 +1,35 @@
+//! Typed per-key watch channels. Lives in its own test binary so it can call
+//! `init` without affecting other tests.
+
+#![cfg(feature = "tokio")]
+
+#[test]
+fn watch_key_follows_reloads() {
+	let path = std::env::temp_dir().join(format!("envflag-watch-{}.env", std::process::id()));
+	std::fs::write(&path, "WATCH_RATE=10\n").unwrap();
+
+	envflag::builder()
+		.path(&path)
+		.prefix("WATCH_")
+		.init()
+		.unwrap();
+
+	let mut rx = envflag::watch_key::<u64>("RATE").unwrap();
+	assert_eq!(*rx.borrow_and_update(), 10);
+
+	// Unchanged value: no notification.
+	envflag::reload().unwrap();
+	assert!(!rx.has_changed().unwrap());
+
+	std::fs::write(&path, "WATCH_RATE=25\n").unwrap();
+	envflag::reload().unwrap();
+	assert!(rx.has_changed().unwrap());
+	assert_eq!(*rx.borrow_and_update(), 25);
+
+	// Garbage keeps the last good value.
+	std::fs::write(&path, "WATCH_RATE=lots\n").unwrap();
+	envflag::reload().unwrap();
+	std::fs::remove_file(&path).unwrap();
+	assert!(!rx.has_changed().unwrap());
+	assert_eq!(*rx.borrow(), 25);
+}